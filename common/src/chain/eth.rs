@@ -0,0 +1,228 @@
+use crate::chain::EthChain;
+use crate::error::BridgeError;
+use crate::eth::signer::{EthSigner, RemoteSigner};
+use crate::eth::{EthTransport, FullBlock};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::time::Duration;
+use web3::contract::{Contract, Options};
+use web3::ethabi::Token;
+use web3::helpers as w3h;
+use web3::types::{
+    Address, BlockId, BlockNumber, Bytes, CallRequest, TransactionRequest, H256, U256, U64,
+};
+use web3::{Transport, Web3};
+
+pub(crate) const ETH_CALL_GAS_LIMIT: u128 = 1000000;
+const ETH_TRANSACTION_TYPE: u64 = 2;
+const ETH_CONFIRMATION_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+fn get_options() -> Options {
+    Options {
+        transaction_type: Some(U64::from(ETH_TRANSACTION_TYPE)),
+        gas: Some(U256::from(ETH_CALL_GAS_LIMIT)),
+        ..Default::default()
+    }
+}
+
+// Errors returned by the node for executed calls mean that the contract reverted, other errors are
+// transport failures
+fn call_error(e: web3::Error, message: String) -> BridgeError {
+    match e {
+        web3::Error::Rpc(e) => BridgeError::ContractRevert(format!("{message}: {e}")),
+        web3::Error::Decoder(e) => BridgeError::Decode(format!("{message}: {e}")),
+        e => BridgeError::Transport(format!("{message}: {e}")),
+    }
+}
+
+fn load_abi(abi: &str) -> anyhow::Result<web3::ethabi::Contract> {
+    web3::ethabi::Contract::load(abi.as_bytes())
+        .map_err(|e| BridgeError::Config(format!("Failed to load ETH contract abi: {e}")).into())
+}
+
+// Build the same transaction as local signing does (nonce and fees of the latest state) and sign
+// it with the remote signer
+async fn sign_remotely(
+    web3: &Web3<EthTransport>,
+    contract: &Contract<EthTransport>,
+    function: &str,
+    params: &[Token],
+    signer: &RemoteSigner,
+) -> anyhow::Result<Bytes> {
+    let data = contract
+        .abi()
+        .function(function)
+        .and_then(|function_abi| function_abi.encode_input(params))
+        .map_err(|e| {
+            BridgeError::Decode(format!(
+                "Failed to encode ETH function {function} call: {e}"
+            ))
+        })?;
+    let from = signer.address();
+    let (nonce, gas_price) = futures::try_join!(
+        web3.eth().transaction_count(from, None),
+        web3.eth().gas_price()
+    )
+    .map_err(|e| {
+        BridgeError::Transport(format!("Failed to prepare ETH transaction {function}: {e}"))
+    })?;
+    let options = get_options();
+    let tx = TransactionRequest {
+        from,
+        to: Some(contract.address()),
+        gas: options.gas,
+        data: Some(data.into()),
+        nonce: Some(nonce),
+        transaction_type: options.transaction_type,
+        max_fee_per_gas: Some(gas_price),
+        max_priority_fee_per_gas: Some(gas_price),
+        ..Default::default()
+    };
+    signer.sign_transaction(&tx).await
+}
+
+#[async_trait]
+impl EthChain for Web3<EthTransport> {
+    async fn read_block(&self, block_id: BlockId) -> anyhow::Result<FullBlock<H256>> {
+        tracing::info!("Reading block: {block_id:?}");
+        let include_txs = w3h::serialize(&false);
+        let block = match block_id {
+            BlockId::Hash(hash) => {
+                let hash = w3h::serialize(&hash);
+                self.transport()
+                    .execute("eth_getBlockByHash", vec![hash, include_txs])
+            }
+            BlockId::Number(num) => {
+                let num = w3h::serialize(&num);
+                self.transport()
+                    .execute("eth_getBlockByNumber", vec![num, include_txs])
+            }
+        }
+        .await
+        .map_err(|e| {
+            BridgeError::Transport(format!("Failed to query ETH block {block_id:?}: {e}"))
+        })?;
+
+        // Node returns null for unknown blocks
+        if block.is_null() {
+            return Err(BridgeError::NotFound(format!("ETH block {block_id:?} not found")).into());
+        }
+        serde_json::from_value(block)
+            .map_err(|e| BridgeError::Decode(format!("Failed to serialize ETH block: {e}")).into())
+    }
+
+    async fn get_logs(&self, params: Value) -> anyhow::Result<Value> {
+        self.transport()
+            .execute("eth_getLogs", vec![params])
+            .await
+            .map_err(|e| {
+                BridgeError::Transport(format!("Failed to execute ETH getLogs request: {e}")).into()
+            })
+    }
+
+    async fn storage(
+        &self,
+        address: Address,
+        index: U256,
+        block: Option<BlockNumber>,
+    ) -> anyhow::Result<H256> {
+        self.eth()
+            .storage(address, index, block)
+            .await
+            .map_err(|e| {
+                BridgeError::Transport(format!("Failed to get ETH contract storage value: {e}"))
+                    .into()
+            })
+    }
+
+    async fn balance(&self, address: Address, block: Option<BlockNumber>) -> anyhow::Result<U256> {
+        self.eth()
+            .balance(address, block)
+            .await
+            .map_err(|e| BridgeError::Transport(format!("Failed to get ETH balance: {e}")).into())
+    }
+
+    async fn gas_price(&self) -> anyhow::Result<U256> {
+        self.eth()
+            .gas_price()
+            .await
+            .map_err(|e| BridgeError::Transport(format!("Failed to get ETH gas price: {e}")).into())
+    }
+
+    async fn query(
+        &self,
+        address: Address,
+        abi: &str,
+        function: &str,
+        params: Vec<Token>,
+    ) -> anyhow::Result<Vec<Token>> {
+        let abi = load_abi(abi)?;
+        let function_abi = abi.function(function).map_err(|e| {
+            BridgeError::Config(format!("Failed to find ETH function {function}: {e}"))
+        })?;
+        let data = function_abi.encode_input(&params).map_err(|e| {
+            BridgeError::Decode(format!("Failed to encode ETH call {function}: {e}"))
+        })?;
+        let request = CallRequest {
+            to: Some(address),
+            data: Some(data.into()),
+            ..Default::default()
+        };
+        let output = self
+            .eth()
+            .call(request, None)
+            .await
+            .map_err(|e| call_error(e, format!("Failed to call ETH getter {function}")))?;
+        function_abi.decode_output(&output.0).map_err(|e| {
+            BridgeError::Decode(format!(
+                "Failed to decode ETH getter {function} output: {e}"
+            ))
+            .into()
+        })
+    }
+
+    async fn call(
+        &self,
+        address: Address,
+        abi: &str,
+        function: &str,
+        params: Vec<Token>,
+        signer: &EthSigner,
+        confirmations: usize,
+    ) -> anyhow::Result<H256> {
+        let contract = Contract::new(self.eth(), address, load_abi(abi)?);
+        let receipt = match signer {
+            EthSigner::Local(key) => {
+                contract
+                    .signed_call_with_confirmations(
+                        function,
+                        params.as_slice(),
+                        get_options(),
+                        confirmations,
+                        key,
+                    )
+                    .await
+            }
+            EthSigner::Remote(signer) => {
+                let raw = sign_remotely(self, &contract, function, &params, signer).await?;
+                web3::confirm::send_raw_transaction_with_confirmation(
+                    self.transport().clone(),
+                    raw,
+                    ETH_CONFIRMATION_POLL_INTERVAL,
+                    confirmations,
+                )
+                .await
+            }
+        }
+        .map_err(|e| call_error(e, format!("Failed to call ETH function {function}")))?;
+        tracing::info!("ETH call result: {}", w3h::to_string(&receipt));
+        if receipt.status == Some(U64::zero()) {
+            return Err(BridgeError::ContractRevert(format!(
+                "ETH transaction {:?} calling {function} was reverted",
+                receipt.transaction_hash
+            ))
+            .into());
+        }
+        Ok(receipt.transaction_hash)
+    }
+}
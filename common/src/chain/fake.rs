@@ -0,0 +1,370 @@
+// In-memory implementations of chain traits for tests. Fakes are filled with the expected chain
+// state before the test and record all state changing calls.
+
+use crate::chain::{DecodedMessageBody, EthChain, GoshChain};
+use crate::error::BridgeError;
+use crate::eth::signer::EthSigner;
+use crate::eth::FullBlock;
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use serde_json::Value;
+use std::collections::HashMap;
+use ton_client::crypto::KeyPair;
+use web3::ethabi::Token;
+use web3::types::{Address, BlockId, BlockNumber, H256, U256, U64};
+
+// (address, storage index, block number)
+type StorageKey = (Address, U256, Option<U64>);
+// (address, function, params, output)
+type EthQuery = (Address, String, Vec<Token>, Vec<Token>);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FakeEthCall {
+    pub address: Address,
+    pub function: String,
+    pub params: Vec<Token>,
+}
+
+#[derive(Default)]
+pub struct FakeEth {
+    blocks: Mutex<Vec<FullBlock<H256>>>,
+    storage: Mutex<HashMap<StorageKey, H256>>,
+    logs: Mutex<Vec<Value>>,
+    queries: Mutex<Vec<EthQuery>>,
+    balances: Mutex<HashMap<Address, U256>>,
+    gas_price: Mutex<U256>,
+    calls: Mutex<Vec<FakeEthCall>>,
+}
+
+impl FakeEth {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_block(&self, block: FullBlock<H256>) {
+        self.blocks.lock().push(block);
+    }
+
+    // Value is returned for reads on the specified block, `None` block sets the default value
+    // for all blocks
+    pub fn set_storage(&self, address: Address, index: U256, block: Option<U64>, value: H256) {
+        self.storage.lock().insert((address, index, block), value);
+    }
+
+    // Log is returned by 'eth_getLogs' if its 'address' and 'blockNumber' match the filter
+    pub fn add_log(&self, log: Value) {
+        self.logs.lock().push(log);
+    }
+
+    pub fn set_query(
+        &self,
+        address: Address,
+        function: &str,
+        params: Vec<Token>,
+        output: Vec<Token>,
+    ) {
+        self.queries
+            .lock()
+            .push((address, function.to_string(), params, output));
+    }
+
+    pub fn set_balance(&self, address: Address, balance: U256) {
+        self.balances.lock().insert(address, balance);
+    }
+
+    pub fn set_gas_price(&self, gas_price: U256) {
+        *self.gas_price.lock() = gas_price;
+    }
+
+    pub fn calls(&self) -> Vec<FakeEthCall> {
+        self.calls.lock().clone()
+    }
+
+    fn latest_block_number(&self) -> Option<U64> {
+        self.blocks.lock().iter().filter_map(|b| b.number).max()
+    }
+
+    fn block_number(&self, block: Option<BlockNumber>) -> Option<U64> {
+        match block? {
+            BlockNumber::Number(num) => Some(num),
+            BlockNumber::Earliest => Some(U64::zero()),
+            _ => self.latest_block_number(),
+        }
+    }
+}
+
+fn log_block_matches(bound: Option<&Value>, block: U64, is_start: bool) -> bool {
+    let bound = match bound.and_then(|v| v.as_str()) {
+        Some(bound) => bound,
+        None => return true,
+    };
+    match U64::from_str_radix(bound.trim_start_matches("0x"), 16) {
+        Ok(bound) if is_start => block >= bound,
+        Ok(bound) => block <= bound,
+        // Tags like 'latest' do not limit the range
+        Err(_) => true,
+    }
+}
+
+#[async_trait]
+impl EthChain for FakeEth {
+    async fn read_block(&self, block_id: BlockId) -> anyhow::Result<FullBlock<H256>> {
+        let number = match block_id {
+            BlockId::Hash(_) => None,
+            BlockId::Number(num) => self.block_number(Some(num)),
+        };
+        self.blocks
+            .lock()
+            .iter()
+            .find(|block| match block_id {
+                BlockId::Hash(hash) => block.hash == Some(hash),
+                BlockId::Number(_) => number.is_some() && block.number == number,
+            })
+            .cloned()
+            .ok_or(BridgeError::NotFound(format!("Fake ETH block {block_id:?} not found")).into())
+    }
+
+    async fn get_logs(&self, params: Value) -> anyhow::Result<Value> {
+        let address = params["address"].as_str().map(|s| s.to_lowercase());
+        let logs = self
+            .logs
+            .lock()
+            .iter()
+            .filter(|log| {
+                let log_address = log["address"].as_str().map(|s| s.to_lowercase());
+                if address.is_some() && log_address != address {
+                    return false;
+                }
+                let block = log["blockNumber"]
+                    .as_str()
+                    .and_then(|s| U64::from_str_radix(s.trim_start_matches("0x"), 16).ok())
+                    .unwrap_or_default();
+                log_block_matches(params.get("fromBlock"), block, true)
+                    && log_block_matches(params.get("toBlock"), block, false)
+            })
+            .cloned()
+            .collect();
+        Ok(Value::Array(logs))
+    }
+
+    async fn storage(
+        &self,
+        address: Address,
+        index: U256,
+        block: Option<BlockNumber>,
+    ) -> anyhow::Result<H256> {
+        let block = self.block_number(block);
+        let storage = self.storage.lock();
+        // Unset storage slots are zero as in the real network
+        Ok(storage
+            .get(&(address, index, block))
+            .or(storage.get(&(address, index, None)))
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    async fn balance(&self, address: Address, _block: Option<BlockNumber>) -> anyhow::Result<U256> {
+        Ok(self
+            .balances
+            .lock()
+            .get(&address)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    async fn gas_price(&self) -> anyhow::Result<U256> {
+        Ok(*self.gas_price.lock())
+    }
+
+    async fn query(
+        &self,
+        address: Address,
+        _abi: &str,
+        function: &str,
+        params: Vec<Token>,
+    ) -> anyhow::Result<Vec<Token>> {
+        self.queries
+            .lock()
+            .iter()
+            .find(|(a, f, p, _)| *a == address && f == function && *p == params)
+            .map(|(_, _, _, output)| output.clone())
+            .ok_or(
+                BridgeError::NotFound(format!(
+                    "Fake ETH getter {function} is not set for {address:?}"
+                ))
+                .into(),
+            )
+    }
+
+    async fn call(
+        &self,
+        address: Address,
+        _abi: &str,
+        function: &str,
+        params: Vec<Token>,
+        _signer: &EthSigner,
+        _confirmations: usize,
+    ) -> anyhow::Result<H256> {
+        let mut calls = self.calls.lock();
+        calls.push(FakeEthCall {
+            address,
+            function: function.to_string(),
+            params,
+        });
+        Ok(H256::from_low_u64_be(calls.len() as u64))
+    }
+}
+
+// (address, function, args, result)
+type GoshGetter = (String, String, Option<Value>, Value);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FakeGoshCall {
+    pub address: String,
+    pub function: String,
+    pub args: Option<Value>,
+}
+
+#[derive(Default)]
+pub struct FakeGosh {
+    getters: Mutex<Vec<GoshGetter>>,
+    // (address, function) of getters failing in TVM
+    reverts: Mutex<Vec<(String, String)>>,
+    queries: Mutex<Vec<(String, Option<Value>, Value)>>,
+    bodies: Mutex<HashMap<String, DecodedMessageBody>>,
+    calls: Mutex<Vec<FakeGoshCall>>,
+}
+
+// Expected value matches if all its object fields are equal to the actual ones
+fn value_matches(expected: &Value, actual: &Value) -> bool {
+    match expected {
+        Value::Object(fields) => fields
+            .iter()
+            .all(|(key, value)| value_matches(value, &actual[key])),
+        _ => expected == actual,
+    }
+}
+
+impl FakeGosh {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // `None` args match getter call with any arguments
+    pub fn set_getter(&self, address: &str, function: &str, args: Option<Value>, result: Value) {
+        self.getters
+            .lock()
+            .push((address.to_string(), function.to_string(), args, result));
+    }
+
+    // Getter fails as if the account exists, but its code can't run it
+    pub fn set_getter_revert(&self, address: &str, function: &str) {
+        self.reverts
+            .lock()
+            .push((address.to_string(), function.to_string()));
+    }
+
+    // Result is returned for queries containing `query_part` whose variables include all fields
+    // of `variables`. The first matching result is used.
+    pub fn set_query(&self, query_part: &str, variables: Option<Value>, result: Value) {
+        self.queries
+            .lock()
+            .push((query_part.to_string(), variables, result));
+    }
+
+    pub fn set_message_body(&self, body: &str, decoded: DecodedMessageBody) {
+        self.bodies.lock().insert(body.to_string(), decoded);
+    }
+
+    pub fn calls(&self) -> Vec<FakeGoshCall> {
+        self.calls.lock().clone()
+    }
+}
+
+#[async_trait]
+impl GoshChain for FakeGosh {
+    async fn run_getter(
+        &self,
+        address: &str,
+        _abi: &str,
+        function: &str,
+        args: Option<Value>,
+    ) -> anyhow::Result<Value> {
+        if self
+            .reverts
+            .lock()
+            .iter()
+            .any(|(a, f)| a == address && f == function)
+        {
+            return Err(BridgeError::ContractRevert(format!(
+                "run_local failed: getter {function} of {address} failed"
+            ))
+            .into());
+        }
+        let actual = args.unwrap_or(Value::Null);
+        self.getters
+            .lock()
+            .iter()
+            .find(|(a, f, expected, _)| {
+                a == address
+                    && f == function
+                    && expected
+                        .as_ref()
+                        .map_or(true, |e| value_matches(e, &actual))
+            })
+            .map(|(_, _, _, result)| result.clone())
+            .ok_or(
+                BridgeError::NotFound(format!(
+                    "account with address {} not found. Was trying to call {}",
+                    address, function,
+                ))
+                .into(),
+            )
+    }
+
+    async fn call_function(
+        &self,
+        address: &str,
+        _abi: &str,
+        _keys: Option<KeyPair>,
+        function: &str,
+        args: Option<Value>,
+    ) -> anyhow::Result<String> {
+        let mut calls = self.calls.lock();
+        calls.push(FakeGoshCall {
+            address: address.to_string(),
+            function: function.to_string(),
+            args,
+        });
+        Ok(format!("{:064x}", calls.len()))
+    }
+
+    async fn query(&self, query: &str, variables: Option<Value>) -> anyhow::Result<Value> {
+        let actual = variables.unwrap_or(Value::Null);
+        self.queries
+            .lock()
+            .iter()
+            .find(|(part, expected, _)| {
+                query.contains(part.as_str())
+                    && expected
+                        .as_ref()
+                        .map_or(true, |e| value_matches(e, &actual))
+            })
+            .map(|(_, _, result)| result.clone())
+            .ok_or(anyhow::format_err!(
+                "Fake GOSH query result is not set: {query}"
+            ))
+    }
+
+    async fn decode_message_body(
+        &self,
+        _abi: &str,
+        body: &str,
+    ) -> anyhow::Result<DecodedMessageBody> {
+        self.bodies
+            .lock()
+            .get(body)
+            .cloned()
+            .ok_or(anyhow::format_err!("Failed to decode message body: {body}"))
+    }
+}
@@ -0,0 +1,206 @@
+use crate::chain::{DecodedMessageBody, GoshChain};
+use crate::error::BridgeError;
+use crate::gosh::helper::{default_callback, CallResult, EverClient};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::sync::Arc;
+use ton_client::abi::{
+    decode_message_body, encode_message, Abi, CallSet, ParamsOfDecodeMessageBody,
+    ParamsOfEncodeMessage, Signer,
+};
+use ton_client::crypto::KeyPair;
+use ton_client::error::ClientError;
+use ton_client::net::{query_collection, ParamsOfQuery, ParamsOfQueryCollection};
+use ton_client::processing::{ParamsOfProcessMessage, ResultOfProcessMessage};
+use ton_client::tvm::{run_tvm, ParamsOfRunTvm};
+
+// Codes of TVM module errors, they are returned when the contract fails to execute the message
+const TVM_ERROR_CODES: std::ops::Range<u32> = 400..500;
+
+fn process_message_error(e: ClientError) -> BridgeError {
+    if TVM_ERROR_CODES.contains(&e.code) {
+        BridgeError::ContractRevert(format!("Failed to process message: {e}"))
+    } else {
+        BridgeError::Transport(format!("Failed to process message: {e}"))
+    }
+}
+
+#[async_trait]
+impl GoshChain for EverClient {
+    async fn run_getter(
+        &self,
+        address: &str,
+        abi: &str,
+        function: &str,
+        args: Option<Value>,
+    ) -> anyhow::Result<Value> {
+        tracing::info!("call_getter: address={address}, function_name={function}, args={args:?}");
+        let filter = Some(serde_json::json!({
+            "id": { "eq": address }
+        }));
+        let query = query_collection(
+            Arc::clone(self),
+            ParamsOfQueryCollection {
+                collection: "accounts".to_owned(),
+                filter,
+                result: "boc".to_owned(),
+                limit: Some(1),
+                order: None,
+            },
+        )
+        .await
+        .map(|r| r.result)
+        .map_err(|e| BridgeError::Transport(format!("Failed to query account state: {e}")))?;
+
+        if query.is_empty() {
+            return Err(BridgeError::NotFound(format!(
+                "account with address {} not found. Was trying to call {}",
+                address, function,
+            ))
+            .into());
+        }
+        let account_boc = &query[0]["boc"].as_str();
+        if account_boc.is_none() {
+            return Err(BridgeError::NotFound(format!(
+                "account with address {} does not contain boc",
+                address,
+            ))
+            .into());
+        }
+        let call_set = match args {
+            Some(value) => CallSet::some_with_function_and_input(function, value),
+            None => CallSet::some_with_function(function),
+        };
+
+        let abi = Abi::Json(abi.to_string());
+
+        let encoded = encode_message(
+            Arc::clone(self),
+            ParamsOfEncodeMessage {
+                abi: abi.clone(),
+                address: Some(address.to_string()),
+                call_set,
+                signer: Signer::None,
+                deploy_set: None,
+                processing_try_index: None,
+                signature_id: None,
+            },
+        )
+        .await
+        .map_err(|e| BridgeError::Decode(format!("Failed to encode message: {e}")))?;
+
+        let result = run_tvm(
+            Arc::clone(self),
+            ParamsOfRunTvm {
+                message: encoded.message,
+                account: account_boc.unwrap().to_string(),
+                abi: Some(abi.clone()),
+                boc_cache: None,
+                execution_options: None,
+                return_updated_account: None,
+            },
+        )
+        .await
+        .map(|r| r.decoded.unwrap())
+        .map(|r| r.output.unwrap())
+        .map_err(|e| BridgeError::ContractRevert(format!("run_local failed: {e}")))?;
+
+        tracing::info!("Call getter result: {result:?}");
+        Ok(result)
+    }
+
+    async fn call_function(
+        &self,
+        address: &str,
+        abi: &str,
+        keys: Option<KeyPair>,
+        function: &str,
+        args: Option<Value>,
+    ) -> anyhow::Result<String> {
+        tracing::info!("call_function: address={address}, function_name={function}");
+        tracing::trace!("call args={args:?}");
+
+        let call_set = match args {
+            Some(value) => CallSet::some_with_function_and_input(function, value),
+            None => CallSet::some_with_function(function),
+        };
+
+        let signer = match keys {
+            Some(key_pair) => Signer::Keys { keys: key_pair },
+            None => Signer::None,
+        };
+
+        let abi = Abi::Json(abi.to_string());
+
+        let message_encode_params = ParamsOfEncodeMessage {
+            abi: abi.clone(),
+            address: Some(address.to_string()),
+            call_set,
+            signer,
+            deploy_set: None,
+            processing_try_index: None,
+            signature_id: None,
+        };
+
+        let sdk_result = ton_client::processing::process_message(
+            Arc::clone(self),
+            ParamsOfProcessMessage {
+                send_events: true,
+                message_encode_params,
+            },
+            default_callback,
+        )
+        .await;
+        if let Err(ref e) = sdk_result {
+            tracing::error!("process_message error: {:#?}", e);
+        }
+        let ResultOfProcessMessage {
+            transaction, /* decoded, */
+            ..
+        } = sdk_result.map_err(process_message_error)?;
+        let call_result: CallResult = serde_json::from_value(transaction)
+            .map_err(|e| BridgeError::Decode(format!("Failed to decode transaction: {e}")))?;
+        tracing::info!("trx id: {}", call_result.trx_id);
+        match call_result.status {
+            3 => Ok(call_result.trx_id),
+            code => Err(
+                BridgeError::ContractRevert(format!("Call ended with error code: {code}")).into(),
+            ),
+        }
+    }
+
+    async fn query(&self, query: &str, variables: Option<Value>) -> anyhow::Result<Value> {
+        ton_client::net::query(
+            Arc::clone(self),
+            ParamsOfQuery {
+                query: query.to_string(),
+                variables,
+            },
+        )
+        .await
+        .map(|r| r.result)
+        .map_err(|e| BridgeError::Transport(format!("Failed to query data: {e}")).into())
+    }
+
+    async fn decode_message_body(
+        &self,
+        abi: &str,
+        body: &str,
+    ) -> anyhow::Result<DecodedMessageBody> {
+        let decode_params = ParamsOfDecodeMessageBody {
+            abi: Abi::Json(abi.to_string()),
+            body: body.to_string(),
+            is_internal: true,
+            allow_partial: false,
+            function_name: None,
+            data_layout: None,
+        };
+        decode_message_body(Arc::clone(self), decode_params)
+            .await
+            .map(|decoded| DecodedMessageBody {
+                name: decoded.name,
+                value: decoded.value,
+            })
+            .map_err(|e| BridgeError::Decode(format!("Failed to decode message body: {e}")).into())
+    }
+}
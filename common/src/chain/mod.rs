@@ -0,0 +1,100 @@
+use crate::eth::signer::EthSigner;
+use crate::eth::FullBlock;
+use async_trait::async_trait;
+use serde_json::Value;
+use ton_client::crypto::KeyPair;
+use web3::ethabi::Token;
+use web3::types::{Address, BlockId, BlockNumber, H256, U256};
+
+mod eth;
+pub mod fake;
+mod gosh;
+
+pub(crate) use eth::ETH_CALL_GAS_LIMIT;
+
+// Operations on ETH network used by the bridge. Implemented for `Web3<EthTransport>`, tests can
+// use `fake::FakeEth` instead.
+#[async_trait]
+pub trait EthChain: Send + Sync {
+    // Read block with hashes of transactions
+    async fn read_block(&self, block_id: BlockId) -> anyhow::Result<FullBlock<H256>>;
+
+    // Query raw logs with 'eth_getLogs' filter params
+    async fn get_logs(&self, params: Value) -> anyhow::Result<Value>;
+
+    // Read contract storage slot, `None` block means the latest one
+    async fn storage(
+        &self,
+        address: Address,
+        index: U256,
+        block: Option<BlockNumber>,
+    ) -> anyhow::Result<H256>;
+
+    // Read account balance, `None` block means the latest one
+    async fn balance(&self, address: Address, block: Option<BlockNumber>) -> anyhow::Result<U256>;
+
+    // Read current gas price
+    async fn gas_price(&self) -> anyhow::Result<U256>;
+
+    // Call contract getter and return decoded output tokens
+    async fn query(
+        &self,
+        address: Address,
+        abi: &str,
+        function: &str,
+        params: Vec<Token>,
+    ) -> anyhow::Result<Vec<Token>>;
+
+    // Send transaction calling contract function signed by `signer`, wait for confirmations and
+    // return the transaction hash
+    async fn call(
+        &self,
+        address: Address,
+        abi: &str,
+        function: &str,
+        params: Vec<Token>,
+        signer: &EthSigner,
+        confirmations: usize,
+    ) -> anyhow::Result<H256>;
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedMessageBody {
+    pub name: String,
+    pub value: Option<Value>,
+}
+
+// Operations on GOSH network used by the bridge. Implemented for `EverClient`, tests can use
+// `fake::FakeGosh` instead.
+#[async_trait]
+pub trait GoshChain: Send + Sync {
+    // Run contract getter locally and return its decoded output
+    async fn run_getter(
+        &self,
+        address: &str,
+        abi: &str,
+        function: &str,
+        args: Option<Value>,
+    ) -> anyhow::Result<Value>;
+
+    // Send external message calling contract function, wait for its processing and return id of
+    // the transaction
+    async fn call_function(
+        &self,
+        address: &str,
+        abi: &str,
+        keys: Option<KeyPair>,
+        function: &str,
+        args: Option<Value>,
+    ) -> anyhow::Result<String>;
+
+    // Execute GraphQL query and return the raw result
+    async fn query(&self, query: &str, variables: Option<Value>) -> anyhow::Result<Value>;
+
+    // Decode body of an internal message with the specified abi
+    async fn decode_message_body(
+        &self,
+        abi: &str,
+        body: &str,
+    ) -> anyhow::Result<DecodedMessageBody>;
+}
@@ -1,9 +1,9 @@
+use crate::chain::GoshChain;
+use crate::elock::transfer::TransferPatch;
 use crate::gosh::call_getter;
-use crate::gosh::helper::EverClient;
-use crate::helper::abi::CHECKER_ABI;
+use crate::helper::abi::{CHECKER_ABI, PROPOSAL_ABI};
 use crate::helper::deserialize_uint;
 use serde::Deserialize;
-use std::env;
 use std::str::FromStr;
 use web3::types::H256;
 
@@ -18,7 +18,7 @@ struct Status {
 }
 
 pub async fn get_block_from_checker(
-    client: &EverClient,
+    client: &dyn GoshChain,
     checker_address: &str,
 ) -> anyhow::Result<H256> {
     tracing::info!("get last ETH block from checker {checker_address}");
@@ -30,18 +30,86 @@ pub async fn get_block_from_checker(
         .map_err(|e| anyhow::format_err!("Failed to convert prev hash: {e}"))
 }
 
-pub fn get_checker_address() -> anyhow::Result<String> {
-    let address = env::var("CHECKER_ADDRESS")
-        .map_err(|e| anyhow::format_err!("Failed to get env CHECKER_ADDRESS: {e}"))?;
-    tracing::info!("Checker address: {address}");
-    Ok(address)
-}
-
-pub async fn get_receiver_address(client: &EverClient) -> anyhow::Result<String> {
-    let checker_address = get_checker_address()?;
+pub async fn get_receiver_address(
+    client: &dyn GoshChain,
+    checker_address: &str,
+) -> anyhow::Result<String> {
     tracing::info!("get receiver address from checker {checker_address}");
-    let status: Status = call_getter(client, &checker_address, CHECKER_ABI, "getStatus", None)
+    let status: Status = call_getter(client, checker_address, CHECKER_ABI, "getStatus", None)
         .await
         .map_err(|e| anyhow::format_err!("Failed to read status of checker: {e}"))?;
     Ok(status.receiver)
 }
+
+#[derive(Deserialize)]
+struct AllProposals {
+    #[serde(rename = "value0")]
+    addresses: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ProposalDetails {
+    pub hash: String,
+    #[serde(rename = "newhash")]
+    pub new_hash: String,
+    pub transactions: Vec<TransferPatch>,
+    #[serde(deserialize_with = "deserialize_uint")]
+    pub index: u128,
+    #[serde(deserialize_with = "deserialize_uint")]
+    pub need: u128,
+}
+
+#[derive(Debug)]
+pub struct Proposal {
+    pub address: String,
+    pub details: ProposalDetails,
+}
+
+pub async fn find_proposals(
+    context: &dyn GoshChain,
+    checker_address: &str,
+) -> anyhow::Result<Vec<Proposal>> {
+    // Call checker to get all proposals
+    let proposal_addresses: AllProposals = call_getter(
+        context,
+        checker_address,
+        CHECKER_ABI,
+        "getAllProposalAddr",
+        None,
+    )
+    .await
+    .map_err(|e| anyhow::format_err!("Failed to call getAllProposalAddr: {e}"))?;
+
+    tracing::info!(
+        "There are {} proposals in the checker contract.",
+        proposal_addresses.addresses.len()
+    );
+
+    // Get details of proposals
+    let mut res = vec![];
+    for proposal_address in proposal_addresses.addresses {
+        match call_getter::<ProposalDetails>(
+            context,
+            &proposal_address,
+            PROPOSAL_ABI,
+            "getDetails",
+            None,
+        )
+        .await
+        {
+            Ok(proposal_details) => {
+                res.push(Proposal {
+                    address: proposal_address,
+                    details: proposal_details,
+                });
+            }
+            Err(e) => {
+                tracing::info!(
+                    "Failed to get details of proposal {}: {e}",
+                    proposal_address
+                );
+            }
+        }
+    }
+    Ok(res)
+}
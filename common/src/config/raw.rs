@@ -0,0 +1,302 @@
+use super::{AlertRuleConfig, TelemetrySinkConfig};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+// Raw config values before validation. Every value is optional here, because the final config
+// is merged from several layers: profile, config file and env variables.
+
+#[derive(Deserialize, Default, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct RawEthConfig {
+    pub network: Option<String>,
+    pub extra_networks: Option<Vec<String>>,
+    pub quorum: Option<usize>,
+    pub elock_address: Option<String>,
+    pub confirmations: Option<usize>,
+    pub private_key_path: Option<PathBuf>,
+    pub keystore_password_path: Option<PathBuf>,
+    pub signer_url: Option<String>,
+    pub signer_address: Option<String>,
+    pub validator_address: Option<String>,
+}
+
+#[derive(Deserialize, Default, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct RawGoshConfig {
+    pub endpoints: Option<Vec<String>>,
+    pub checker_address: Option<String>,
+    pub validators_key_path: Option<PathBuf>,
+    pub validators_key_password_path: Option<PathBuf>,
+    pub validator_config_path: Option<PathBuf>,
+    pub message_processing_timeout_sec: Option<u64>,
+    pub wait_for_timeout_sec: Option<u64>,
+    pub query_timeout_sec: Option<u64>,
+}
+
+#[derive(Deserialize, Default, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct RawProposerConfig {
+    pub max_block_in_one_chunk: Option<u64>,
+    pub interval_sec: Option<u64>,
+    pub fetch_concurrency: Option<usize>,
+    pub finality: Option<String>,
+    pub leader_window_blocks: Option<u64>,
+}
+
+#[derive(Deserialize, Default, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct RawStoreConfig {
+    pub path: Option<PathBuf>,
+}
+
+#[derive(Deserialize, Default, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct RawTelemetryConfig {
+    pub listen: Option<String>,
+    pub interval_sec: Option<u64>,
+    pub sinks: Option<Vec<TelemetrySinkConfig>>,
+    pub sink_buffer_size: Option<usize>,
+    pub sink_retries: Option<u32>,
+    pub alerts: Option<Vec<AlertRuleConfig>>,
+    pub alert_webhook: Option<String>,
+}
+
+#[derive(Deserialize, Default, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct RawRetryConfig {
+    pub attempts: Option<u32>,
+    pub initial_backoff_ms: Option<u64>,
+    pub max_backoff_ms: Option<u64>,
+    pub jitter: Option<bool>,
+    pub read_deadline_sec: Option<u64>,
+    pub call_deadline_sec: Option<u64>,
+}
+
+#[derive(Deserialize, Default, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct RawLayer {
+    #[serde(default)]
+    pub eth: RawEthConfig,
+    #[serde(default)]
+    pub gosh: RawGoshConfig,
+    #[serde(default)]
+    pub proposer: RawProposerConfig,
+    #[serde(default)]
+    pub store: RawStoreConfig,
+    #[serde(default)]
+    pub telemetry: RawTelemetryConfig,
+    #[serde(default)]
+    pub retry: RawRetryConfig,
+}
+
+#[derive(Deserialize, Default, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct RawFile {
+    pub profile: Option<String>,
+    #[serde(default)]
+    pub profiles: HashMap<String, RawLayer>,
+    #[serde(default)]
+    pub eth: RawEthConfig,
+    #[serde(default)]
+    pub gosh: RawGoshConfig,
+    #[serde(default)]
+    pub proposer: RawProposerConfig,
+    #[serde(default)]
+    pub store: RawStoreConfig,
+    #[serde(default)]
+    pub telemetry: RawTelemetryConfig,
+    #[serde(default)]
+    pub retry: RawRetryConfig,
+}
+
+impl RawFile {
+    pub fn into_parts(self) -> (Option<String>, HashMap<String, RawLayer>, RawLayer) {
+        let layer = RawLayer {
+            eth: self.eth,
+            gosh: self.gosh,
+            proposer: self.proposer,
+            store: self.store,
+            telemetry: self.telemetry,
+            retry: self.retry,
+        };
+        (self.profile, self.profiles, layer)
+    }
+}
+
+impl RawLayer {
+    // Values of `other` take precedence over values of `self`
+    pub fn merge(self, other: RawLayer) -> RawLayer {
+        RawLayer {
+            eth: RawEthConfig {
+                network: other.eth.network.or(self.eth.network),
+                extra_networks: other.eth.extra_networks.or(self.eth.extra_networks),
+                quorum: other.eth.quorum.or(self.eth.quorum),
+                elock_address: other.eth.elock_address.or(self.eth.elock_address),
+                confirmations: other.eth.confirmations.or(self.eth.confirmations),
+                private_key_path: other.eth.private_key_path.or(self.eth.private_key_path),
+                keystore_password_path: other
+                    .eth
+                    .keystore_password_path
+                    .or(self.eth.keystore_password_path),
+                signer_url: other.eth.signer_url.or(self.eth.signer_url),
+                signer_address: other.eth.signer_address.or(self.eth.signer_address),
+                validator_address: other.eth.validator_address.or(self.eth.validator_address),
+            },
+            gosh: RawGoshConfig {
+                endpoints: other.gosh.endpoints.or(self.gosh.endpoints),
+                checker_address: other.gosh.checker_address.or(self.gosh.checker_address),
+                validators_key_path: other
+                    .gosh
+                    .validators_key_path
+                    .or(self.gosh.validators_key_path),
+                validators_key_password_path: other
+                    .gosh
+                    .validators_key_password_path
+                    .or(self.gosh.validators_key_password_path),
+                validator_config_path: other
+                    .gosh
+                    .validator_config_path
+                    .or(self.gosh.validator_config_path),
+                message_processing_timeout_sec: other
+                    .gosh
+                    .message_processing_timeout_sec
+                    .or(self.gosh.message_processing_timeout_sec),
+                wait_for_timeout_sec: other
+                    .gosh
+                    .wait_for_timeout_sec
+                    .or(self.gosh.wait_for_timeout_sec),
+                query_timeout_sec: other.gosh.query_timeout_sec.or(self.gosh.query_timeout_sec),
+            },
+            proposer: RawProposerConfig {
+                max_block_in_one_chunk: other
+                    .proposer
+                    .max_block_in_one_chunk
+                    .or(self.proposer.max_block_in_one_chunk),
+                interval_sec: other.proposer.interval_sec.or(self.proposer.interval_sec),
+                fetch_concurrency: other
+                    .proposer
+                    .fetch_concurrency
+                    .or(self.proposer.fetch_concurrency),
+                finality: other.proposer.finality.or(self.proposer.finality),
+                leader_window_blocks: other
+                    .proposer
+                    .leader_window_blocks
+                    .or(self.proposer.leader_window_blocks),
+            },
+            store: RawStoreConfig {
+                path: other.store.path.or(self.store.path),
+            },
+            telemetry: RawTelemetryConfig {
+                listen: other.telemetry.listen.or(self.telemetry.listen),
+                interval_sec: other.telemetry.interval_sec.or(self.telemetry.interval_sec),
+                sinks: other.telemetry.sinks.or(self.telemetry.sinks),
+                sink_buffer_size: other
+                    .telemetry
+                    .sink_buffer_size
+                    .or(self.telemetry.sink_buffer_size),
+                sink_retries: other.telemetry.sink_retries.or(self.telemetry.sink_retries),
+                alerts: other.telemetry.alerts.or(self.telemetry.alerts),
+                alert_webhook: other
+                    .telemetry
+                    .alert_webhook
+                    .or(self.telemetry.alert_webhook),
+            },
+            retry: RawRetryConfig {
+                attempts: other.retry.attempts.or(self.retry.attempts),
+                initial_backoff_ms: other
+                    .retry
+                    .initial_backoff_ms
+                    .or(self.retry.initial_backoff_ms),
+                max_backoff_ms: other.retry.max_backoff_ms.or(self.retry.max_backoff_ms),
+                jitter: other.retry.jitter.or(self.retry.jitter),
+                read_deadline_sec: other
+                    .retry
+                    .read_deadline_sec
+                    .or(self.retry.read_deadline_sec),
+                call_deadline_sec: other
+                    .retry
+                    .call_deadline_sec
+                    .or(self.retry.call_deadline_sec),
+            },
+        }
+    }
+
+    // Build layer from env variables. Names of the variables are kept the same as they were
+    // before the config file was introduced, so existing '.env' files still work.
+    pub fn from_env<F>(env: F) -> anyhow::Result<RawLayer>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let string = |name: &str| env(name).filter(|s| !s.is_empty());
+        let path = |name: &str| string(name).map(PathBuf::from);
+        let number = |name: &str| -> anyhow::Result<Option<u64>> {
+            string(name)
+                .map(|s| {
+                    u64::from_str(&s)
+                        .map_err(|e| anyhow::format_err!("Failed to parse env {name}={s}: {e}"))
+                })
+                .transpose()
+        };
+        Ok(RawLayer {
+            eth: RawEthConfig {
+                network: string("ETH_NETWORK"),
+                extra_networks: string("ETH_EXTRA_NETWORKS").map(|s| {
+                    s.split(',')
+                        .map(|e| e.trim().to_string())
+                        .collect::<Vec<String>>()
+                }),
+                quorum: number("ETH_QUORUM")?.map(|v| v as usize),
+                elock_address: string("ETH_CONTRACT_ADDRESS"),
+                confirmations: number("ETH_CONFIRMATIONS_CNT")?.map(|v| v as usize),
+                private_key_path: path("ETH_PRIVATE_KEY_PATH"),
+                keystore_password_path: path("ETH_KEYSTORE_PASSWORD_PATH"),
+                signer_url: string("ETH_SIGNER_URL"),
+                signer_address: string("ETH_SIGNER_ADDRESS"),
+                validator_address: string("ETH_VALIDATOR_CONTRACT_ADDRESS"),
+            },
+            gosh: RawGoshConfig {
+                endpoints: string("GOSH_ENDPOINTS").map(|s| {
+                    s.split(',')
+                        .map(|e| e.trim().to_string())
+                        .collect::<Vec<String>>()
+                }),
+                checker_address: string("CHECKER_ADDRESS"),
+                validators_key_path: path("VALIDATORS_KEY_PATH"),
+                validators_key_password_path: path("VALIDATORS_KEY_PASSWORD_PATH"),
+                validator_config_path: path("VALIDATOR_CONFIG_PATH"),
+                message_processing_timeout_sec: number("GOSH_MESSAGE_PROCESSING_TIMEOUT_SEC")?,
+                wait_for_timeout_sec: number("GOSH_WAIT_FOR_TIMEOUT_SEC")?,
+                query_timeout_sec: number("GOSH_QUERY_TIMEOUT_SEC")?,
+            },
+            proposer: RawProposerConfig {
+                max_block_in_one_chunk: number("MAX_BLOCK_IN_ONE_CHUNK")?,
+                interval_sec: number("PROPOSER_INTERVAL_SEC")?,
+                fetch_concurrency: number("PROPOSER_FETCH_CONCURRENCY")?.map(|v| v as usize),
+                finality: string("PROPOSER_FINALITY"),
+                leader_window_blocks: number("PROPOSER_LEADER_WINDOW_BLOCKS")?,
+            },
+            store: RawStoreConfig {
+                path: path("BRIDGE_STORE_PATH"),
+            },
+            telemetry: RawTelemetryConfig {
+                listen: string("TELEMETRY_LISTEN"),
+                interval_sec: number("TELEMETRY_INTERVAL_SEC")?,
+                sinks: None,
+                sink_buffer_size: number("TELEMETRY_SINK_BUFFER_SIZE")?.map(|v| v as usize),
+                sink_retries: number("TELEMETRY_SINK_RETRIES")?.map(|v| v as u32),
+                alerts: None,
+                alert_webhook: string("TELEMETRY_ALERT_WEBHOOK"),
+            },
+            retry: RawRetryConfig {
+                attempts: number("RETRY_ATTEMPTS")?.map(|v| v as u32),
+                initial_backoff_ms: number("RETRY_INITIAL_BACKOFF_MS")?,
+                max_backoff_ms: number("RETRY_MAX_BACKOFF_MS")?,
+                jitter: None,
+                read_deadline_sec: number("RETRY_READ_DEADLINE_SEC")?,
+                call_deadline_sec: number("RETRY_CALL_DEADLINE_SEC")?,
+            },
+        })
+    }
+}
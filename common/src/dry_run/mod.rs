@@ -0,0 +1,286 @@
+use crate::chain::{DecodedMessageBody, EthChain, GoshChain, ETH_CALL_GAS_LIMIT};
+use crate::error::BridgeError;
+use crate::eth::signer::EthSigner;
+use crate::eth::FullBlock;
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use ton_client::crypto::KeyPair;
+use web3::ethabi::{Contract, Token};
+use web3::types::{Address, BlockId, BlockNumber, H256, U256};
+
+// Chain wrapper for `--dry-run`. Reads and validation go to the wrapped chain as usual, but
+// transactions and external messages are printed to stdout as JSON instead of being signed and
+// sent. Params are printed with names and types from the function ABI, and a call which could
+// not be encoded fails the same way it would without dry run. Calls return zero transaction ids,
+// so flows finish as if the calls succeeded.
+pub struct DryRun<'a, C: ?Sized> {
+    inner: &'a C,
+    enabled: bool,
+}
+
+impl<'a, C: ?Sized> DryRun<'a, C> {
+    pub fn new(inner: &'a C, enabled: bool) -> Self {
+        Self { inner, enabled }
+    }
+}
+
+fn print_action(action: Value) {
+    tracing::info!("Dry run, not sent: {action}");
+    println!("{}", json!({ "dry_run": action }));
+}
+
+fn hex(bytes: &[u8]) -> String {
+    format!("0x{}", hex::encode(bytes))
+}
+
+// ETH call params in readable form: numbers in decimal, addresses and bytes in hex
+pub fn token_to_json(token: &Token) -> Value {
+    match token {
+        Token::Address(address) => json!(format!("{address:?}")),
+        Token::FixedBytes(bytes) | Token::Bytes(bytes) => json!(hex(bytes)),
+        Token::Int(value) | Token::Uint(value) => json!(value.to_string()),
+        Token::Bool(value) => json!(value),
+        Token::String(value) => json!(value),
+        Token::FixedArray(tokens) | Token::Array(tokens) | Token::Tuple(tokens) => {
+            Value::Array(tokens.iter().map(token_to_json).collect())
+        }
+    }
+}
+
+// ETH call params with names and types of the function inputs
+fn eth_params(abi: &str, function: &str, params: &[Token]) -> anyhow::Result<Value> {
+    let contract = Contract::load(abi.as_bytes())
+        .map_err(|e| BridgeError::Config(format!("Failed to load ETH contract abi: {e}")))?;
+    let function_abi = contract
+        .function(function)
+        .map_err(|e| BridgeError::Decode(format!("Failed to find ETH function {function}: {e}")))?;
+    function_abi.encode_input(params).map_err(|e| {
+        BridgeError::Decode(format!(
+            "Failed to encode ETH function {function} call: {e}"
+        ))
+    })?;
+    Ok(function_abi
+        .inputs
+        .iter()
+        .zip(params)
+        .map(|(input, token)| {
+            json!({
+                "name": input.name,
+                "type": input.kind.to_string(),
+                "value": token_to_json(token),
+            })
+        })
+        .collect())
+}
+
+// GOSH call args in the order of the function inputs with their types
+fn gosh_params(abi: &str, function: &str, args: Option<&Value>) -> anyhow::Result<Value> {
+    let abi: Value = serde_json::from_str(abi)
+        .map_err(|e| BridgeError::Config(format!("Failed to load GOSH contract abi: {e}")))?;
+    let inputs = abi["functions"]
+        .as_array()
+        .and_then(|functions| functions.iter().find(|abi| abi["name"] == function))
+        .and_then(|abi| abi["inputs"].as_array())
+        .ok_or(BridgeError::Decode(format!(
+            "Failed to find GOSH function {function}"
+        )))?;
+    let mut params = vec![];
+    for input in inputs {
+        let name = input["name"].as_str().unwrap_or_default();
+        let value = args
+            .and_then(|args| args.get(name))
+            .ok_or(BridgeError::Decode(format!(
+                "Argument {name} of GOSH function {function} is not set"
+            )))?;
+        params.push(json!({
+            "name": name,
+            "type": input["type"],
+            "value": value,
+        }));
+    }
+    Ok(Value::Array(params))
+}
+
+#[async_trait]
+impl<C: EthChain + ?Sized> EthChain for DryRun<'_, C> {
+    async fn read_block(&self, block_id: BlockId) -> anyhow::Result<FullBlock<H256>> {
+        self.inner.read_block(block_id).await
+    }
+
+    async fn get_logs(&self, params: Value) -> anyhow::Result<Value> {
+        self.inner.get_logs(params).await
+    }
+
+    async fn storage(
+        &self,
+        address: Address,
+        index: U256,
+        block: Option<BlockNumber>,
+    ) -> anyhow::Result<H256> {
+        self.inner.storage(address, index, block).await
+    }
+
+    async fn balance(&self, address: Address, block: Option<BlockNumber>) -> anyhow::Result<U256> {
+        self.inner.balance(address, block).await
+    }
+
+    async fn gas_price(&self) -> anyhow::Result<U256> {
+        self.inner.gas_price().await
+    }
+
+    async fn query(
+        &self,
+        address: Address,
+        abi: &str,
+        function: &str,
+        params: Vec<Token>,
+    ) -> anyhow::Result<Vec<Token>> {
+        self.inner.query(address, abi, function, params).await
+    }
+
+    async fn call(
+        &self,
+        address: Address,
+        abi: &str,
+        function: &str,
+        params: Vec<Token>,
+        signer: &EthSigner,
+        confirmations: usize,
+    ) -> anyhow::Result<H256> {
+        if !self.enabled {
+            return self
+                .inner
+                .call(address, abi, function, params, signer, confirmations)
+                .await;
+        }
+        // Transaction is sent with fixed gas limit, so its fee is at most limit * gas price
+        let gas_price = self.inner.gas_price().await?;
+        let gas_limit = U256::from(ETH_CALL_GAS_LIMIT);
+        print_action(json!({
+            "chain": "eth",
+            "from": format!("{:?}", signer.address()),
+            "to": format!("{address:?}"),
+            "function": function,
+            "params": eth_params(abi, function, &params)?,
+            "gas_limit": gas_limit.to_string(),
+            "gas_price": gas_price.to_string(),
+            "max_fee": gas_limit.saturating_mul(gas_price).to_string(),
+        }));
+        Ok(H256::zero())
+    }
+}
+
+#[async_trait]
+impl<C: GoshChain + ?Sized> GoshChain for DryRun<'_, C> {
+    async fn run_getter(
+        &self,
+        address: &str,
+        abi: &str,
+        function: &str,
+        args: Option<Value>,
+    ) -> anyhow::Result<Value> {
+        self.inner.run_getter(address, abi, function, args).await
+    }
+
+    async fn call_function(
+        &self,
+        address: &str,
+        abi: &str,
+        keys: Option<KeyPair>,
+        function: &str,
+        args: Option<Value>,
+    ) -> anyhow::Result<String> {
+        if !self.enabled {
+            return self
+                .inner
+                .call_function(address, abi, keys, function, args)
+                .await;
+        }
+        print_action(json!({
+            "chain": "gosh",
+            "signer": keys.map(|keys| format!("0x{}", keys.public)),
+            "to": address,
+            "function": function,
+            "params": gosh_params(abi, function, args.as_ref())?,
+        }));
+        Ok(format!("{:064x}", 0))
+    }
+
+    async fn query(&self, query: &str, variables: Option<Value>) -> anyhow::Result<Value> {
+        self.inner.query(query, variables).await
+    }
+
+    async fn decode_message_body(
+        &self,
+        abi: &str,
+        body: &str,
+    ) -> anyhow::Result<DecodedMessageBody> {
+        self.inner.decode_message_body(abi, body).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{eth_params, gosh_params, DryRun};
+    use crate::chain::fake::{FakeEth, FakeGosh};
+    use crate::chain::{EthChain, GoshChain};
+    use crate::eth::signer::EthSigner;
+    use crate::helper::abi::{ELOCK_ABI, PROPOSAL_ABI};
+    use serde_json::json;
+    use web3::ethabi::Token;
+    use web3::signing::SecretKey;
+    use web3::types::{Address, U256};
+
+    #[tokio::test]
+    async fn test_calls_are_not_sent() -> anyhow::Result<()> {
+        let eth = FakeEth::new();
+        eth.set_gas_price(U256::from(10));
+        let signer = EthSigner::Local(SecretKey::from_slice(&[1; 32])?);
+        let params = vec![Token::Uint(U256::from(1))];
+        DryRun::new(&eth, true)
+            .call(
+                Address::zero(),
+                ELOCK_ABI,
+                "voteForWithdrawal",
+                params,
+                &signer,
+                1,
+            )
+            .await?;
+        assert!(eth.calls().is_empty());
+
+        let gosh = FakeGosh::new();
+        let args = Some(json!({"id": "1"}));
+        DryRun::new(&gosh, true)
+            .call_function("0:01", PROPOSAL_ABI, None, "setVote", args.clone())
+            .await?;
+        assert!(gosh.calls().is_empty());
+        DryRun::new(&gosh, false)
+            .call_function("0:01", PROPOSAL_ABI, None, "setVote", args)
+            .await?;
+        assert_eq!(gosh.calls().len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_decoded_params() -> anyhow::Result<()> {
+        assert_eq!(
+            eth_params(
+                ELOCK_ABI,
+                "voteForWithdrawal",
+                &[Token::Uint(U256::from(7))]
+            )?,
+            json!([{"name": "proposalKey", "type": "uint256", "value": "7"}])
+        );
+        assert!(eth_params(ELOCK_ABI, "voteForWithdrawal", &[Token::Bool(true)]).is_err());
+        assert!(eth_params(ELOCK_ABI, "unknown", &[]).is_err());
+
+        assert_eq!(
+            gosh_params(PROPOSAL_ABI, "setVote", Some(&json!({"id": "1"})))?,
+            json!([{"name": "id", "type": "uint16", "value": "1"}])
+        );
+        assert!(gosh_params(PROPOSAL_ABI, "setVote", None).is_err());
+        assert!(gosh_params(PROPOSAL_ABI, "unknown", None).is_err());
+        Ok(())
+    }
+}
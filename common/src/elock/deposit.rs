@@ -1,20 +1,21 @@
+use crate::chain::EthChain;
 use crate::elock::transfer::{Transfer, TransferPatch};
+use crate::error::BridgeError;
 use crate::eth::events::get_events;
+use crate::store::Store;
 use crate::token_root::eth::get_root_data;
 use crate::token_root::RootData;
 use serde_json::json;
 use std::collections::HashMap;
 use std::str::FromStr;
-use web3::transports::WebSocket;
-use web3::types::{Address, U64};
-use web3::Web3;
+use web3::types::{Address, H256, U64};
 
 const DEPOSIT_EVENT_SIGNATURE: &str =
     "0xf5681f9d0db1b911ac18ee83d515a1cf1051853a9eae418316a2fdf7dea427c5";
 const DEPOSIT_EVENT_NAME: &str = "Deposited";
 
 pub async fn get_deposits(
-    web3s: &Web3<WebSocket>,
+    web3s: &dyn EthChain,
     elock_address: Address,
     from: U64,
     to: U64,
@@ -34,10 +35,13 @@ pub async fn get_deposits(
     let mut roots_map: HashMap<String, RootData> = HashMap::new();
     let mut transfers = vec![];
     for event in events {
-        assert_eq!(
-            &event.name, DEPOSIT_EVENT_NAME,
-            "Decoded ELock event has wrong name"
-        );
+        if event.name != DEPOSIT_EVENT_NAME {
+            return Err(BridgeError::ValidationMismatch(format!(
+                "Decoded ELock event has wrong name: {}",
+                event.name
+            ))
+            .into());
+        }
         let eth_root = event.params.get("token").ok_or(anyhow::format_err!(
             "Decoded event arguments do not contain 'token' field"
         ))?;
@@ -80,3 +84,27 @@ pub async fn get_deposits(
 
     Ok(transfers)
 }
+
+// Same as `get_deposits`, but the result is taken from the store if the same block range was
+// queried before. The range is identified by the hash of its last block, and ranges of blocks
+// which are not finalized are not cached, because they still can be reorged.
+pub async fn get_deposits_cached(
+    web3s: &dyn EthChain,
+    store: &Store,
+    elock_address: Address,
+    from: U64,
+    to: U64,
+    to_hash: H256,
+    finalized: bool,
+) -> anyhow::Result<Vec<TransferPatch>> {
+    if !finalized {
+        return get_deposits(web3s, elock_address, from, to).await;
+    }
+    if let Some(deposits) = store.deposits(from, to_hash)? {
+        tracing::info!("Use deposits from store for blocks {from} - {to}");
+        return Ok(deposits);
+    }
+    let deposits = get_deposits(web3s, elock_address, from, to).await?;
+    store.save_deposits(from, to, to_hash, &deposits)?;
+    Ok(deposits)
+}
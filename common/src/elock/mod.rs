@@ -1,9 +1,8 @@
-use std::{env, str::FromStr};
+use crate::chain::EthChain;
+use crate::helper::abi::ELOCK_ABI;
 use std::collections::HashMap;
-use web3::contract::{Contract, Options};
-use web3::transports::WebSocket;
+use web3::ethabi::Token;
 use web3::types::{Address, BlockNumber, H256, U256, U64};
-use web3::Web3;
 use crate::token_root::eth::get_root_data;
 use crate::token_root::RootData;
 
@@ -15,13 +14,12 @@ pub const COUNTERS_INDEX: u8 = 1;
 const LAST_PROCESSED_BLOCK_INDEX: u8 = 3;
 
 pub async fn get_storage(
-    web3s: &Web3<WebSocket>,
+    web3s: &dyn EthChain,
     eth_address: Address,
     block_num: U64,
     index: u8,
 ) -> anyhow::Result<H256> {
     web3s
-        .eth()
         .storage(
             eth_address,
             U256::from(index),
@@ -32,7 +30,7 @@ pub async fn get_storage(
 }
 
 pub async fn get_tx_counter(
-    web3s: &Web3<WebSocket>,
+    web3s: &dyn EthChain,
     eth_address: Address,
     block_num: U64,
 ) -> anyhow::Result<U256> {
@@ -46,24 +44,13 @@ pub async fn get_tx_counter(
     Ok(res)
 }
 
-pub fn get_elock_address() -> anyhow::Result<Address> {
-    let eth_contract_address = env::var("ETH_CONTRACT_ADDRESS")
-        .map_err(|e| anyhow::format_err!("Failed to get env ETH_CONTRACT_ADDRESS: {e}"))?
-        .to_lowercase();
-    tracing::info!("ELock address: {eth_contract_address}");
-    Address::from_str(&eth_contract_address)
-        .map_err(|e| anyhow::format_err!("Failed to convert eth address: {e}"))
-}
-
 pub async fn get_last_gosh_block_id(
     elock_address: Address,
-    web3s: &Web3<WebSocket>,
+    web3s: &dyn EthChain,
 ) -> anyhow::Result<String> {
     let last_gosh_block = web3s
-        .eth()
         .storage(elock_address, U256::from(LAST_PROCESSED_BLOCK_INDEX), None)
-        .await
-        .map_err(|e| anyhow::format_err!("Failed to get ETH contract storage value: {e}"))?;
+        .await?;
 
     let res = web3::helpers::to_string(&last_gosh_block)
         .replace('"', "")
@@ -74,35 +61,51 @@ pub async fn get_last_gosh_block_id(
 }
 
 pub async fn get_token_roots(
-    elock_contract: &Contract<WebSocket>,
+    web3s: &dyn EthChain,
+    elock_address: Address,
 ) -> anyhow::Result<Vec<Address>> {
-    elock_contract
-        .query("getTokenRoots", (), None, Options::default(), None)
+    let output = web3s
+        .query(elock_address, ELOCK_ABI, "getTokenRoots", vec![])
         .await
-        .map_err(|e| anyhow::format_err!("Failed to call ELock getter getTokenRoots: {e}"))
+        .map_err(|e| anyhow::format_err!("Failed to call ELock getter getTokenRoots: {e}"))?;
+    output
+        .into_iter()
+        .next()
+        .and_then(|token| token.into_array())
+        .ok_or(anyhow::format_err!("Wrong ELock getTokenRoots output"))?
+        .into_iter()
+        .map(|token| {
+            token
+                .into_address()
+                .ok_or(anyhow::format_err!("Wrong ELock token root address"))
+        })
+        .collect()
 }
 
 pub async fn get_total_supplies(
-    web3s: &Web3<WebSocket>,
-    elock_contract: &Contract<WebSocket>,
+    web3s: &dyn EthChain,
+    elock_address: Address,
 ) -> anyhow::Result<HashMap<RootData, u128>> {
     tracing::info!("Get ELock total supplies");
     let mut res = HashMap::new();
-    let token_roots = get_token_roots(elock_contract).await?;
+    let token_roots = get_token_roots(web3s, elock_address).await?;
 
     for root in token_roots {
         let root_data = get_root_data(
             web3s,
             root,
         ).await?;
-        let value: U256 = elock_contract.query(
+        let value = web3s.query(
+            elock_address,
+            ELOCK_ABI,
             "getTotalSupply",
-            root,
-            None,
-            Options::default(),
-            None,
+            vec![Token::Address(root)],
         ).await
-            .map_err(|e| anyhow::format_err!("Failed to call ELock getter getTotalSupply: {e}"))?;
+            .map_err(|e| anyhow::format_err!("Failed to call ELock getter getTotalSupply: {e}"))?
+            .into_iter()
+            .next()
+            .and_then(|token| token.into_uint())
+            .ok_or(anyhow::format_err!("Wrong ELock getTotalSupply output"))?;
         res.insert(root_data, value.as_u128());
     }
     tracing::info!("ELock total supplies: {res:?}");
@@ -0,0 +1,90 @@
+// Typed causes of bridge failures. Functions still return `anyhow::Result`, callers which need to
+// branch on the cause look it up with `BridgeError::find`. Wrapping an error with `format_err!`
+// drops its cause, so errors that are checked by callers should be wrapped with
+// `anyhow::Context` instead.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum BridgeError {
+    // Endpoint is unreachable or request failed, retry may succeed
+    #[error("{0}")]
+    Transport(String),
+    // Data can't be encoded or decoded with the contract abi or the expected format
+    #[error("{0}")]
+    Decode(String),
+    // Contract execution failed or transaction was aborted
+    #[error("{0}")]
+    ContractRevert(String),
+    // Data read from chain does not match the expected one
+    #[error("{0}")]
+    ValidationMismatch(String),
+    #[error("{0}")]
+    Config(String),
+    // Account, block or contract getter does not exist
+    #[error("{0}")]
+    NotFound(String),
+}
+
+impl BridgeError {
+    // Find the first typed cause in the error chain
+    pub fn find(error: &anyhow::Error) -> Option<&BridgeError> {
+        error
+            .chain()
+            .find_map(|cause| cause.downcast_ref::<BridgeError>())
+    }
+
+    pub fn is_not_found(error: &anyhow::Error) -> bool {
+        matches!(Self::find(error), Some(BridgeError::NotFound(_)))
+    }
+
+    // Only transport failures are worth retrying, other causes repeat on the same input
+    pub fn is_retryable(error: &anyhow::Error) -> bool {
+        matches!(Self::find(error), Some(BridgeError::Transport(_)))
+    }
+
+    // Same cause with another message, e.g. to report failures of several endpoints at once
+    pub fn with_message(&self, message: String) -> BridgeError {
+        match self {
+            BridgeError::Transport(_) => BridgeError::Transport(message),
+            BridgeError::Decode(_) => BridgeError::Decode(message),
+            BridgeError::ContractRevert(_) => BridgeError::ContractRevert(message),
+            BridgeError::ValidationMismatch(_) => BridgeError::ValidationMismatch(message),
+            BridgeError::Config(_) => BridgeError::Config(message),
+            BridgeError::NotFound(_) => BridgeError::NotFound(message),
+        }
+    }
+
+    // Short name of the cause for logs and alert labels
+    pub fn kind(&self) -> &'static str {
+        match self {
+            BridgeError::Transport(_) => "transport",
+            BridgeError::Decode(_) => "decode",
+            BridgeError::ContractRevert(_) => "contract_revert",
+            BridgeError::ValidationMismatch(_) => "validation_mismatch",
+            BridgeError::Config(_) => "config",
+            BridgeError::NotFound(_) => "not_found",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BridgeError;
+    use anyhow::Context;
+
+    #[test]
+    fn test_find_cause() {
+        let error: anyhow::Error =
+            BridgeError::NotFound("account 0:01 not found".to_string()).into();
+        let error = Err::<(), _>(error)
+            .context("Failed to read root")
+            .unwrap_err();
+        assert!(BridgeError::is_not_found(&error));
+        assert!(!BridgeError::is_retryable(&error));
+        assert_eq!(
+            BridgeError::find(&error).map(|e| e.kind()),
+            Some("not_found")
+        );
+
+        let error = anyhow::format_err!("Failed to read root: {error}");
+        assert_eq!(BridgeError::find(&error), None);
+    }
+}
@@ -1,8 +1,10 @@
+use crate::chain::EthChain;
+use crate::error::BridgeError;
+use crate::eth::encoder::serialize_block;
+use crate::store::Store;
+use futures::{stream, StreamExt, TryStreamExt};
 use serde::{Deserialize, Deserializer, Serialize};
-use web3::helpers as w3h;
-use web3::transports::WebSocket;
-use web3::types::{BlockId, Bytes, H160, H2048, H256, H64, U256, U64};
-use web3::{Transport, Web3};
+use web3::types::{BlockId, BlockNumber, Bytes, H160, H2048, H256, H64, U256, U64};
 
 #[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
 pub struct FullBlock<TX> {
@@ -71,6 +73,21 @@ pub struct FullBlock<TX> {
     /// Base fee per unit of gas (if past London)
     #[serde(rename = "withdrawalsRoot", skip_serializing_if = "Option::is_none")]
     pub withdrawals_root: Option<H256>,
+    /// Blob gas used by the block transactions (if past Cancun)
+    #[serde(rename = "blobGasUsed", skip_serializing_if = "Option::is_none")]
+    pub blob_gas_used: Option<U64>,
+    /// Excess blob gas (if past Cancun)
+    #[serde(rename = "excessBlobGas", skip_serializing_if = "Option::is_none")]
+    pub excess_blob_gas: Option<U64>,
+    /// Root of the parent beacon block (if past Cancun)
+    #[serde(
+        rename = "parentBeaconBlockRoot",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub parent_beacon_block_root: Option<H256>,
+    /// Hash of the execution layer requests (if past Prague)
+    #[serde(rename = "requestsHash", skip_serializing_if = "Option::is_none")]
+    pub requests_hash: Option<H256>,
 }
 
 fn null_to_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
@@ -84,40 +101,113 @@ where
 
 // Read Ethereum block with specified block id
 pub async fn read_block(
-    web3s: &Web3<WebSocket>,
+    web3s: &dyn EthChain,
     block_id: BlockId,
 ) -> anyhow::Result<FullBlock<H256>> {
-    tracing::info!("Reading block: {block_id:?}");
-    let include_txs = w3h::serialize(&false);
-    let block = match block_id {
-        BlockId::Hash(hash) => {
-            let hash = w3h::serialize(&hash);
-            web3s
-                .transport()
-                .execute("eth_getBlockByHash", vec![hash, include_txs])
+    web3s.read_block(block_id).await
+}
+
+// Same as `read_block`, but blocks requested by hash are taken from the store if they were
+// fetched before. Fetched blocks are saved to the store.
+pub async fn read_block_cached(
+    web3s: &dyn EthChain,
+    store: &Store,
+    block_id: BlockId,
+) -> anyhow::Result<FullBlock<H256>> {
+    if let BlockId::Hash(hash) = block_id {
+        if let Some(block) = store.eth_block(hash)? {
+            tracing::info!("Use ETH block {hash:?} from store");
+            return Ok(block);
         }
-        BlockId::Number(num) => {
-            let num = w3h::serialize(&num);
-            web3s
-                .transport()
-                .execute("eth_getBlockByNumber", vec![num, include_txs])
+    }
+    let block = web3s.read_block(block_id).await?;
+    // Only verified blocks are cached, so a bad provider response is read again on the next run
+    if serialize_block(&block).is_ok() {
+        store.save_eth_block(&block)?;
+    }
+    Ok(block)
+}
+
+// Read blocks with numbers from the range (borders included) with up to `concurrency` requests
+// at the same time. Blocks are returned newest first and saved to the store if they are verified.
+pub async fn read_blocks(
+    web3s: &dyn EthChain,
+    store: &Store,
+    from: U64,
+    till: U64,
+    concurrency: usize,
+) -> anyhow::Result<Vec<FullBlock<H256>>> {
+    let blocks: Vec<FullBlock<H256>> = stream::iter((from.as_u64()..=till.as_u64()).rev())
+        .map(|number| async move {
+            let number = U64::from(number);
+            let block = web3s
+                .read_block(BlockId::Number(BlockNumber::Number(number)))
+                .await?;
+            if block.number != Some(number) {
+                return Err(anyhow::Error::from(BridgeError::ValidationMismatch(
+                    format!(
+                        "ETH block {number} was requested, but block {:?} was returned",
+                        block.number
+                    ),
+                )));
+            }
+            Ok(block)
+        })
+        .buffered(concurrency)
+        .try_collect()
+        .await?;
+    for block in &blocks {
+        if serialize_block(block).is_ok() {
+            store.save_eth_block(block)?;
         }
     }
-    .await
-    .map_err(|e| anyhow::format_err!("Failed to query ETH block {block_id:?}: {e}"))?;
+    Ok(blocks)
+}
 
-    serde_json::from_value(block)
-        .map_err(|e| anyhow::format_err!("Failed to serialize ETH block: {e}"))
+fn describe(block: &FullBlock<H256>) -> String {
+    format!(
+        "{} ({:?})",
+        block.number.unwrap_or_default(),
+        block.hash.unwrap_or_default()
+    )
+}
+
+// Check that blocks (newest first) are linked by parent hashes and the oldest one is the child of
+// the block with `prev_hash`
+pub fn check_chain(blocks: &[FullBlock<H256>], prev_hash: H256) -> anyhow::Result<()> {
+    for (block, parent) in blocks.iter().zip(blocks.iter().skip(1)) {
+        if parent.hash != Some(block.parent_hash) {
+            return Err(BridgeError::ValidationMismatch(format!(
+                "ETH block {} has parent hash {:?}, but the previous block is {}",
+                describe(block),
+                block.parent_hash,
+                describe(parent)
+            ))
+            .into());
+        }
+    }
+    if let Some(oldest) = blocks.last() {
+        if oldest.parent_hash != prev_hash {
+            return Err(BridgeError::ValidationMismatch(format!(
+                "ETH block {} has parent hash {:?}, but the last block saved in GOSH checker is \
+                {prev_hash:?}",
+                describe(oldest),
+                oldest.parent_hash
+            ))
+            .into());
+        }
+    }
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::super::encoder::serialize_block;
-    use super::read_block;
-    use std::env;
-    use web3::transports::WebSocket;
-    use web3::types::{BlockId, BlockNumber, U64};
-    use web3::Web3;
+    use super::{check_chain, read_block, FullBlock};
+    use crate::config::BridgeConfig;
+    use crate::error::BridgeError;
+    use crate::eth::create_web3;
+    use web3::types::{BlockId, BlockNumber, H256, U64};
 
     #[tokio::test]
     pub async fn test_hash() -> anyhow::Result<()> {
@@ -125,15 +215,37 @@ mod tests {
         let block_id = BlockId::Number(BlockNumber::Number(
             U64::from_str_radix("400000", 10).unwrap(),
         ));
-        let websocket = WebSocket::new(
-            &env::var("ETH_NETWORK")
-                .map_err(|e| anyhow::format_err!("Failed to get env ETH_NETWORK: {e}"))?,
-        )
-        .await
-        .map_err(|e| anyhow::format_err!("Failed to create websocket: {e}"))?;
-        let web3s = Web3::new(websocket);
+        let config = BridgeConfig::load()?;
+        let web3s = create_web3(&config.eth).await?;
         let block = read_block(&web3s, block_id).await?;
         serialize_block(&block)?;
         Ok(())
     }
+
+    fn block(number: u64, parent_hash: H256) -> FullBlock<H256> {
+        FullBlock {
+            hash: Some(H256::from_low_u64_be(number)),
+            number: Some(U64::from(number)),
+            parent_hash,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_check_chain() {
+        let prev_hash = H256::from_low_u64_be(10);
+        let blocks = vec![block(12, H256::from_low_u64_be(11)), block(11, prev_hash)];
+        assert!(check_chain(&blocks, prev_hash).is_ok());
+
+        let err = check_chain(&blocks, H256::from_low_u64_be(9)).unwrap_err();
+        assert!(matches!(
+            BridgeError::find(&err),
+            Some(BridgeError::ValidationMismatch(_))
+        ));
+        assert!(err.to_string().contains("GOSH checker"));
+
+        let blocks = vec![block(12, H256::from_low_u64_be(5)), block(11, prev_hash)];
+        let err = check_chain(&blocks, prev_hash).unwrap_err();
+        assert!(err.to_string().starts_with("ETH block 12"));
+    }
 }
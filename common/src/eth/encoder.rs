@@ -1,18 +1,87 @@
 use rlp::RlpStream;
 use sha3::{Digest, Keccak256};
-use web3::types::H256;
+use web3::types::{H256, U64};
 
 use crate::eth::block::FullBlock;
 
-pub fn serialize_block(block: &FullBlock<H256>) -> anyhow::Result<Vec<u8>> {
+// Reasons why block header can't be verified. Block with such header must not be proposed, but it
+// is not a reason to stop: the block can be read again from another provider, or the run can be
+// skipped and reported.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum HeaderError {
+    #[error("ETH block {number:?} header field {field} is missing")]
+    MissingField {
+        number: Option<U64>,
+        field: &'static str,
+    },
+    #[error(
+        "ETH block {number:?} has header field {field} without fields of the previous forks, \
+        unknown fork?"
+    )]
+    ForkFieldGap {
+        number: Option<U64>,
+        field: &'static str,
+    },
+    #[error(
+        "ETH block {number:?} hash mismatch: reported {reported:?}, computed {computed:?} from \
+        {fields} RLP fields"
+    )]
+    HashMismatch {
+        number: Option<U64>,
+        reported: H256,
+        computed: H256,
+        fields: usize,
+    },
+}
+
+fn required<T: Copy>(
+    block: &FullBlock<H256>,
+    value: Option<T>,
+    field: &'static str,
+) -> Result<T, HeaderError> {
+    value.ok_or(HeaderError::MissingField {
+        number: block.number,
+        field,
+    })
+}
+
+// RLP encode block header and check that its hash matches the reported one
+pub fn serialize_block(block: &FullBlock<H256>) -> Result<Vec<u8>, HeaderError> {
     tracing::trace!("serialize block: {:?}", block);
-    let list_len = match block.base_fee_per_gas {
-        Some(_) => match block.withdrawals_root {
-            Some(_) => 17,
-            None => 16,
-        },
-        None => 15,
-    };
+    let reported = required(block, block.hash, "hash")?;
+    let logs_bloom = required(block, block.logs_bloom, "logsBloom")?;
+    let number = required(block, block.number, "number")?;
+    let mix_hash = required(block, block.mix_hash, "mixHash")?;
+    let nonce = required(block, block.nonce, "nonce")?;
+
+    // Every fork appends fields to the header of the previous one, so the header has all fork
+    // fields up to the last present one:
+    //   London   - baseFeePerGas
+    //   Shanghai - withdrawalsRoot
+    //   Cancun   - blobGasUsed, excessBlobGas, parentBeaconBlockRoot
+    //   Prague   - requestsHash
+    let fork_fields = [
+        ("baseFeePerGas", block.base_fee_per_gas.is_some()),
+        ("withdrawalsRoot", block.withdrawals_root.is_some()),
+        ("blobGasUsed", block.blob_gas_used.is_some()),
+        ("excessBlobGas", block.excess_blob_gas.is_some()),
+        (
+            "parentBeaconBlockRoot",
+            block.parent_beacon_block_root.is_some(),
+        ),
+        ("requestsHash", block.requests_hash.is_some()),
+    ];
+    let present = fork_fields
+        .iter()
+        .take_while(|(_, present)| *present)
+        .count();
+    if let Some((field, _)) = fork_fields[present..].iter().find(|(_, present)| *present) {
+        return Err(HeaderError::ForkFieldGap {
+            number: block.number,
+            field,
+        });
+    }
+    let list_len = 15 + present;
     let mut rlp_stream = RlpStream::new_list(list_len);
     rlp_stream.append(&block.parent_hash);
     rlp_stream.append(&block.uncles_hash);
@@ -20,20 +89,32 @@ pub fn serialize_block(block: &FullBlock<H256>) -> anyhow::Result<Vec<u8>> {
     rlp_stream.append(&block.state_root);
     rlp_stream.append(&block.transactions_root);
     rlp_stream.append(&block.receipts_root);
-    rlp_stream.append(&block.logs_bloom.unwrap());
+    rlp_stream.append(&logs_bloom);
     rlp_stream.append(&block.difficulty);
-    rlp_stream.append(&block.number.unwrap());
+    rlp_stream.append(&number);
     rlp_stream.append(&block.gas_limit);
     rlp_stream.append(&block.gas_used);
     rlp_stream.append(&block.timestamp);
     rlp_stream.append(&block.extra_data.0);
-    rlp_stream.append(&block.mix_hash.unwrap());
-    rlp_stream.append(&block.nonce.unwrap());
-    if block.base_fee_per_gas.is_some() {
-        rlp_stream.append(&block.base_fee_per_gas.unwrap());
+    rlp_stream.append(&mix_hash);
+    rlp_stream.append(&nonce);
+    if let Some(base_fee_per_gas) = &block.base_fee_per_gas {
+        rlp_stream.append(base_fee_per_gas);
+    }
+    if let Some(withdrawals_root) = &block.withdrawals_root {
+        rlp_stream.append(withdrawals_root);
+    }
+    if let Some(blob_gas_used) = &block.blob_gas_used {
+        rlp_stream.append(blob_gas_used);
     }
-    if block.withdrawals_root.is_some() {
-        rlp_stream.append(&block.withdrawals_root.unwrap());
+    if let Some(excess_blob_gas) = &block.excess_blob_gas {
+        rlp_stream.append(excess_blob_gas);
+    }
+    if let Some(root) = &block.parent_beacon_block_root {
+        rlp_stream.append(root);
+    }
+    if let Some(requests_hash) = &block.requests_hash {
+        rlp_stream.append(requests_hash);
     }
     let out = rlp_stream.out().to_vec();
 
@@ -44,15 +125,104 @@ pub fn serialize_block(block: &FullBlock<H256>) -> anyhow::Result<Vec<u8>> {
 
     let mut hasher = Keccak256::new();
     hasher.update(&out);
-    let hash = hasher.finalize();
-    let hash_string = hash
-        .iter()
-        .fold(String::new(), |acc, el| format!("{acc}{:0x}", el));
-    tracing::trace!(
-        "Calculated block hash: {hash_string}.\nOriginal: {:?}",
-        block.hash
-    );
-    assert_eq!(block.hash.unwrap().0.to_vec(), hash.to_vec());
+    let computed = H256::from_slice(&hasher.finalize());
+    tracing::trace!("Calculated block hash: {computed:?}.\nOriginal: {reported:?}");
+    if computed != reported {
+        return Err(HeaderError::HashMismatch {
+            number: block.number,
+            reported,
+            computed,
+            fields: list_len,
+        });
+    }
 
     Ok(out)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{serialize_block, HeaderError};
+    use crate::eth::FullBlock;
+    use std::collections::HashSet;
+    use std::path::PathBuf;
+    use web3::types::{H256, U256};
+
+    // Number of header fields by fork name in the fixture file name '<network>-<fork>-<number>.json'
+    const FORK_FIELDS: [(&str, usize); 5] = [
+        ("frontier", 15),
+        ("london", 16),
+        ("shanghai", 17),
+        ("cancun", 20),
+        ("prague", 21),
+    ];
+
+    // Headers fetched with 'tools/fetch_header_fixtures.py', hash of every header is checked
+    #[test]
+    fn test_header_fixtures() -> anyhow::Result<()> {
+        let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("fixtures/headers");
+        let mut checked = HashSet::new();
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            let name = path.file_name().unwrap().to_string_lossy().to_string();
+            let fork = name.split('-').nth(1).unwrap_or_default();
+            let (_, fields) = FORK_FIELDS
+                .iter()
+                .find(|(known, _)| *known == fork)
+                .unwrap_or_else(|| panic!("Unknown fork {fork:?} of fixture {name}"));
+            let block: FullBlock<H256> = serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+            let out = serialize_block(&block)?;
+            assert_eq!(rlp::Rlp::new(&out).item_count()?, *fields, "{name}");
+            checked.insert(fork.to_string());
+        }
+        let missing: Vec<&str> = FORK_FIELDS
+            .iter()
+            .map(|(fork, _)| *fork)
+            .filter(|fork| !checked.contains(*fork))
+            .collect();
+        assert!(
+            missing.is_empty(),
+            "No header fixtures for forks {missing:?}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_header_errors() -> anyhow::Result<()> {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("fixtures/headers/mainnet-frontier-0.json");
+        let block: FullBlock<H256> = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+
+        let mut missing = block.clone();
+        missing.mix_hash = None;
+        assert_eq!(
+            serialize_block(&missing),
+            Err(HeaderError::MissingField {
+                number: block.number,
+                field: "mixHash"
+            })
+        );
+
+        let mut gap = block.clone();
+        gap.parent_beacon_block_root = Some(H256::zero());
+        assert_eq!(
+            serialize_block(&gap),
+            Err(HeaderError::ForkFieldGap {
+                number: block.number,
+                field: "parentBeaconBlockRoot"
+            })
+        );
+
+        let mut wrong = block.clone();
+        wrong.timestamp = U256::from(1);
+        match serialize_block(&wrong) {
+            Err(HeaderError::HashMismatch {
+                reported, fields, ..
+            }) => {
+                assert_eq!(Some(reported), block.hash);
+                assert_eq!(fields, 15);
+            }
+            result => panic!("Unexpected result: {result:?}"),
+        }
+        Ok(())
+    }
+}
@@ -1,14 +1,13 @@
-use crate::elock::get_elock_address;
-use crate::eth::create_web3_socket;
+use crate::chain::EthChain;
+use crate::config::BridgeConfig;
+use crate::eth::create_eth_client;
 use crate::helper::abi::EVENTS_IDS;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::collections::HashMap;
 use std::io::BufReader;
 use std::str::FromStr;
-use web3::transports::WebSocket;
 use web3::types::{H256, U256};
-use web3::{Transport, Web3};
 
 #[derive(Deserialize, Debug)]
 struct EventLog {
@@ -59,12 +58,12 @@ fn get_events_signatures_table() -> anyhow::Result<HashMap<String, Event>> {
         .map_err(|e| anyhow::format_err!("Failed to decode identifiers map {}", e))
 }
 
-pub async fn get_all_events() -> anyhow::Result<()> {
+pub async fn get_all_events(config: &BridgeConfig) -> anyhow::Result<()> {
     // create ETH client
-    let web3s = create_web3_socket().await?;
+    let web3s = create_eth_client(&config.eth, &config.retry).await?;
 
     // Load ELock address
-    let elock_address = get_elock_address()?;
+    let elock_address = config.eth.elock_address;
 
     // Setup event filter
     let params = web3::helpers::serialize(&json!({
@@ -112,15 +111,11 @@ pub async fn get_all_events() -> anyhow::Result<()> {
 // setting the fromBlock and toBlock to the block number the blockHash references. If blockHash
 // is present in the filter criteria, neither fromBlock nor toBlock is allowed
 pub async fn get_events(
-    web3s: &Web3<WebSocket>,
+    web3s: &dyn EthChain,
     params: serde_json::Value,
 ) -> anyhow::Result<Vec<DecodedEvent>> {
     // Execute query
-    let res = web3s
-        .transport()
-        .execute("eth_getLogs", vec![params])
-        .await
-        .map_err(|e| anyhow::format_err!("Failed to execute ETH getLogs request: {e}"))?;
+    let res = web3s.get_logs(params).await?;
 
     // Deserialize result
     let events: Vec<EventLog> = serde_json::from_value(res)?;
@@ -158,11 +153,12 @@ pub async fn get_events(
                 let mut params = HashMap::new();
                 let mut indexed_cnt = 0;
                 for (index, input) in event_scheme.params.iter().enumerate() {
+                    // Data offset of the param is its index among non-indexed params
+                    let value =
+                        decode_argument(input, index - indexed_cnt, &mut extra_topics, &data_buf)?;
                     if input.indexed {
                         indexed_cnt += 1;
                     }
-                    let value =
-                        decode_argument(input, index - indexed_cnt, &mut extra_topics, &data_buf)?;
                     params.insert(input.name.clone(), value);
                 }
                 tracing::info!("Decoded event args: {params:?}");
@@ -0,0 +1,132 @@
+use crate::config::EthConfig;
+use crate::helper::password::read_password;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use web3::signing::{Key, SecretKey, SecretKeyRef};
+use web3::types::Address;
+
+const PASSWORD_ENV: &str = "ETH_KEYSTORE_PASSWORD";
+
+// ETH private key file is either a V3 JSON keystore (Web3 Secret Storage, scrypt or pbkdf2) or a
+// hex encoded plaintext key, which is still accepted for existing deployments
+fn is_keystore(content: &str) -> bool {
+    content.trim_start().starts_with('{')
+}
+
+fn read_keystore_password(password_path: Option<&Path>, confirm: bool) -> anyhow::Result<String> {
+    read_password(
+        password_path,
+        PASSWORD_ENV,
+        "ETH keystore",
+        "eth.keystore_password_path (ETH_KEYSTORE_PASSWORD_PATH)",
+        confirm,
+    )
+}
+
+fn parse_plain_key(content: &str) -> anyhow::Result<SecretKey> {
+    SecretKey::from_str(content.trim())
+        .map_err(|e| anyhow::format_err!("Failed to load private key: {e}"))
+}
+
+pub fn is_encrypted(key_path: &Path) -> anyhow::Result<bool> {
+    let content = std::fs::read_to_string(key_path).map_err(|e| {
+        anyhow::format_err!("Failed to read ETH private key {}: {e}", key_path.display())
+    })?;
+    Ok(is_keystore(&content))
+}
+
+// Load ETH private key from a keystore or a plaintext key file
+pub fn load_key(key_path: &Path, password_path: Option<&Path>) -> anyhow::Result<SecretKey> {
+    let content = std::fs::read_to_string(key_path).map_err(|e| {
+        anyhow::format_err!("Failed to read ETH private key {}: {e}", key_path.display())
+    })?;
+    if !is_keystore(&content) {
+        tracing::warn!(
+            "ETH private key {} is not encrypted, import it with `gosh-bridge keys import`",
+            key_path.display()
+        );
+        return parse_plain_key(&content);
+    }
+    let password = read_keystore_password(password_path, false)?;
+    let secret = eth_keystore::decrypt_key(key_path, password).map_err(|e| {
+        anyhow::format_err!("Failed to decrypt ETH keystore {}: {e}", key_path.display())
+    })?;
+    SecretKey::from_slice(&secret)
+        .map_err(|e| anyhow::format_err!("Failed to load private key from keystore: {e}"))
+}
+
+// Load validator ETH key set in config
+pub fn load_secret(config: &EthConfig) -> anyhow::Result<SecretKey> {
+    load_key(
+        config.private_key_path()?,
+        config.keystore_password_path.as_deref(),
+    )
+}
+
+pub fn key_address(key: &SecretKey) -> Address {
+    SecretKeyRef::new(key).address()
+}
+
+// Encrypt plaintext key into a keystore named by the key address in `dir`. Returns the address
+// and path of the created keystore, the plaintext key is left for the operator to remove.
+pub fn import_key(
+    key_path: &Path,
+    dir: &Path,
+    password_path: Option<&Path>,
+) -> anyhow::Result<(Address, PathBuf)> {
+    let content = std::fs::read_to_string(key_path).map_err(|e| {
+        anyhow::format_err!("Failed to read ETH private key {}: {e}", key_path.display())
+    })?;
+    if is_keystore(&content) {
+        anyhow::bail!("{} is already a keystore", key_path.display());
+    }
+    let key = parse_plain_key(&content)?;
+    let address = key_address(&key);
+    let name = format!("{address:?}.json");
+    let path = dir.join(&name);
+    if path.exists() {
+        anyhow::bail!("Keystore {} already exists", path.display());
+    }
+    let password = read_keystore_password(password_path, true)?;
+    eth_keystore::encrypt_key(
+        dir,
+        &mut rand::thread_rng(),
+        key.secret_bytes(),
+        password,
+        Some(&name),
+    )
+    .map_err(|e| anyhow::format_err!("Failed to encrypt ETH keystore: {e}"))?;
+    Ok((address, path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{import_key, is_encrypted, key_address, load_key};
+
+    #[test]
+    fn test_import_and_load() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let key_path = dir.path().join("eth.private.key");
+        std::fs::write(
+            &key_path,
+            "4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318\n",
+        )?;
+        let password_path = dir.path().join("password");
+        std::fs::write(&password_path, "secret\n")?;
+
+        let key = load_key(&key_path, None)?;
+        let (address, keystore) = import_key(&key_path, dir.path(), Some(&password_path))?;
+        assert!(is_encrypted(&keystore)?);
+        assert_eq!(address, key_address(&key));
+        assert_eq!(
+            keystore.file_name().unwrap().to_string_lossy(),
+            format!("{address:?}.json")
+        );
+        assert_eq!(load_key(&keystore, Some(&password_path))?, key);
+
+        std::fs::write(&password_path, "wrong")?;
+        assert!(load_key(&keystore, Some(&password_path)).is_err());
+        assert!(import_key(&keystore, dir.path(), Some(&password_path)).is_err());
+        Ok(())
+    }
+}
@@ -3,19 +3,51 @@ mod call;
 pub mod encoder;
 pub mod events;
 pub mod helper;
+pub mod keystore;
+mod quorum;
+pub mod signer;
+pub mod transport;
 
-pub use block::{read_block, FullBlock};
+use crate::config::{EthConfig, RetryConfig};
+use crate::retry::Retry;
+pub use block::{check_chain, read_block, read_block_cached, read_blocks, FullBlock};
 pub use call::call_function;
-use std::env;
-use web3::transports::WebSocket;
+pub use quorum::Quorum;
+use std::str::FromStr;
+pub use transport::{EthEndpoint, EthTransport};
 use web3::Web3;
 
-pub async fn create_web3_socket() -> anyhow::Result<Web3<WebSocket>> {
-    let eth_endpoint = env::var("ETH_NETWORK")
-        .map_err(|e| anyhow::format_err!("Failed to get ETH_NETWORK env var: {e}"))?;
-    tracing::info!("Connecting to the ETH endpoint: {eth_endpoint}");
-    let websocket = WebSocket::new(&eth_endpoint)
-        .await
-        .map_err(|e| anyhow::format_err!("Failed to create websocket: {e}"))?;
-    Ok(Web3::new(websocket))
+// ETH client used by the bridge: every provider retries its own failed reads, results of several
+// providers are cross-checked
+pub type EthClient = Quorum<Retry<Web3<EthTransport>>>;
+
+pub async fn create_web3(config: &EthConfig) -> anyhow::Result<Web3<EthTransport>> {
+    let endpoint = EthEndpoint::from_str(&config.network)?;
+    tracing::info!("Connecting to the ETH endpoint: {endpoint}");
+    let transport = EthTransport::connect(&endpoint).await?;
+    Ok(Web3::new(transport))
+}
+
+// Connect to the primary ETH endpoint and the extra ones. Extra providers that failed to connect
+// are skipped while the quorum can still be reached.
+pub async fn create_eth_client(
+    config: &EthConfig,
+    retry: &RetryConfig,
+) -> anyhow::Result<EthClient> {
+    let mut providers = vec![];
+    for (i, network) in std::iter::once(&config.network)
+        .chain(&config.extra_networks)
+        .enumerate()
+    {
+        let endpoint = EthEndpoint::from_str(network)?;
+        // Several providers may share the host, so they are told apart by index
+        let label = format!("#{i} {endpoint}");
+        tracing::info!("Connecting to the ETH endpoint: {label}");
+        match EthTransport::connect(&endpoint).await {
+            Ok(transport) => providers.push((label, Retry::new(Web3::new(transport), retry))),
+            Err(e) if i == 0 => return Err(e),
+            Err(e) => tracing::warn!("Failed to connect to the ETH endpoint {label}: {e}"),
+        }
+    }
+    Quorum::new(providers, config.quorum)
 }
@@ -0,0 +1,566 @@
+use crate::chain::EthChain;
+use crate::error::BridgeError;
+use crate::eth::encoder::serialize_block;
+use crate::eth::signer::EthSigner;
+use crate::eth::FullBlock;
+use async_trait::async_trait;
+use futures::future::join_all;
+use serde_json::Value;
+use std::future::Future;
+use web3::ethabi::Token;
+use web3::types::{Address, BlockId, BlockNumber, Bytes, Log, H256, U256};
+
+// Several ETH providers used as one. Security relevant reads (blocks by number, logs and
+// storage) are sent to all providers and the result is accepted only if `quorum` of them returned
+// the same values. Block tags of these reads are replaced with one block number first, so
+// providers at different heights are asked for the same block. Other reads are sent to the first
+// provider and fall back to the next ones on error. Transactions are sent only with the first
+// provider.
+pub struct Quorum<C> {
+    providers: Vec<(String, C)>,
+    quorum: usize,
+}
+
+impl<C: EthChain> Quorum<C> {
+    // Providers are identified by their labels in logs, the first one is the primary
+    pub fn new(providers: Vec<(String, C)>, quorum: usize) -> anyhow::Result<Self> {
+        if providers.is_empty() || quorum == 0 || quorum > providers.len() {
+            return Err(BridgeError::Config(format!(
+                "ETH quorum {quorum} can't be reached with {} providers",
+                providers.len()
+            ))
+            .into());
+        }
+        Ok(Self { providers, quorum })
+    }
+
+    pub fn primary(&self) -> &C {
+        &self.providers[0].1
+    }
+
+    // Values are grouped by `key`, so providers agree if they return the same data even if it is
+    // formatted differently. Value which can't be keyed counts as a failure of its provider.
+    async fn agreed<'a, T, K, F, Fut>(
+        &'a self,
+        operation: &str,
+        f: F,
+        key: impl Fn(&T) -> anyhow::Result<K>,
+    ) -> anyhow::Result<T>
+    where
+        K: PartialEq,
+        F: Fn(&'a C) -> Fut,
+        Fut: Future<Output = anyhow::Result<T>>,
+    {
+        if self.providers.len() == 1 {
+            return f(&self.providers[0].1).await;
+        }
+        let results = join_all(self.providers.iter().map(|(_, chain)| f(chain))).await;
+
+        // Group providers by returned value
+        let mut groups: Vec<(K, T, Vec<&str>)> = vec![];
+        let mut failures = vec![];
+        for ((endpoint, _), result) in self.providers.iter().zip(results) {
+            let keyed = result.and_then(|value| Ok((key(&value)?, value)));
+            match keyed {
+                Ok((key, value)) => match groups.iter_mut().find(|(k, _, _)| *k == key) {
+                    Some((_, _, endpoints)) => endpoints.push(endpoint),
+                    None => groups.push((key, value, vec![endpoint])),
+                },
+                Err(e) => failures.push((endpoint.as_str(), e)),
+            }
+        }
+        groups.sort_by_key(|(_, _, endpoints)| std::cmp::Reverse(endpoints.len()));
+        let report = groups
+            .iter()
+            .map(|(_, _, endpoints)| format!("[{}]", endpoints.join(", ")))
+            .chain(
+                failures
+                    .iter()
+                    .map(|(endpoint, e)| format!("failed {endpoint}: {e}")),
+            )
+            .collect::<Vec<String>>()
+            .join(", ");
+
+        match groups.into_iter().next() {
+            Some((_, value, endpoints)) if endpoints.len() >= self.quorum => {
+                if endpoints.len() < self.providers.len() {
+                    tracing::warn!("ETH providers diverged on {operation}: {report}");
+                }
+                Ok(value)
+            }
+            Some(_) => Err(BridgeError::ValidationMismatch(format!(
+                "ETH providers did not reach quorum {} on {operation}: {report}",
+                self.quorum
+            ))
+            .into()),
+            None => Err(all_failed(operation, &failures, &report)),
+        }
+    }
+
+    // Replace block tag with the lowest number of the tagged block among providers, so the block
+    // is known to all of them. Explicit numbers are kept.
+    async fn pinned(&self, block: BlockNumber) -> anyhow::Result<BlockNumber> {
+        if self.providers.len() == 1
+            || matches!(block, BlockNumber::Number(_) | BlockNumber::Earliest)
+        {
+            return Ok(block);
+        }
+        let results = join_all(
+            self.providers
+                .iter()
+                .map(|(_, chain)| chain.read_block(BlockId::Number(block))),
+        )
+        .await;
+        let mut numbers = vec![];
+        let mut failures = vec![];
+        for ((label, _), result) in self.providers.iter().zip(results) {
+            let number = result.and_then(|block| {
+                block
+                    .number
+                    .ok_or(BridgeError::Decode("ETH block number is missing".to_string()).into())
+            });
+            match number {
+                Ok(number) => numbers.push(number),
+                Err(e) => failures.push((label.as_str(), e)),
+            }
+        }
+        let report = failures
+            .iter()
+            .map(|(label, e)| format!("failed {label}: {e}"))
+            .collect::<Vec<String>>()
+            .join(", ");
+        match numbers.iter().min() {
+            Some(number) if numbers.len() >= self.quorum => {
+                tracing::trace!("ETH block {block:?} is pinned to {number}");
+                Ok(BlockNumber::Number(*number))
+            }
+            Some(_) => Err(BridgeError::Transport(format!(
+                "Not enough ETH providers returned block {block:?} for quorum {}: {report}",
+                self.quorum
+            ))
+            .into()),
+            None => Err(all_failed(
+                &format!("read_block {block:?}"),
+                &failures,
+                &report,
+            )),
+        }
+    }
+
+    // Pin tags of the logs filter range, the filter by block hash does not depend on heights
+    async fn pinned_logs_filter(&self, mut params: Value) -> anyhow::Result<Value> {
+        if params.get("blockHash").is_some() {
+            return Ok(params);
+        }
+        for bound in ["fromBlock", "toBlock"] {
+            if let Some(tag) = block_tag(params.get(bound)) {
+                params[bound] = serde_json::to_value(self.pinned(tag).await?)?;
+            }
+        }
+        Ok(params)
+    }
+
+    async fn first<'a, T, F, Fut>(&'a self, operation: &str, f: F) -> anyhow::Result<T>
+    where
+        F: Fn(&'a C) -> Fut,
+        Fut: Future<Output = anyhow::Result<T>>,
+    {
+        let mut last_error = None;
+        for (endpoint, chain) in &self.providers {
+            match f(chain).await {
+                Ok(value) => return Ok(value),
+                Err(e) if BridgeError::is_retryable(&e) => {
+                    tracing::warn!("ETH provider {endpoint} failed on {operation}: {e}");
+                    last_error = Some(e);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Err(last_error.unwrap())
+    }
+
+    // Read block from the first provider which returns it with a header matching the hash.
+    // Providers which fail or return a block that fails verification are skipped.
+    async fn verified_block(&self, hash: H256) -> anyhow::Result<FullBlock<H256>> {
+        let mut failures = vec![];
+        for (endpoint, chain) in &self.providers {
+            let verified = chain
+                .read_block(BlockId::Hash(hash))
+                .await
+                .and_then(|block| verify_block(block, hash));
+            match verified {
+                Ok(block) => return Ok(block),
+                Err(e) => {
+                    tracing::warn!("ETH provider {endpoint} failed on read_block {hash:?}: {e}");
+                    failures.push((endpoint.as_str(), e));
+                }
+            }
+        }
+        let report = failures
+            .iter()
+            .map(|(endpoint, e)| format!("{endpoint}: {e}"))
+            .collect::<Vec<String>>()
+            .join(", ");
+        let mismatch = failures.iter().any(|(_, e)| {
+            matches!(
+                BridgeError::find(e),
+                Some(BridgeError::ValidationMismatch(_))
+            )
+        });
+        if mismatch {
+            Err(BridgeError::ValidationMismatch(format!(
+                "No ETH provider returned valid block {hash:?}: {report}"
+            ))
+            .into())
+        } else {
+            Err(all_failed(
+                &format!("read_block {hash:?}"),
+                &failures,
+                &report,
+            ))
+        }
+    }
+}
+
+// Error of a read which failed on every provider. The cause is kept if all of them failed the
+// same way, e.g. a block which does not exist yet is not retried as a transport failure.
+fn all_failed(operation: &str, failures: &[(&str, anyhow::Error)], report: &str) -> anyhow::Error {
+    let message = format!("All ETH providers failed on {operation}: {report}");
+    let kind = |e: &anyhow::Error| BridgeError::find(e).map(BridgeError::kind);
+    match failures.first().and_then(|(_, e)| BridgeError::find(e)) {
+        Some(cause) if failures.iter().all(|(_, e)| kind(e) == Some(cause.kind())) => {
+            cause.with_message(message).into()
+        }
+        // Otherwise the read can be retried later
+        _ => BridgeError::Transport(message).into(),
+    }
+}
+
+// Tag of the logs filter bound, missing bound means the latest block
+fn block_tag(bound: Option<&Value>) -> Option<BlockNumber> {
+    match bound.map(|bound| bound.as_str()) {
+        None => Some(BlockNumber::Latest),
+        Some(Some("latest")) => Some(BlockNumber::Latest),
+        Some(Some("safe")) => Some(BlockNumber::Safe),
+        Some(Some("finalized")) => Some(BlockNumber::Finalized),
+        Some(Some("pending")) => Some(BlockNumber::Pending),
+        _ => None,
+    }
+}
+
+// Hash of a block with a header matching it, so blocks are compared by the verified header
+// rather than by the fields which are not part of it
+fn block_key(block: &FullBlock<H256>) -> anyhow::Result<H256> {
+    serialize_block(block).map_err(|e| BridgeError::ValidationMismatch(e.to_string()))?;
+    Ok(block.hash.unwrap_or_default())
+}
+
+fn verify_block(block: FullBlock<H256>, hash: H256) -> anyhow::Result<FullBlock<H256>> {
+    if block.hash != Some(hash) {
+        return Err(
+            BridgeError::ValidationMismatch(format!("returned block {:?}", block.hash)).into(),
+        );
+    }
+    block_key(&block)?;
+    Ok(block)
+}
+
+type LogKey = (
+    Address,
+    Vec<H256>,
+    Bytes,
+    Option<H256>,
+    Option<H256>,
+    Option<U256>,
+);
+
+// Fields which identify logs, providers differ in formatting and optional fields
+fn logs_key(logs: &Value) -> anyhow::Result<Vec<LogKey>> {
+    let logs: Vec<Log> = serde_json::from_value(logs.clone())
+        .map_err(|e| BridgeError::Decode(format!("Failed to decode ETH logs: {e}")))?;
+    Ok(logs
+        .into_iter()
+        .map(|log| {
+            (
+                log.address,
+                log.topics,
+                log.data,
+                log.block_hash,
+                log.transaction_hash,
+                log.log_index,
+            )
+        })
+        .collect())
+}
+
+#[async_trait]
+impl<C: EthChain> EthChain for Quorum<C> {
+    async fn read_block(&self, block_id: BlockId) -> anyhow::Result<FullBlock<H256>> {
+        match block_id {
+            // Block hash is checked against the header, so any provider can be used
+            BlockId::Hash(hash) => self.verified_block(hash).await,
+            BlockId::Number(number) => {
+                let block_id = BlockId::Number(self.pinned(number).await?);
+                self.agreed(
+                    &format!("read_block {block_id:?}"),
+                    |chain| chain.read_block(block_id),
+                    block_key,
+                )
+                .await
+            }
+        }
+    }
+
+    async fn get_logs(&self, params: Value) -> anyhow::Result<Value> {
+        let params = self.pinned_logs_filter(params).await?;
+        self.agreed("get_logs", |chain| chain.get_logs(params.clone()), logs_key)
+            .await
+    }
+
+    async fn storage(
+        &self,
+        address: Address,
+        index: U256,
+        block: Option<BlockNumber>,
+    ) -> anyhow::Result<H256> {
+        let block = Some(self.pinned(block.unwrap_or(BlockNumber::Latest)).await?);
+        self.agreed(
+            &format!("storage {address:?} {index} {block:?}"),
+            |chain| chain.storage(address, index, block),
+            |value| Ok(*value),
+        )
+        .await
+    }
+
+    async fn balance(&self, address: Address, block: Option<BlockNumber>) -> anyhow::Result<U256> {
+        self.first("balance", |chain| chain.balance(address, block))
+            .await
+    }
+
+    async fn gas_price(&self) -> anyhow::Result<U256> {
+        self.first("gas_price", |chain| chain.gas_price()).await
+    }
+
+    async fn query(
+        &self,
+        address: Address,
+        abi: &str,
+        function: &str,
+        params: Vec<Token>,
+    ) -> anyhow::Result<Vec<Token>> {
+        self.first(&format!("getter {function}"), |chain| {
+            chain.query(address, abi, function, params.clone())
+        })
+        .await
+    }
+
+    async fn call(
+        &self,
+        address: Address,
+        abi: &str,
+        function: &str,
+        params: Vec<Token>,
+        signer: &EthSigner,
+        confirmations: usize,
+    ) -> anyhow::Result<H256> {
+        self.primary()
+            .call(address, abi, function, params, signer, confirmations)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Quorum;
+    use crate::chain::fake::FakeEth;
+    use crate::chain::EthChain;
+    use crate::error::BridgeError;
+    use crate::eth::FullBlock;
+    use serde_json::json;
+    use std::path::PathBuf;
+    use web3::types::{Address, BlockId, BlockNumber, H256, U256};
+
+    fn provider(value: u64) -> FakeEth {
+        let eth = FakeEth::new();
+        eth.set_storage(
+            Address::from_low_u64_be(1),
+            U256::from(1),
+            None,
+            H256::from_low_u64_be(value),
+        );
+        eth
+    }
+
+    async fn read(quorum: &Quorum<FakeEth>) -> anyhow::Result<H256> {
+        quorum
+            .storage(
+                Address::from_low_u64_be(1),
+                U256::from(1),
+                Some(BlockNumber::Latest),
+            )
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_quorum_storage() -> anyhow::Result<()> {
+        let block = genesis()?;
+        let providers = |values: &[u64]| {
+            values
+                .iter()
+                .enumerate()
+                .map(|(i, value)| {
+                    let eth = provider(*value);
+                    eth.add_block(block.clone());
+                    (format!("provider{i}"), eth)
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let quorum = Quorum::new(providers(&[5, 5, 7]), 2)?;
+        assert_eq!(read(&quorum).await?, H256::from_low_u64_be(5));
+
+        let quorum = Quorum::new(providers(&[5, 6, 7]), 2)?;
+        let err = read(&quorum).await.unwrap_err();
+        assert!(matches!(
+            BridgeError::find(&err),
+            Some(BridgeError::ValidationMismatch(_))
+        ));
+
+        assert!(Quorum::new(providers(&[5]), 2).is_err());
+
+        // Provider ahead of others is read at the block known to all of them
+        let address = Address::from_low_u64_be(1);
+        let [behind, ahead] = [provider(5), provider(5)];
+        behind.add_block(block.clone());
+        ahead.add_block(block.clone());
+        ahead.add_block(FullBlock {
+            number: Some(1.into()),
+            ..block.clone()
+        });
+        ahead.set_storage(
+            address,
+            U256::from(1),
+            Some(1.into()),
+            H256::from_low_u64_be(6),
+        );
+        let quorum = Quorum::new(
+            vec![
+                ("#0 behind".to_string(), behind),
+                ("#1 ahead".to_string(), ahead),
+            ],
+            2,
+        )?;
+        assert_eq!(read(&quorum).await?, H256::from_low_u64_be(5));
+        Ok(())
+    }
+
+    fn genesis() -> anyhow::Result<FullBlock<H256>> {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("fixtures/headers/mainnet-frontier-0.json");
+        Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+    }
+
+    #[tokio::test]
+    async fn test_quorum_blocks_and_logs() -> anyhow::Result<()> {
+        let block = genesis()?;
+        let number = BlockId::Number(BlockNumber::Number(0.into()));
+        let log = json!({
+            "address": "0x00000000000000000000000000000000000000aa",
+            "topics": [format!("{:?}", H256::from_low_u64_be(1))],
+            "data": "0x01",
+            "blockNumber": "0x0",
+            "blockHash": format!("{:?}", block.hash.unwrap()),
+            "transactionHash": format!("{:?}", H256::from_low_u64_be(2)),
+            "logIndex": "0x0",
+        });
+
+        // Fields which are not part of the header and formatting of logs may differ
+        let first = provider(0);
+        first.add_block(block.clone());
+        first.add_log(log.clone());
+        let second = provider(0);
+        second.add_block(FullBlock {
+            total_difficulty: None,
+            size: None,
+            ..block.clone()
+        });
+        let mut other_log = log.clone();
+        other_log["address"] = json!("0x00000000000000000000000000000000000000AA");
+        other_log["removed"] = json!(false);
+        second.add_log(other_log);
+        let quorum = Quorum::new(
+            vec![("first".to_string(), first), ("second".to_string(), second)],
+            2,
+        )?;
+        assert_eq!(quorum.read_block(number).await?, block);
+        assert_eq!(quorum.get_logs(json!({})).await?, json!([log]));
+
+        // Providers agreeing on a block with forged header do not reach quorum
+        let forged = FullBlock {
+            timestamp: U256::from(1),
+            ..block.clone()
+        };
+        let first = provider(0);
+        first.add_block(forged.clone());
+        let second = provider(0);
+        second.add_block(forged);
+        let quorum = Quorum::new(
+            vec![("first".to_string(), first), ("second".to_string(), second)],
+            1,
+        )?;
+        let err = quorum.read_block(number).await.unwrap_err();
+        assert!(matches!(
+            BridgeError::find(&err),
+            Some(BridgeError::ValidationMismatch(_))
+        ));
+
+        // Block missing on all providers is not a transport failure
+        let quorum = Quorum::new(
+            vec![
+                ("first".to_string(), provider(0)),
+                ("second".to_string(), provider(0)),
+            ],
+            1,
+        )?;
+        let err = quorum.read_block(number).await.unwrap_err();
+        assert!(BridgeError::is_not_found(&err));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_verified_block() -> anyhow::Result<()> {
+        let block = genesis()?;
+        let hash = block.hash.unwrap();
+
+        // The first provider returns block with a forged header, the second one the real block
+        let forged = provider(0);
+        forged.add_block(FullBlock {
+            timestamp: U256::from(1),
+            ..block.clone()
+        });
+        let valid = provider(0);
+        valid.add_block(block.clone());
+        let quorum = Quorum::new(
+            vec![
+                ("missing".to_string(), provider(0)),
+                ("forged".to_string(), forged),
+                ("valid".to_string(), valid),
+            ],
+            1,
+        )?;
+        assert_eq!(quorum.read_block(BlockId::Hash(hash)).await?, block);
+
+        let forged = provider(0);
+        forged.add_block(FullBlock {
+            timestamp: U256::from(1),
+            ..block
+        });
+        let quorum = Quorum::new(vec![("forged".to_string(), forged)], 1)?;
+        let err = quorum.read_block(BlockId::Hash(hash)).await.unwrap_err();
+        assert!(matches!(
+            BridgeError::find(&err),
+            Some(BridgeError::ValidationMismatch(_))
+        ));
+        Ok(())
+    }
+}
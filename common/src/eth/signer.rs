@@ -0,0 +1,96 @@
+use crate::config::{EthConfig, RemoteSignerConfig};
+use crate::error::BridgeError;
+use crate::eth::keystore::load_secret;
+use crate::eth::{EthEndpoint, EthTransport};
+use std::str::FromStr;
+use web3::helpers as w3h;
+use web3::signing::{Key, SecretKey, SecretKeyRef};
+use web3::types::{Address, Bytes, TransactionRequest};
+use web3::{Transport, Web3};
+
+// Signer of ETH transactions sent by the validator
+pub enum EthSigner {
+    // Key loaded into the process from a plaintext key file or a keystore
+    Local(SecretKey),
+    // Key held by a separate signing service
+    Remote(RemoteSigner),
+}
+
+impl EthSigner {
+    pub fn address(&self) -> Address {
+        match self {
+            EthSigner::Local(key) => SecretKeyRef::new(key).address(),
+            EthSigner::Remote(signer) => signer.address(),
+        }
+    }
+}
+
+// Web3Signer compatible service. Transactions are signed with JSON-RPC 'eth_signTransaction' and
+// sent by the bridge, so the key never leaves the service.
+pub struct RemoteSigner {
+    web3: Web3<EthTransport>,
+    label: String,
+    address: Address,
+}
+
+impl RemoteSigner {
+    pub async fn connect(config: &RemoteSignerConfig) -> anyhow::Result<Self> {
+        let endpoint = EthEndpoint::from_str(&config.url)?;
+        let label = endpoint.label();
+        tracing::info!("Connecting to the ETH signer: {label}");
+        let web3 = Web3::new(EthTransport::connect(&endpoint).await?);
+
+        // Fail on start rather than on the first transaction if the signer has no such key
+        let accounts = web3.eth().accounts().await.map_err(|e| {
+            BridgeError::Transport(format!(
+                "Failed to query accounts of ETH signer {label}: {e}"
+            ))
+        })?;
+        if !accounts.contains(&config.address) {
+            return Err(BridgeError::Config(format!(
+                "ETH signer {label} has no key for {:?}",
+                config.address
+            ))
+            .into());
+        }
+        Ok(Self {
+            web3,
+            label,
+            address: config.address,
+        })
+    }
+
+    pub fn address(&self) -> Address {
+        self.address
+    }
+
+    // Sign transaction with nonce and fees set, returns it encoded for 'eth_sendRawTransaction'
+    pub async fn sign_transaction(&self, tx: &TransactionRequest) -> anyhow::Result<Bytes> {
+        let raw = self
+            .web3
+            .transport()
+            .execute("eth_signTransaction", vec![w3h::serialize(tx)])
+            .await
+            .map_err(|e| {
+                BridgeError::Transport(format!(
+                    "Failed to sign ETH transaction with signer {}: {e}",
+                    self.label
+                ))
+            })?;
+        serde_json::from_value(raw).map_err(|e| {
+            BridgeError::Decode(format!(
+                "Failed to decode ETH transaction signed by {}: {e}",
+                self.label
+            ))
+            .into()
+        })
+    }
+}
+
+// Connect to the remote signer if it is configured, load the local key otherwise
+pub async fn load_signer(config: &EthConfig) -> anyhow::Result<EthSigner> {
+    match &config.signer {
+        Some(signer) => Ok(EthSigner::Remote(RemoteSigner::connect(signer).await?)),
+        None => Ok(EthSigner::Local(load_secret(config)?)),
+    }
+}
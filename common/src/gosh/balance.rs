@@ -1,10 +1,8 @@
-use std::sync::Arc;
 use serde_json::json;
-use ton_client::net::ParamsOfQuery;
-use crate::gosh::helper::EverClient;
+use crate::chain::GoshChain;
 
 pub async fn query_balance(
-    context: &EverClient,
+    context: &dyn GoshChain,
     address: &str,
 ) -> anyhow::Result<u128> {
     tracing::info!("query account balance, address={address}");
@@ -24,18 +22,11 @@ pub async fn query_balance(
     let dst_address = address.to_string();
 
 
-    let result = ton_client::net::query(
-        Arc::clone(context),
-        ParamsOfQuery {
-            query: query.clone(),
-            variables: Some(json!({
-                "addr": dst_address.clone(),
-            })),
-        },
-    )
-        .await
-        .map(|r| r.result)
-        .map_err(|e| anyhow::format_err!("Failed to query data: {e}"))?;
+    let result = context
+        .query(&query, Some(json!({
+            "addr": dst_address.clone(),
+        })))
+        .await?;
 
     // Decode query results
     let result = u128::from_str_radix(
@@ -1,8 +1,6 @@
-use crate::gosh::helper::EverClient;
+use crate::chain::GoshChain;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-use std::sync::Arc;
-use ton_client::net::ParamsOfQuery;
 use crate::helper::serialize_u128;
 
 #[derive(Deserialize, Debug, Serialize)]
@@ -19,7 +17,7 @@ struct SeqNo {
     workchain_id: i8,
 }
 
-pub async fn get_master_block_seq_no(context: &EverClient, block_id: &str) -> anyhow::Result<u128> {
+pub async fn get_master_block_seq_no(context: &dyn GoshChain, block_id: &str) -> anyhow::Result<u128> {
     tracing::info!("query seq no for block_id={block_id}");
     let query = r#"query($block_id: String!){
         blockchain {
@@ -33,18 +31,9 @@ pub async fn get_master_block_seq_no(context: &EverClient, block_id: &str) -> an
     .to_string();
 
     let block_id = block_id.to_string();
-    let result = ton_client::net::query(
-        Arc::clone(context),
-        ParamsOfQuery {
-            query: query.clone(),
-            variables: Some(json!({
-                "block_id": block_id
-            })),
-        },
-    )
-    .await
-    .map(|r| r.result)
-    .map_err(|e| anyhow::format_err!("Failed to query data: {e}"))?;
+    let result = context
+        .query(&query, Some(json!({ "block_id": block_id })))
+        .await?;
 
     tracing::info!("query result: {result}");
 
@@ -57,7 +46,7 @@ pub async fn get_master_block_seq_no(context: &EverClient, block_id: &str) -> an
     Ok(seq_no.seq_no)
 }
 
-pub async fn get_latest_master_block(context: &EverClient) -> anyhow::Result<MasterBlock> {
+pub async fn get_latest_master_block(context: &dyn GoshChain) -> anyhow::Result<MasterBlock> {
     tracing::info!("query latest master block seq no");
     let query = r#"query {
         blockchain {
@@ -68,16 +57,7 @@ pub async fn get_latest_master_block(context: &EverClient) -> anyhow::Result<Mas
     }"#
     .to_string();
 
-    let result = ton_client::net::query(
-        Arc::clone(context),
-        ParamsOfQuery {
-            query: query.clone(),
-            variables: None,
-        },
-    )
-    .await
-    .map(|r| r.result)
-    .map_err(|e| anyhow::format_err!("Failed to query data: {e}"))?;
+    let result = context.query(&query, None).await?;
 
     tracing::info!("query result: {result}");
 
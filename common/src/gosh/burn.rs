@@ -1,12 +1,11 @@
-use crate::gosh::helper::EverClient;
+use crate::chain::GoshChain;
 use crate::gosh::message::query_messages;
 use crate::helper::abi::RECEIVER_ABI;
 use serde::Deserialize;
 
 use crate::checker::get_receiver_address;
+use crate::store::Store;
 use crate::token_root::RootData;
-use std::sync::Arc;
-use ton_client::abi::{decode_message_body, Abi, ParamsOfDecodeMessageBody};
 
 const RECEIVER_FUNCTION_NAME: &str = "burnTokens";
 
@@ -30,34 +29,25 @@ struct BurnArguments {
 }
 
 pub async fn find_burns(
-    context: &EverClient,
+    context: &dyn GoshChain,
+    checker_address: &str,
     start_seq_no: u128,
     end_seq_no: u128,
 ) -> anyhow::Result<Vec<Burn>> {
     // Get receiver address
-    let receiver_address = get_receiver_address(context).await?;
+    let receiver_address = get_receiver_address(context, checker_address).await?;
 
     // Query all messages to receiver
     let messages = query_messages(context, &receiver_address, start_seq_no, end_seq_no)
         .await
         .map_err(|e| anyhow::format_err!("Failed to query messages to ROOT: {e}"))?;
 
-    // Load receiver abi
-    let abi = Abi::Json(RECEIVER_ABI.to_string());
-
     // Decode messages and look for message with burn
     let mut res = vec![];
     for message in messages {
-        let body = message.body;
-        let decode_params = ParamsOfDecodeMessageBody {
-            abi: abi.clone(),
-            body,
-            is_internal: true,
-            allow_partial: false,
-            function_name: None,
-            data_layout: None,
-        };
-        let decode_result = decode_message_body(Arc::clone(context), decode_params).await;
+        let decode_result = context
+            .decode_message_body(RECEIVER_ABI, &message.body)
+            .await;
         if let Ok(decode_result) = decode_result {
             if decode_result.name != RECEIVER_FUNCTION_NAME {
                 continue;
@@ -82,3 +72,28 @@ pub async fn find_burns(
     }
     Ok(res)
 }
+
+// Same as `find_burns`, but burns found before for the same range start are taken from the store,
+// so only master blocks after the saved range are scanned. Found burns are saved to the store.
+pub async fn find_burns_cached(
+    context: &dyn GoshChain,
+    store: &Store,
+    checker_address: &str,
+    start_seq_no: u128,
+    end_seq_no: u128,
+) -> anyhow::Result<Vec<Burn>> {
+    let (scanned_end, mut burns) = store
+        .burns(checker_address, start_seq_no, end_seq_no)?
+        .unwrap_or((start_seq_no, vec![]));
+    if scanned_end == end_seq_no {
+        tracing::info!("Use burns from store for seq no range {start_seq_no} - {end_seq_no}");
+        return Ok(burns);
+    }
+    tracing::info!(
+        "Found {} burns in store till seq no {scanned_end}",
+        burns.len()
+    );
+    burns.extend(find_burns(context, checker_address, scanned_end, end_seq_no).await?);
+    store.save_burns(checker_address, start_seq_no, end_seq_no, &burns)?;
+    Ok(burns)
+}
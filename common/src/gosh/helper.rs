@@ -1,34 +1,31 @@
+use crate::config::{redact_url, GoshConfig};
+use crate::gosh::keystore::load_keys;
+use base64::Engine;
 use serde::Deserialize;
-use std::env;
+use std::collections::HashMap;
+use std::path::Path;
 use std::sync::Arc;
-use std::time::Duration;
-use ton_client::crypto::KeyPair;
+use ton_client::crypto::{
+    nacl_sign_keypair_from_secret_key, KeyPair, ParamsOfNaclSignKeyPairFromSecret,
+};
 use ton_client::net::NetworkQueriesProtocol;
 use ton_client::processing::ProcessingEvent;
 use ton_client::{ClientConfig, ClientContext};
 
 pub type EverClient = Arc<ClientContext>;
-static DEFAULT_BLOCKCHAIN_TIMEOUT: Duration = Duration::from_secs(3 * 60);
-static MESSAGE_PROCESSING_TIMEOUT: &str = "GOSH_MESSAGE_PROCESSING_TIMEOUT_SEC";
-static WAIT_FOR_TIMEOUT: &str = "GOSH_WAIT_FOR_TIMEOUT_SEC";
-static QUERY_TIMEOUT: &str = "GOSH_QUERY_TIMEOUT_SEC";
 
-pub fn create_client() -> anyhow::Result<EverClient> {
-    let endpoints = env::var("GOSH_ENDPOINTS")
-        .map_err(|e| anyhow::format_err!("Failed to get GOSH_ENDPOINTS env var: {e}"))?
-        .split(',')
-        .map(|e| e.to_string())
-        .collect::<Vec<String>>();
-    tracing::info!("create gosh client. endpoints: {endpoints:?}");
-    let message_processing_timeout = env::var(MESSAGE_PROCESSING_TIMEOUT)
-        .map(|secs| Duration::from_secs(secs.parse::<u64>().unwrap_or(0)))
-        .unwrap_or(DEFAULT_BLOCKCHAIN_TIMEOUT);
-    let wait_for_timeout = env::var(WAIT_FOR_TIMEOUT)
-        .map(|secs| Duration::from_secs(secs.parse::<u64>().unwrap_or(0)))
-        .unwrap_or(DEFAULT_BLOCKCHAIN_TIMEOUT);
-    let query_timeout = env::var(QUERY_TIMEOUT)
-        .map(|secs| Duration::from_secs(secs.parse::<u64>().unwrap_or(0)))
-        .unwrap_or(DEFAULT_BLOCKCHAIN_TIMEOUT);
+pub fn create_client(config: &GoshConfig) -> anyhow::Result<EverClient> {
+    let endpoints = config.endpoints.clone();
+    tracing::info!(
+        "create gosh client. endpoints: {:?}",
+        endpoints
+            .iter()
+            .map(|endpoint| redact_url(endpoint))
+            .collect::<Vec<String>>()
+    );
+    let message_processing_timeout = config.message_processing_timeout;
+    let wait_for_timeout = config.wait_for_timeout;
+    let query_timeout = config.query_timeout;
 
     let config = ClientConfig {
         network: ton_client::net::NetworkConfig {
@@ -54,6 +51,16 @@ pub fn create_client() -> anyhow::Result<EverClient> {
     Ok(Arc::new(es_client))
 }
 
+#[derive(Deserialize)]
+struct ValidatorKey {
+    pvt_key: String,
+}
+
+#[derive(Deserialize)]
+struct ValidatorConfig {
+    validator_key_ring: HashMap<String, ValidatorKey>,
+}
+
 #[derive(Deserialize, Debug)]
 pub struct CallResult {
     #[serde(rename = "id")]
@@ -138,8 +145,71 @@ pub async fn default_callback(pe: ProcessingEvent) {
     tracing::trace!("callback: {}", processing_event_to_string(pe));
 }
 
-pub fn load_keys(path: &str) -> anyhow::Result<KeyPair> {
-    let data_str = std::fs::read_to_string(path)?;
-    serde_json::from_str(&data_str)
-        .map_err(|e| anyhow::format_err!("Failed to load key pair from {path}: {e}"))
+// Load all validator keys from the node config ('validator_key_ring' section). Key pairs are
+// derived in memory from base64 encoded private keys.
+pub fn load_validator_keys(
+    context: &EverClient,
+    config_path: &Path,
+) -> anyhow::Result<Vec<KeyPair>> {
+    let data_str = std::fs::read_to_string(config_path).map_err(|e| {
+        anyhow::format_err!(
+            "Failed to read validator config {}: {e}",
+            config_path.display()
+        )
+    })?;
+    let config: ValidatorConfig = serde_json::from_str(&data_str).map_err(|e| {
+        anyhow::format_err!(
+            "Failed to decode validator config {}: {e}",
+            config_path.display()
+        )
+    })?;
+
+    let mut res = vec![];
+    for (key_id, key) in config.validator_key_ring {
+        let secret = base64::engine::general_purpose::STANDARD
+            .decode(&key.pvt_key)
+            .map_err(|e| anyhow::format_err!("Failed to decode private key {key_id}: {e}"))?;
+        let secret = hex::encode(secret);
+        let derived = nacl_sign_keypair_from_secret_key(
+            Arc::clone(context),
+            ParamsOfNaclSignKeyPairFromSecret {
+                secret: secret.clone(),
+            },
+        )
+        .map_err(|e| anyhow::format_err!("Failed to derive key pair {key_id}: {e}"))?;
+        tracing::info!("Loaded validator key: 0x{}", derived.public);
+        res.push(KeyPair {
+            public: derived.public,
+            secret,
+        });
+    }
+    Ok(res)
+}
+
+// Load validator keys. If the node config is specified, all keys from its key ring are used,
+// otherwise one key pair is loaded from the keys file.
+pub fn get_validator_keys(
+    context: &EverClient,
+    config: &GoshConfig,
+) -> anyhow::Result<Vec<KeyPair>> {
+    if let Some(config_path) = &config.validator_config_path {
+        let keys = load_validator_keys(context, config_path)
+            .map_err(|e| anyhow::format_err!("Failed to load validator keys from config: {e}"))?;
+        tracing::info!(
+            "Loaded {} validator keys from {}",
+            keys.len(),
+            config_path.display()
+        );
+        return Ok(keys);
+    }
+    let key_path = config
+        .validators_key_path
+        .as_ref()
+        .ok_or(anyhow::format_err!(
+            "Neither gosh.validator_config_path (VALIDATOR_CONFIG_PATH) nor \
+gosh.validators_key_path (VALIDATORS_KEY_PATH) is set"
+        ))?;
+    let keys = load_keys(key_path, config.validators_key_password_path.as_deref())
+        .map_err(|e| anyhow::format_err!("Failed to load validator GOSH keys: {e}"))?;
+    Ok(vec![keys])
 }
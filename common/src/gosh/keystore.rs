@@ -0,0 +1,131 @@
+use crate::helper::password::read_password;
+use serde_json::Value;
+use std::io::Read;
+use std::path::Path;
+use ton_client::crypto::KeyPair;
+
+const PASSWORD_ENV: &str = "VALIDATORS_KEY_PASSWORD";
+
+// GOSH key pair file is either plaintext `KeyPair` JSON or the same JSON encrypted in Web3 Secret
+// Storage format, as the ETH keystore: the key is derived from the password with scrypt, data is
+// encrypted with AES-128-CTR and authenticated with keccak MAC checked before decryption.
+// Encrypted key pairs are decrypted in memory only.
+fn is_encrypted(content: &str) -> bool {
+    serde_json::from_str::<Value>(content).is_ok_and(|value| value.get("crypto").is_some())
+}
+
+fn read_keys_password(password_path: Option<&Path>, confirm: bool) -> anyhow::Result<String> {
+    read_password(
+        password_path,
+        PASSWORD_ENV,
+        "GOSH validator keys",
+        "gosh.validators_key_password_path (VALIDATORS_KEY_PASSWORD_PATH)",
+        confirm,
+    )
+}
+
+fn parse_keys(data: &[u8], source: &str) -> anyhow::Result<KeyPair> {
+    serde_json::from_slice(data)
+        .map_err(|e| anyhow::format_err!("Failed to load key pair from {source}: {e}"))
+}
+
+fn read_keys_file(path: &Path) -> anyhow::Result<String> {
+    std::fs::read_to_string(path)
+        .map_err(|e| anyhow::format_err!("Failed to read key pair {}: {e}", path.display()))
+}
+
+pub fn load_keys(path: &Path, password_path: Option<&Path>) -> anyhow::Result<KeyPair> {
+    let content = read_keys_file(path)?;
+    let source = path.display().to_string();
+    if !is_encrypted(&content) {
+        tracing::warn!(
+            "GOSH key pair {source} is not encrypted, import it with `gosh-bridge keys gosh-import`"
+        );
+        return parse_keys(content.as_bytes(), &source);
+    }
+    let password = read_keys_password(password_path, false)?;
+    let data = eth_keystore::decrypt_key(path, password)
+        .map_err(|e| anyhow::format_err!("Failed to decrypt key pair {source}: {e}"))?;
+    parse_keys(&data, &source)
+}
+
+// Encrypt key pair JSON from the `source` file, or from stdin if it is not set, into `output`.
+// Piped through stdin the key pair is never written to disk unencrypted.
+pub fn import_keys(
+    source: Option<&Path>,
+    output: &Path,
+    password_path: Option<&Path>,
+) -> anyhow::Result<KeyPair> {
+    if output.exists() {
+        anyhow::bail!("Key pair file {} already exists", output.display());
+    }
+    let content = match source {
+        Some(path) => read_keys_file(path)?,
+        None => {
+            let mut content = String::new();
+            std::io::stdin()
+                .read_to_string(&mut content)
+                .map_err(|e| anyhow::format_err!("Failed to read key pair from stdin: {e}"))?;
+            content
+        }
+    };
+    if is_encrypted(&content) {
+        anyhow::bail!("Key pair is already encrypted");
+    }
+    let keys = parse_keys(content.as_bytes(), "input")?;
+
+    let name = output
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or(anyhow::format_err!(
+            "Wrong key pair file {}",
+            output.display()
+        ))?;
+    let dir = match output.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let password = read_keys_password(password_path, true)?;
+    eth_keystore::encrypt_key(
+        dir,
+        &mut rand::thread_rng(),
+        serde_json::to_vec(&keys)?,
+        password,
+        Some(name),
+    )
+    .map_err(|e| anyhow::format_err!("Failed to encrypt key pair: {e}"))?;
+    Ok(keys)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{import_keys, is_encrypted, load_keys};
+
+    #[test]
+    fn test_import_and_load() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let keys_path = dir.path().join("keys.json");
+        std::fs::write(&keys_path, r#"{"public": "aa", "secret": "plain-secret"}"#)?;
+        let password_path = dir.path().join("password");
+        std::fs::write(&password_path, "secret\n")?;
+
+        let output = dir.path().join("keys.enc.json");
+        import_keys(Some(&keys_path), &output, Some(&password_path))?;
+        let content = std::fs::read_to_string(&output)?;
+        assert!(is_encrypted(&content));
+        assert!(!content.contains("plain-secret"));
+        let keys = load_keys(&output, Some(&password_path))?;
+        assert_eq!(
+            (keys.public.as_str(), keys.secret.as_str()),
+            ("aa", "plain-secret")
+        );
+
+        // Plaintext key pairs are still loaded
+        assert_eq!(load_keys(&keys_path, None)?.secret, "plain-secret");
+
+        std::fs::write(&password_path, "wrong")?;
+        assert!(load_keys(&output, Some(&password_path)).is_err());
+        assert!(import_keys(Some(&keys_path), &output, Some(&password_path)).is_err());
+        Ok(())
+    }
+}
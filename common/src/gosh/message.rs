@@ -1,11 +1,8 @@
-use crate::gosh::helper::EverClient;
+use crate::chain::GoshChain;
 use crate::helper::abi::TOKEN_WALLET_ABI;
 use crate::helper::deserialize_uint;
 use serde::Deserialize;
 use serde_json::json;
-use std::sync::Arc;
-use ton_client::abi::{decode_message_body, Abi, ParamsOfDecodeMessageBody};
-use ton_client::net::ParamsOfQuery;
 
 pub struct Message {
     pub id: String,
@@ -66,7 +63,7 @@ struct AcceptArguments {
 }
 
 pub async fn query_messages(
-    context: &EverClient,
+    context: &dyn GoshChain,
     address: &str,
     start_seq_no: u128,
     end_seq_no: u128,
@@ -108,21 +105,17 @@ pub async fn query_messages(
 
     // Start a loop to query all messages in chunks
     loop {
-        let result = ton_client::net::query(
-            Arc::clone(context),
-            ParamsOfQuery {
-                query: query.clone(),
-                variables: Some(json!({
+        let result = context
+            .query(
+                &query,
+                Some(json!({
                     "addr": dst_address.clone(),
                     "start": start_seq_no,
                     "end": end_seq_no,
                     "after": after,
                 })),
-            },
-        )
-        .await
-        .map(|r| r.result)
-        .map_err(|e| anyhow::format_err!("Failed to query data: {e}"))?;
+            )
+            .await?;
 
         // Decode query results
         let nodes = &result["data"]["blockchain"]["account"]["transactions"];
@@ -164,11 +157,10 @@ pub async fn query_messages(
 }
 
 pub async fn get_token_wallet_total_mint(
-    gosh_context: &EverClient,
+    gosh_context: &dyn GoshChain,
     wallet_address: &str,
 ) -> anyhow::Result<u128> {
     tracing::info!("query token transfers to wallet, address={wallet_address}");
-    let abi = Abi::Json(TOKEN_WALLET_ABI.to_string());
     let wallet_function_name = "acceptMint";
 
     let query = r#"query($addr: String!, $after: String){
@@ -200,19 +192,15 @@ pub async fn get_token_wallet_total_mint(
     let dst_address = wallet_address.to_string();
     let mut total_value = 0;
     loop {
-        let result = ton_client::net::query(
-            Arc::clone(gosh_context),
-            ParamsOfQuery {
-                query: query.clone(),
-                variables: Some(json!({
+        let result = gosh_context
+            .query(
+                &query,
+                Some(json!({
                     "addr": dst_address.clone(),
                     "after": after,
                 })),
-            },
-        )
-        .await
-        .map(|r| r.result)
-        .map_err(|e| anyhow::format_err!("Failed to query data: {e}"))?;
+            )
+            .await?;
         let nodes = &result["data"]["blockchain"]["account"]["transactions"];
         let nodes: Messages = serde_json::from_value(nodes.clone())
             .map_err(|e| anyhow::format_err!("Failed to deserialize query result: {e}"))?;
@@ -221,16 +209,9 @@ pub async fn get_token_wallet_total_mint(
         for node in nodes.edges {
             let msg = node.node.message;
             if msg.body.is_some() && msg.msg_type == 0 && !node.node.aborted {
-                let decode_params = ParamsOfDecodeMessageBody {
-                    abi: abi.clone(),
-                    body: msg.body.unwrap(),
-                    is_internal: true,
-                    allow_partial: false,
-                    function_name: None,
-                    data_layout: None,
-                };
-                let decode_result =
-                    decode_message_body(Arc::clone(gosh_context), decode_params).await;
+                let decode_result = gosh_context
+                    .decode_message_body(TOKEN_WALLET_ABI, &msg.body.unwrap())
+                    .await;
                 if let Ok(decode_result) = decode_result {
                     if decode_result.name != wallet_function_name {
                         continue;
@@ -2,7 +2,9 @@ pub mod block;
 pub mod burn;
 mod call;
 pub mod helper;
+pub mod keystore;
 pub mod message;
+pub mod validators;
 pub mod balance;
 
 pub use call::{call_function, call_getter};
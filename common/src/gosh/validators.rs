@@ -0,0 +1,88 @@
+use crate::chain::GoshChain;
+use crate::error::BridgeError;
+use crate::gosh::call_getter;
+use crate::helper::abi::PROPOSAL_ABI;
+use anyhow::Context;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+#[derive(Deserialize, Debug)]
+struct ValidatorDescr {
+    public_key: String,
+}
+
+#[derive(Deserialize)]
+struct GetValidatorsSetResult {
+    #[serde(rename = "value0")]
+    validators: HashMap<String, String>,
+}
+
+// Keys are compared as 64 hex digits, sources differ in prefix, case and padding
+pub fn normalize_pubkey(pubkey: &str) -> String {
+    format!("{:0>64}", pubkey.trim_start_matches("0x").to_lowercase())
+}
+
+// Public keys of the current GOSH validators (config param 34) ordered by their index
+pub async fn get_validator_set(context: &dyn GoshChain) -> anyhow::Result<Vec<String>> {
+    tracing::info!("query current validator set");
+    let query = r#"query {
+        blockchain {
+            key_blocks( last: 1 ) {
+                edges { node { master { config { p34 { list { public_key } } } } } }
+            }
+        }
+    }"#
+    .to_string();
+
+    let result = context.query(&query, None).await?;
+
+    let validators: Vec<ValidatorDescr> = serde_json::from_value(
+        result["data"]["blockchain"]["key_blocks"]["edges"][0]["node"]["master"]["config"]["p34"]
+            ["list"]
+            .clone(),
+    )
+    .map_err(|e| anyhow::format_err!("Failed to deserialize validator set: {e}"))?;
+    Ok(validators
+        .into_iter()
+        .map(|validator| normalize_pubkey(&validator.public_key))
+        .collect())
+}
+
+// Public keys of validators which vote for the proposal ordered by their ids in the proposal
+pub async fn get_proposal_validator_set(
+    context: &dyn GoshChain,
+    proposal_address: &str,
+) -> anyhow::Result<Vec<String>> {
+    let set: GetValidatorsSetResult = call_getter(
+        context,
+        proposal_address,
+        PROPOSAL_ABI,
+        "getValidatorsSet",
+        None,
+    )
+    .await
+    .context("Failed to call getter getValidatorsSet")?;
+
+    let mut validators = set
+        .validators
+        .into_iter()
+        .map(|(id, pubkey)| {
+            let id = id.parse::<u16>().map_err(|e| {
+                BridgeError::Decode(format!("Wrong validator id {id} in proposal: {e}"))
+            })?;
+            Ok((id, normalize_pubkey(&pubkey)))
+        })
+        .collect::<anyhow::Result<Vec<(u16, String)>>>()?;
+    validators.sort_by_key(|(id, _)| *id);
+    if validators
+        .iter()
+        .enumerate()
+        .any(|(index, (id, _))| *id as usize != index)
+    {
+        return Err(BridgeError::Decode(format!(
+            "Validator ids of proposal {proposal_address} are not sequential"
+        ))
+        .into());
+    }
+    Ok(validators.into_iter().map(|(_, pubkey)| pubkey).collect())
+}
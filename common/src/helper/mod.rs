@@ -1,11 +1,14 @@
-use crate::eth::{create_web3_socket, read_block};
+use crate::config::BridgeConfig;
+use crate::eth::{create_eth_client, read_block};
 use crate::gosh::block::get_latest_master_block;
 use crate::gosh::helper::create_client;
+use crate::retry::Retry;
 use serde::{Deserialize, Deserializer, Serializer};
 use serde_json::json;
 use web3::types::{BlockId, BlockNumber};
 
 pub mod abi;
+pub mod password;
 pub mod tracing;
 
 pub fn deserialize_uint<'de, D, T>(deserializer: D) -> Result<T, D::Error>
@@ -26,10 +29,20 @@ pub fn serialize_u128<S>(val: &u128, s: S) -> Result<S::Ok, S::Error>
     s.serialize_str(&val_str)
 }
 
-pub async fn get_last_blocks() -> anyhow::Result<()> {
-    let context = create_client()?;
+// Legacy binaries accept `--dry-run` anywhere in arguments. The flag is removed from `args` and
+// enables dry run in config.
+pub fn take_dry_run_flag(config: &mut BridgeConfig, args: &mut Vec<String>) {
+    let count = args.len();
+    args.retain(|arg| arg != "--dry-run");
+    if args.len() < count {
+        config.set_dry_run();
+    }
+}
+
+pub async fn get_last_blocks(config: &BridgeConfig) -> anyhow::Result<()> {
+    let context = Retry::new(create_client(&config.gosh)?, &config.retry);
 
-    let web3s = create_web3_socket().await?;
+    let web3s = create_eth_client(&config.eth, &config.retry).await?;
 
     let last_gosh_block = get_latest_master_block(&context)
         .await
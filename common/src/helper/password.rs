@@ -0,0 +1,40 @@
+use crate::error::BridgeError;
+use std::io::IsTerminal;
+use std::path::Path;
+
+// Password of an encrypted key file. It is read from the file if it is set, then from env
+// `env`, then prompted if the process runs in a terminal. `name` and `setting` describe the key
+// and its password file option in messages.
+pub fn read_password(
+    password_path: Option<&Path>,
+    env: &str,
+    name: &str,
+    setting: &str,
+    confirm: bool,
+) -> anyhow::Result<String> {
+    if let Some(path) = password_path {
+        let password = std::fs::read_to_string(path).map_err(|e| {
+            anyhow::format_err!("Failed to read {name} password {}: {e}", path.display())
+        })?;
+        return Ok(password.trim_end_matches(['\r', '\n']).to_string());
+    }
+    if let Ok(password) = std::env::var(env) {
+        return Ok(password);
+    }
+    if !std::io::stdin().is_terminal() {
+        return Err(BridgeError::Config(format!(
+            "{name} password is not set, use {setting} or {env}"
+        ))
+        .into());
+    }
+    let password = rpassword::prompt_password(format!("{name} password: "))
+        .map_err(|e| anyhow::format_err!("Failed to read {name} password: {e}"))?;
+    if confirm {
+        let repeated = rpassword::prompt_password(format!("Repeat {name} password: "))
+            .map_err(|e| anyhow::format_err!("Failed to read {name} password: {e}"))?;
+        if repeated != password {
+            anyhow::bail!("{name} passwords do not match");
+        }
+    }
+    Ok(password)
+}
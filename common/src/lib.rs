@@ -1,6 +1,13 @@
+pub mod chain;
 pub mod checker;
+pub mod config;
+pub mod dry_run;
 pub mod elock;
+pub mod error;
 pub mod eth;
 pub mod gosh;
 pub mod helper;
+pub mod outcome;
+pub mod retry;
+pub mod store;
 pub mod token_root;
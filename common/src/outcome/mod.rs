@@ -0,0 +1,94 @@
+use serde::Serialize;
+use std::process::ExitCode;
+
+// Exit code of a flow that had work to do, but did not do it (e.g. all proposals failed
+// validation). Codes 1 and 2 are used for errors and wrong command line arguments.
+pub const SKIPPED_EXIT_CODE: u8 = 3;
+// Exit code of a flow that refused to work because the ETH block saved in GOSH is not canonical
+pub const REORG_EXIT_CODE: u8 = 4;
+
+// Result of one run of a bridge flow. Binaries print it as JSON and turn it into exit code.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+pub enum Outcome {
+    NothingToDo,
+    // Proposal with blocks (deposit) or master blocks (withdrawal) range was sent
+    Proposed {
+        from: String,
+        till: String,
+        transfers: usize,
+    },
+    // Addresses or keys of proposals this node voted for
+    Voted {
+        proposals: Vec<String>,
+    },
+    Skipped {
+        reason: String,
+    },
+    // ETH block saved in GOSH checker is not in the canonical chain. Fork point is the last block
+    // common for both chains, it is unknown if orphaned blocks are not available.
+    Reorg {
+        saved_block: u64,
+        saved_hash: String,
+        canonical_hash: String,
+        fork_block: Option<u64>,
+        fork_hash: Option<String>,
+        depth: Option<u64>,
+    },
+}
+
+impl Outcome {
+    // Outcome of a voting run. Proposals which were already voted for before are in neither list.
+    pub fn from_votes(voted: Vec<String>, failures: Vec<String>) -> Self {
+        if !voted.is_empty() {
+            Outcome::Voted { proposals: voted }
+        } else if !failures.is_empty() {
+            Outcome::Skipped {
+                reason: failures.join("; "),
+            }
+        } else {
+            Outcome::NothingToDo
+        }
+    }
+
+    pub fn exit_code(&self) -> ExitCode {
+        match self {
+            Outcome::Skipped { .. } => ExitCode::from(SKIPPED_EXIT_CODE),
+            Outcome::Reorg { .. } => ExitCode::from(REORG_EXIT_CODE),
+            _ => ExitCode::SUCCESS,
+        }
+    }
+
+    // Print outcome to stdout and return exit code for it
+    pub fn report(&self) -> anyhow::Result<ExitCode> {
+        tracing::info!("Outcome: {self:?}");
+        println!(
+            "{}",
+            serde_json::to_string(self)
+                .map_err(|e| anyhow::format_err!("Failed to serialize outcome: {e}"))?
+        );
+        Ok(self.exit_code())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Outcome;
+    use serde_json::json;
+
+    #[test]
+    fn test_votes_outcome() {
+        let outcome =
+            Outcome::from_votes(vec!["0:01".to_string()], vec!["0:02: failed".to_string()]);
+        assert_eq!(
+            json!(outcome),
+            json!({"outcome": "voted", "proposals": ["0:01"]})
+        );
+        let outcome = Outcome::from_votes(vec![], vec!["0:02: failed".to_string()]);
+        assert_eq!(
+            json!(outcome),
+            json!({"outcome": "skipped", "reason": "0:02: failed"})
+        );
+        assert_eq!(Outcome::from_votes(vec![], vec![]), Outcome::NothingToDo);
+    }
+}
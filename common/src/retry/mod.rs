@@ -0,0 +1,291 @@
+use crate::chain::{DecodedMessageBody, EthChain, GoshChain};
+use crate::config::RetryConfig;
+use crate::error::BridgeError;
+use crate::eth::signer::EthSigner;
+use crate::eth::FullBlock;
+use async_trait::async_trait;
+use rand::Rng;
+use serde_json::Value;
+use std::future::Future;
+use std::time::Duration;
+use ton_client::crypto::KeyPair;
+use web3::ethabi::Token;
+use web3::types::{Address, BlockId, BlockNumber, H256, U256};
+
+// Chain wrapper which retries failed reads with exponential backoff and limits time of every
+// operation. Transactions and external messages are never resent: a call that failed or timed out
+// may still be processed by the network, so its error is returned to the caller as is.
+pub struct Retry<C> {
+    inner: C,
+    config: RetryConfig,
+}
+
+impl<C> Retry<C> {
+    pub fn new(inner: C, config: &RetryConfig) -> Self {
+        Self {
+            inner,
+            config: config.clone(),
+        }
+    }
+
+    // Wrapped chain, calls made directly to it are not retried
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+}
+
+// Delay before retry number `attempt` starting from 1
+fn backoff(config: &RetryConfig, attempt: u32) -> Duration {
+    let backoff = config
+        .initial_backoff
+        .saturating_mul(2u32.saturating_pow(attempt - 1))
+        .min(config.max_backoff);
+    if config.jitter {
+        // Random delay from half to full backoff
+        let half = backoff / 2;
+        half + rand::thread_rng().gen_range(Duration::ZERO..=half)
+    } else {
+        backoff
+    }
+}
+
+async fn with_deadline<T, Fut>(
+    deadline: Duration,
+    operation: &str,
+    future: Fut,
+) -> anyhow::Result<T>
+where
+    Fut: Future<Output = anyhow::Result<T>>,
+{
+    tokio::time::timeout(deadline, future)
+        .await
+        .map_err(|_| BridgeError::Transport(format!("{operation} timed out after {deadline:?}")))?
+}
+
+// Run idempotent operation, retrying transport failures
+pub async fn retry<T, F, Fut>(config: &RetryConfig, operation: &str, mut f: F) -> anyhow::Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = anyhow::Result<T>>,
+{
+    let mut attempt = 1;
+    loop {
+        match with_deadline(config.read_deadline, operation, f()).await {
+            Err(e) if attempt < config.attempts && BridgeError::is_retryable(&e) => {
+                let delay = backoff(config, attempt);
+                tracing::warn!(
+                    "{operation} failed (attempt {attempt}/{}), retry in {delay:?}: {e}",
+                    config.attempts
+                );
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+// Run operation that must not be repeated, only its time is limited
+pub async fn once<T, Fut>(config: &RetryConfig, operation: &str, future: Fut) -> anyhow::Result<T>
+where
+    Fut: Future<Output = anyhow::Result<T>>,
+{
+    let deadline = config.call_deadline;
+    tokio::time::timeout(deadline, future).await.map_err(|_| {
+        BridgeError::Transport(format!(
+            "{operation} timed out after {deadline:?}, it may still be processed"
+        ))
+    })?
+}
+
+#[async_trait]
+impl<C: EthChain> EthChain for Retry<C> {
+    async fn read_block(&self, block_id: BlockId) -> anyhow::Result<FullBlock<H256>> {
+        retry(&self.config, "ETH read_block", || {
+            self.inner.read_block(block_id)
+        })
+        .await
+    }
+
+    async fn get_logs(&self, params: Value) -> anyhow::Result<Value> {
+        retry(&self.config, "ETH get_logs", || {
+            self.inner.get_logs(params.clone())
+        })
+        .await
+    }
+
+    async fn storage(
+        &self,
+        address: Address,
+        index: U256,
+        block: Option<BlockNumber>,
+    ) -> anyhow::Result<H256> {
+        retry(&self.config, "ETH storage", || {
+            self.inner.storage(address, index, block)
+        })
+        .await
+    }
+
+    async fn balance(&self, address: Address, block: Option<BlockNumber>) -> anyhow::Result<U256> {
+        retry(&self.config, "ETH balance", || {
+            self.inner.balance(address, block)
+        })
+        .await
+    }
+
+    async fn gas_price(&self) -> anyhow::Result<U256> {
+        retry(&self.config, "ETH gas_price", || self.inner.gas_price()).await
+    }
+
+    async fn query(
+        &self,
+        address: Address,
+        abi: &str,
+        function: &str,
+        params: Vec<Token>,
+    ) -> anyhow::Result<Vec<Token>> {
+        retry(&self.config, &format!("ETH getter {function}"), || {
+            self.inner.query(address, abi, function, params.clone())
+        })
+        .await
+    }
+
+    async fn call(
+        &self,
+        address: Address,
+        abi: &str,
+        function: &str,
+        params: Vec<Token>,
+        signer: &EthSigner,
+        confirmations: usize,
+    ) -> anyhow::Result<H256> {
+        once(
+            &self.config,
+            &format!("ETH call {function}"),
+            self.inner
+                .call(address, abi, function, params, signer, confirmations),
+        )
+        .await
+    }
+}
+
+#[async_trait]
+impl<C: GoshChain> GoshChain for Retry<C> {
+    async fn run_getter(
+        &self,
+        address: &str,
+        abi: &str,
+        function: &str,
+        args: Option<Value>,
+    ) -> anyhow::Result<Value> {
+        retry(&self.config, &format!("GOSH getter {function}"), || {
+            self.inner.run_getter(address, abi, function, args.clone())
+        })
+        .await
+    }
+
+    async fn call_function(
+        &self,
+        address: &str,
+        abi: &str,
+        keys: Option<KeyPair>,
+        function: &str,
+        args: Option<Value>,
+    ) -> anyhow::Result<String> {
+        once(
+            &self.config,
+            &format!("GOSH call {function}"),
+            self.inner.call_function(address, abi, keys, function, args),
+        )
+        .await
+    }
+
+    async fn query(&self, query: &str, variables: Option<Value>) -> anyhow::Result<Value> {
+        retry(&self.config, "GOSH query", || {
+            self.inner.query(query, variables.clone())
+        })
+        .await
+    }
+
+    async fn decode_message_body(
+        &self,
+        abi: &str,
+        body: &str,
+    ) -> anyhow::Result<DecodedMessageBody> {
+        // Decoding is local, there is nothing to retry
+        self.inner.decode_message_body(abi, body).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{once, retry};
+    use crate::config::RetryConfig;
+    use crate::error::BridgeError;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::time::Duration;
+
+    fn config() -> RetryConfig {
+        RetryConfig {
+            attempts: 3,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(2),
+            jitter: true,
+            read_deadline: Duration::from_millis(50),
+            call_deadline: Duration::from_millis(50),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retry_transient_errors() {
+        let config = config();
+        let calls = AtomicU32::new(0);
+        let result = retry(&config, "read", || async {
+            match calls.fetch_add(1, Ordering::SeqCst) {
+                // Hanging call is cut by the deadline and retried
+                0 => {
+                    tokio::time::sleep(Duration::from_secs(10)).await;
+                    Ok(0)
+                }
+                1 => Err(BridgeError::Transport("connection reset".to_string()).into()),
+                n => Ok(n),
+            }
+        })
+        .await;
+        assert_eq!(result.unwrap(), 2);
+
+        // Errors that repeat on the same input are returned at once
+        calls.store(0, Ordering::SeqCst);
+        let result: anyhow::Result<()> = retry(&config, "read", || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err(BridgeError::NotFound("block".to_string()).into())
+        })
+        .await;
+        assert!(BridgeError::is_not_found(&result.unwrap_err()));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        // Attempts are limited
+        calls.store(0, Ordering::SeqCst);
+        let result: anyhow::Result<()> = retry(&config, "read", || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err(BridgeError::Transport("connection reset".to_string()).into())
+        })
+        .await;
+        assert!(BridgeError::is_retryable(&result.unwrap_err()));
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_call_is_not_repeated() {
+        let config = config();
+        let calls = AtomicU32::new(0);
+        let result: anyhow::Result<()> = once(&config, "call", async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_secs(10)).await;
+            Ok(())
+        })
+        .await;
+        assert!(result.unwrap_err().to_string().contains("timed out"));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,636 @@
+use crate::config::StoreConfig;
+use crate::elock::transfer::{Transfer, TransferPatch};
+use crate::eth::FullBlock;
+use crate::gosh::burn::Burn;
+use crate::token_root::RootData;
+use parking_lot::Mutex;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::path::Path;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+use web3::types::{Address, H256, U64};
+
+// Stored in `user_version` pragma, bump it when the schema changes
+const SCHEMA_VERSION: i64 = 1;
+
+const SCHEMA: &str = r#"
+CREATE TABLE IF NOT EXISTS eth_blocks (
+    hash TEXT PRIMARY KEY,
+    number INTEGER NOT NULL,
+    data TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS eth_blocks_number ON eth_blocks (number);
+
+CREATE TABLE IF NOT EXISTS deposit_scans (
+    from_block INTEGER NOT NULL,
+    to_block INTEGER NOT NULL,
+    to_hash TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    PRIMARY KEY (from_block, to_hash)
+);
+CREATE TABLE IF NOT EXISTS deposits (
+    from_block INTEGER NOT NULL,
+    to_hash TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    tx_hash TEXT NOT NULL,
+    pubkey TEXT NOT NULL,
+    value TEXT NOT NULL,
+    root_name TEXT NOT NULL,
+    root_symbol TEXT NOT NULL,
+    root_decimals INTEGER NOT NULL,
+    eth_root TEXT NOT NULL,
+    PRIMARY KEY (from_block, to_hash, position)
+);
+
+CREATE TABLE IF NOT EXISTS burn_scans (
+    checker TEXT NOT NULL,
+    start_seq_no TEXT NOT NULL,
+    end_seq_no TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    PRIMARY KEY (checker, start_seq_no, end_seq_no)
+);
+CREATE TABLE IF NOT EXISTS burns (
+    checker TEXT NOT NULL,
+    start_seq_no TEXT NOT NULL,
+    end_seq_no TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    tx_id TEXT NOT NULL,
+    dest TEXT NOT NULL,
+    value TEXT NOT NULL,
+    eth_root TEXT NOT NULL,
+    PRIMARY KEY (checker, start_seq_no, end_seq_no, position)
+);
+
+CREATE TABLE IF NOT EXISTS proposals (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    kind TEXT NOT NULL,
+    from_block TEXT NOT NULL,
+    till_block TEXT NOT NULL,
+    transfers INTEGER NOT NULL,
+    tx_id TEXT NOT NULL,
+    created_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS seen_proposals (
+    address TEXT PRIMARY KEY,
+    eth_block INTEGER NOT NULL,
+    created_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS votes (
+    kind TEXT NOT NULL,
+    proposal TEXT NOT NULL,
+    voter TEXT NOT NULL,
+    tx_id TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    PRIMARY KEY (kind, proposal, voter)
+);
+
+CREATE TABLE IF NOT EXISTS alerts (
+    name TEXT PRIMARY KEY,
+    firing INTEGER NOT NULL,
+    observation TEXT NOT NULL,
+    runs INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+"#;
+
+// Deposit proposals are created in GOSH checker, withdrawal proposals in ELock
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProposalKind {
+    Deposit,
+    Withdrawal,
+}
+
+impl ProposalKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ProposalKind::Deposit => "deposit",
+            ProposalKind::Withdrawal => "withdrawal",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProposalRecord {
+    pub kind: ProposalKind,
+    pub from_block: String,
+    pub till_block: String,
+    pub transfers: usize,
+    pub tx_id: String,
+}
+
+// State of telemetry alert rule kept between runs
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AlertState {
+    pub firing: bool,
+    // Rule specific value observed in the last run
+    pub observation: String,
+    // Number of consecutive runs with the same observation
+    pub runs: u32,
+}
+
+// Local state of bridge processes. Data fetched from chains is cached here (only finalized data
+// is saved, so it never becomes stale), and proposals and votes sent by this node are kept as an
+// audit trail.
+pub struct Store {
+    connection: Mutex<Connection>,
+}
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+fn parse<T: FromStr>(value: &str, name: &str) -> anyhow::Result<T>
+where
+    T::Err: std::fmt::Display,
+{
+    T::from_str(value).map_err(|e| anyhow::format_err!("Wrong {name} in store: {value}: {e}"))
+}
+
+impl Store {
+    pub fn open(path: &Path) -> anyhow::Result<Self> {
+        tracing::info!("Open store {}", path.display());
+        let connection = Connection::open(path)
+            .map_err(|e| anyhow::format_err!("Failed to open store {}: {e}", path.display()))?;
+        Self::init(connection)
+    }
+
+    pub fn in_memory() -> anyhow::Result<Self> {
+        let connection = Connection::open_in_memory()
+            .map_err(|e| anyhow::format_err!("Failed to open in-memory store: {e}"))?;
+        Self::init(connection)
+    }
+
+    pub fn from_config(config: &StoreConfig) -> anyhow::Result<Self> {
+        match &config.path {
+            Some(path) => Self::open(path),
+            None => {
+                tracing::info!("Store path is not set, use in-memory store");
+                Self::in_memory()
+            }
+        }
+    }
+
+    fn init(connection: Connection) -> anyhow::Result<Self> {
+        let version: i64 = connection.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        if version > SCHEMA_VERSION {
+            anyhow::bail!(
+                "Store schema version {version} is newer than supported {SCHEMA_VERSION}"
+            );
+        }
+        connection
+            .execute_batch(SCHEMA)
+            .map_err(|e| anyhow::format_err!("Failed to create store schema: {e}"))?;
+        connection.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+        Ok(Self {
+            connection: Mutex::new(connection),
+        })
+    }
+
+    pub fn save_eth_block(&self, block: &FullBlock<H256>) -> anyhow::Result<()> {
+        let (hash, number) = match (block.hash, block.number) {
+            (Some(hash), Some(number)) => (hash, number),
+            _ => anyhow::bail!("Pending ETH block can't be saved"),
+        };
+        self.connection.lock().execute(
+            "INSERT OR REPLACE INTO eth_blocks (hash, number, data) VALUES (?1, ?2, ?3)",
+            params![
+                format!("{hash:?}"),
+                number.as_u64(),
+                serde_json::to_string(block)?
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn eth_block(&self, hash: H256) -> anyhow::Result<Option<FullBlock<H256>>> {
+        let data: Option<String> = self
+            .connection
+            .lock()
+            .query_row(
+                "SELECT data FROM eth_blocks WHERE hash = ?1",
+                params![format!("{hash:?}")],
+                |row| row.get(0),
+            )
+            .optional()?;
+        data.map(|data| {
+            serde_json::from_str(&data)
+                .map_err(|e| anyhow::format_err!("Failed to decode ETH block from store: {e}"))
+        })
+        .transpose()
+    }
+
+    // Deposits found in ELock events for the block range (borders included). The range is
+    // identified by the hash of its last block, so deposits of a reorged range are not reused.
+    pub fn save_deposits(
+        &self,
+        from_block: U64,
+        to_block: U64,
+        to_hash: H256,
+        deposits: &[TransferPatch],
+    ) -> anyhow::Result<()> {
+        let mut connection = self.connection.lock();
+        let tx = connection.transaction()?;
+        let to_hash = format!("{to_hash:?}");
+        let range = params![from_block.as_u64(), to_hash];
+        tx.execute(
+            "DELETE FROM deposits WHERE from_block = ?1 AND to_hash = ?2",
+            range,
+        )?;
+        for (position, deposit) in deposits.iter().enumerate() {
+            tx.execute(
+                "INSERT INTO deposits (from_block, to_hash, position, tx_hash, pubkey, value, \
+                root_name, root_symbol, root_decimals, eth_root) \
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                params![
+                    from_block.as_u64(),
+                    to_hash,
+                    position,
+                    deposit.data.hash,
+                    deposit.data.pubkey,
+                    deposit.data.value.to_string(),
+                    deposit.root.name,
+                    deposit.root.symbol,
+                    deposit.root.decimals,
+                    format!("{:?}", deposit.root.eth_root),
+                ],
+            )?;
+        }
+        tx.execute(
+            "INSERT OR REPLACE INTO deposit_scans (from_block, to_block, to_hash, created_at) \
+            VALUES (?1, ?2, ?3, ?4)",
+            params![from_block.as_u64(), to_block.as_u64(), to_hash, now()],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn deposits(
+        &self,
+        from_block: U64,
+        to_hash: H256,
+    ) -> anyhow::Result<Option<Vec<TransferPatch>>> {
+        let connection = self.connection.lock();
+        let range = params![from_block.as_u64(), format!("{to_hash:?}")];
+        let scanned = connection
+            .query_row(
+                "SELECT 1 FROM deposit_scans WHERE from_block = ?1 AND to_hash = ?2",
+                range,
+                |_| Ok(()),
+            )
+            .optional()?;
+        if scanned.is_none() {
+            return Ok(None);
+        }
+        let mut statement = connection.prepare(
+            "SELECT tx_hash, pubkey, value, root_name, root_symbol, root_decimals, eth_root \
+            FROM deposits WHERE from_block = ?1 AND to_hash = ?2 ORDER BY position",
+        )?;
+        let rows = statement.query_map(range, |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, String>(4)?,
+                row.get::<_, u8>(5)?,
+                row.get::<_, String>(6)?,
+            ))
+        })?;
+        let mut deposits = vec![];
+        for row in rows {
+            let (hash, pubkey, value, name, symbol, decimals, eth_root) = row?;
+            deposits.push(TransferPatch {
+                root: RootData {
+                    name,
+                    symbol,
+                    decimals,
+                    eth_root: parse::<Address>(eth_root.trim_start_matches("0x"), "token root")?,
+                },
+                data: Transfer {
+                    pubkey,
+                    value: parse(&value, "deposit value")?,
+                    hash,
+                },
+            });
+        }
+        Ok(Some(deposits))
+    }
+
+    // Burns found in the receiver transactions for the masterchain seq no range
+    pub fn save_burns(
+        &self,
+        checker: &str,
+        start_seq_no: u128,
+        end_seq_no: u128,
+        burns: &[Burn],
+    ) -> anyhow::Result<()> {
+        let (start, end) = (start_seq_no.to_string(), end_seq_no.to_string());
+        let mut connection = self.connection.lock();
+        let tx = connection.transaction()?;
+        tx.execute(
+            "DELETE FROM burns WHERE checker = ?1 AND start_seq_no = ?2 AND end_seq_no = ?3",
+            params![checker, start, end],
+        )?;
+        for (position, burn) in burns.iter().enumerate() {
+            tx.execute(
+                "INSERT INTO burns (checker, start_seq_no, end_seq_no, position, tx_id, dest, \
+                value, eth_root) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    checker,
+                    start,
+                    end,
+                    position,
+                    burn.tx_id,
+                    burn.dest,
+                    burn.value.to_string(),
+                    burn.eth_root,
+                ],
+            )?;
+        }
+        tx.execute(
+            "INSERT OR REPLACE INTO burn_scans (checker, start_seq_no, end_seq_no, created_at) \
+            VALUES (?1, ?2, ?3, ?4)",
+            params![checker, start, end, now()],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    // Find the longest saved scan starting at `start_seq_no` and ending not later than
+    // `end_seq_no`. Returns end of the found scan and its burns.
+    pub fn burns(
+        &self,
+        checker: &str,
+        start_seq_no: u128,
+        end_seq_no: u128,
+    ) -> anyhow::Result<Option<(u128, Vec<Burn>)>> {
+        let connection = self.connection.lock();
+        let mut statement = connection.prepare(
+            "SELECT end_seq_no FROM burn_scans WHERE checker = ?1 AND start_seq_no = ?2",
+        )?;
+        let scanned_end = statement
+            .query_map(params![checker, start_seq_no.to_string()], |row| {
+                row.get::<_, String>(0)
+            })?
+            .collect::<Result<Vec<String>, _>>()?
+            .into_iter()
+            .map(|end| parse::<u128>(&end, "seq no"))
+            .collect::<anyhow::Result<Vec<u128>>>()?
+            .into_iter()
+            .filter(|end| *end <= end_seq_no)
+            .max();
+        let scanned_end = match scanned_end {
+            Some(end) => end,
+            None => return Ok(None),
+        };
+
+        let mut statement = connection.prepare(
+            "SELECT tx_id, dest, value, eth_root FROM burns \
+            WHERE checker = ?1 AND start_seq_no = ?2 AND end_seq_no = ?3 ORDER BY position",
+        )?;
+        let rows = statement.query_map(
+            params![checker, start_seq_no.to_string(), scanned_end.to_string()],
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, String>(3)?,
+                ))
+            },
+        )?;
+        let mut burns = vec![];
+        for row in rows {
+            let (tx_id, dest, value, eth_root) = row?;
+            burns.push(Burn {
+                dest,
+                value: parse(&value, "burn value")?,
+                tx_id,
+                eth_root,
+            });
+        }
+        Ok(Some((scanned_end, burns)))
+    }
+
+    pub fn save_proposal(&self, proposal: &ProposalRecord) -> anyhow::Result<()> {
+        tracing::info!("Save proposal: {proposal:?}");
+        self.connection.lock().execute(
+            "INSERT INTO proposals (kind, from_block, till_block, transfers, tx_id, created_at) \
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                proposal.kind.as_str(),
+                proposal.from_block,
+                proposal.till_block,
+                proposal.transfers,
+                proposal.tx_id,
+                now()
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn proposals(&self, kind: ProposalKind) -> anyhow::Result<Vec<ProposalRecord>> {
+        let connection = self.connection.lock();
+        let mut statement = connection.prepare(
+            "SELECT from_block, till_block, transfers, tx_id FROM proposals \
+            WHERE kind = ?1 ORDER BY id",
+        )?;
+        let rows = statement.query_map(params![kind.as_str()], |row| {
+            Ok(ProposalRecord {
+                kind,
+                from_block: row.get(0)?,
+                till_block: row.get(1)?,
+                transfers: row.get(2)?,
+                tx_id: row.get(3)?,
+            })
+        })?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    // Number of the last ETH block when this node saw the proposal for the first time. The
+    // proposal is saved with `last_block` if it is seen for the first time.
+    pub fn proposal_seen_at(&self, address: &str, last_block: u64) -> anyhow::Result<u64> {
+        let connection = self.connection.lock();
+        connection.execute(
+            "INSERT OR IGNORE INTO seen_proposals (address, eth_block, created_at) \
+            VALUES (?1, ?2, ?3)",
+            params![address, last_block, now()],
+        )?;
+        Ok(connection.query_row(
+            "SELECT eth_block FROM seen_proposals WHERE address = ?1",
+            params![address],
+            |row| row.get(0),
+        )?)
+    }
+
+    pub fn save_vote(
+        &self,
+        kind: ProposalKind,
+        proposal: &str,
+        voter: &str,
+        tx_id: &str,
+    ) -> anyhow::Result<()> {
+        tracing::info!(
+            "Save {} vote of {voter} for {proposal}: {tx_id}",
+            kind.as_str()
+        );
+        self.connection.lock().execute(
+            "INSERT OR REPLACE INTO votes (kind, proposal, voter, tx_id, created_at) \
+            VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![kind.as_str(), proposal, voter, tx_id, now()],
+        )?;
+        Ok(())
+    }
+
+    // Id of the transaction with vote of `voter` for the proposal, if it was sent by this node
+    pub fn vote(
+        &self,
+        kind: ProposalKind,
+        proposal: &str,
+        voter: &str,
+    ) -> anyhow::Result<Option<String>> {
+        Ok(self
+            .connection
+            .lock()
+            .query_row(
+                "SELECT tx_id FROM votes WHERE kind = ?1 AND proposal = ?2 AND voter = ?3",
+                params![kind.as_str(), proposal, voter],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    pub fn save_alert_state(&self, name: &str, state: &AlertState) -> anyhow::Result<()> {
+        self.connection.lock().execute(
+            "INSERT OR REPLACE INTO alerts (name, firing, observation, runs, updated_at) \
+            VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![name, state.firing, state.observation, state.runs, now()],
+        )?;
+        Ok(())
+    }
+
+    pub fn alert_state(&self, name: &str) -> anyhow::Result<Option<AlertState>> {
+        Ok(self
+            .connection
+            .lock()
+            .query_row(
+                "SELECT firing, observation, runs FROM alerts WHERE name = ?1",
+                params![name],
+                |row| {
+                    Ok(AlertState {
+                        firing: row.get(0)?,
+                        observation: row.get(1)?,
+                        runs: row.get(2)?,
+                    })
+                },
+            )
+            .optional()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AlertState, ProposalKind, ProposalRecord, Store};
+    use crate::elock::transfer::{Transfer, TransferPatch};
+    use crate::eth::FullBlock;
+    use crate::gosh::burn::Burn;
+    use crate::token_root::eth::get_geth_root_data;
+    use web3::types::{H256, U64};
+
+    fn burn(tx_id: &str) -> Burn {
+        Burn {
+            dest: "0x0000000000000000000000000000000000000001".to_string(),
+            value: u128::MAX,
+            tx_id: tx_id.to_string(),
+            eth_root: "0x0000000000000000000000000000000000000000".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_cache_roundtrip() -> anyhow::Result<()> {
+        let store = Store::in_memory()?;
+
+        let block = FullBlock {
+            hash: Some(H256::from_low_u64_be(1)),
+            number: Some(U64::from(10)),
+            ..Default::default()
+        };
+        store.save_eth_block(&block)?;
+        assert_eq!(store.eth_block(H256::from_low_u64_be(1))?, Some(block));
+        assert_eq!(store.eth_block(H256::from_low_u64_be(2))?, None);
+
+        let deposits = vec![TransferPatch {
+            root: get_geth_root_data(),
+            data: Transfer {
+                pubkey: "0x01".to_string(),
+                value: 100,
+                hash: "0x02".to_string(),
+            },
+        }];
+        let to_hash = H256::from_low_u64_be(5);
+        assert_eq!(store.deposits(U64::from(1), to_hash)?, None);
+        store.save_deposits(U64::from(1), U64::from(5), to_hash, &deposits)?;
+        assert_eq!(store.deposits(U64::from(1), to_hash)?, Some(deposits));
+        // Range ending with another block at the same height is not cached
+        assert_eq!(
+            store.deposits(U64::from(1), H256::from_low_u64_be(6))?,
+            None
+        );
+
+        store.save_burns("0:01", 10, 20, &[burn("aa")])?;
+        store.save_burns("0:01", 10, 30, &[burn("aa"), burn("bb")])?;
+        assert_eq!(store.burns("0:01", 10, 25)?, Some((20, vec![burn("aa")])));
+        assert_eq!(store.burns("0:01", 10, 30)?.map(|(end, _)| end), Some(30));
+        assert_eq!(store.burns("0:01", 10, 15)?, None);
+        assert_eq!(store.burns("0:02", 10, 30)?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_audit_trail() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("bridge.sqlite");
+        {
+            let store = Store::open(&path)?;
+            store.save_proposal(&ProposalRecord {
+                kind: ProposalKind::Withdrawal,
+                from_block: "aa".to_string(),
+                till_block: "bb".to_string(),
+                transfers: 2,
+                tx_id: "0x01".to_string(),
+            })?;
+            store.save_vote(ProposalKind::Deposit, "0:01", "0x02", "cc")?;
+            store.save_alert_state(
+                "eth_block_lag",
+                &AlertState {
+                    firing: true,
+                    observation: "120".to_string(),
+                    runs: 2,
+                },
+            )?;
+        }
+        let store = Store::open(&path)?;
+        assert_eq!(store.proposals(ProposalKind::Withdrawal)?.len(), 1);
+        assert!(store.proposals(ProposalKind::Deposit)?.is_empty());
+        assert_eq!(
+            store.vote(ProposalKind::Deposit, "0:01", "0x02")?,
+            Some("cc".to_string())
+        );
+        assert_eq!(store.vote(ProposalKind::Withdrawal, "0:01", "0x02")?, None);
+        assert_eq!(
+            store.alert_state("eth_block_lag")?.map(|state| state.runs),
+            Some(2)
+        );
+        assert_eq!(store.alert_state("supply_mismatch")?, None);
+
+        // Proposal keeps the block it was seen on first
+        assert_eq!(store.proposal_seen_at("0:03", 105)?, 105);
+        assert_eq!(store.proposal_seen_at("0:03", 111)?, 105);
+        Ok(())
+    }
+}
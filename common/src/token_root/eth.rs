@@ -1,9 +1,8 @@
+use crate::chain::EthChain;
 use crate::helper::abi::ERC20_ABI;
 use crate::token_root::RootData;
-use web3::contract::{Contract, Options};
-use web3::transports::WebSocket;
+use web3::ethabi::Token;
 use web3::types::Address;
-use web3::Web3;
 
 // Wrapped GOSH ETH token data:
 pub const GETH_NAME: &str = "geth";
@@ -20,24 +19,41 @@ pub fn get_geth_root_data() -> RootData {
     }
 }
 
-pub async fn get_root_data(web3s: &Web3<WebSocket>, address: Address) -> anyhow::Result<RootData> {
+async fn query_root(
+    web3s: &dyn EthChain,
+    address: Address,
+    function: &str,
+) -> anyhow::Result<Token> {
+    web3s
+        .query(address, ERC20_ABI, function, vec![])
+        .await?
+        .into_iter()
+        .next()
+        .ok_or(anyhow::format_err!(
+            "Empty output of token root getter {function}"
+        ))
+}
+
+pub async fn get_root_data(web3s: &dyn EthChain, address: Address) -> anyhow::Result<RootData> {
     if address.is_zero() {
         return Ok(get_geth_root_data());
     }
-    let root_abi = web3::ethabi::Contract::load(ERC20_ABI.as_bytes())?;
-    let root_contract = Contract::new(web3s.eth(), address, root_abi);
 
-    let name: String = root_contract
-        .query("name", (), None, Options::default(), None)
-        .await?;
+    let name = query_root(web3s, address, "name")
+        .await?
+        .into_string()
+        .ok_or(anyhow::format_err!("Wrong token root name"))?;
 
-    let symbol: String = root_contract
-        .query("symbol", (), None, Options::default(), None)
-        .await?;
+    let symbol = query_root(web3s, address, "symbol")
+        .await?
+        .into_string()
+        .ok_or(anyhow::format_err!("Wrong token root symbol"))?;
 
-    let decimals: u8 = root_contract
-        .query("decimals", (), None, Options::default(), None)
-        .await?;
+    let decimals = query_root(web3s, address, "decimals")
+        .await?
+        .into_uint()
+        .ok_or(anyhow::format_err!("Wrong token root decimals"))?
+        .as_u32() as u8;
 
     Ok(RootData {
         name,
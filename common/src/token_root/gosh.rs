@@ -1,4 +1,5 @@
-use crate::gosh::helper::EverClient;
+use crate::chain::GoshChain;
+use crate::error::BridgeError;
 use crate::gosh::{call_function, call_getter};
 use crate::helper::abi::CHECKER_ABI;
 use crate::helper::{
@@ -118,7 +119,7 @@ struct WalletDetails {
 }
 
 pub async fn get_root_owner_address(
-    gosh_context: &EverClient,
+    gosh_context: &dyn GoshChain,
     root_address: &str,
 ) -> anyhow::Result<String> {
     let root_owner_key: GetRootPubkeyResult =
@@ -140,7 +141,7 @@ pub async fn get_root_owner_address(
 }
 
 pub async fn get_wallet_balance(
-    gosh_context: &EverClient,
+    gosh_context: &dyn GoshChain,
     wallet_address: &str,
 ) -> anyhow::Result<u128> {
     let details: WalletDetails = call_getter(
@@ -155,7 +156,7 @@ pub async fn get_wallet_balance(
 }
 
 pub async fn get_root_owner_balance(
-    gosh_context: &EverClient,
+    gosh_context: &dyn GoshChain,
     root_address: &str,
 ) -> anyhow::Result<u128> {
     let wallet_address = get_root_owner_address(gosh_context, root_address).await?;
@@ -163,7 +164,7 @@ pub async fn get_root_owner_balance(
 }
 
 pub async fn get_root_address(
-    gosh_context: &EverClient,
+    gosh_context: &dyn GoshChain,
     checker_address: &str,
     root_data: &RootData,
 ) -> anyhow::Result<String> {
@@ -179,8 +180,37 @@ pub async fn get_root_address(
     Ok(root.address)
 }
 
+// Account types of GraphQL 'acc_type' field
+const ACCOUNT_ACTIVE: u8 = 1;
+
+#[derive(Deserialize)]
+struct AccountInfo {
+    acc_type: u8,
+}
+
+async fn is_account_active(gosh_context: &dyn GoshChain, address: &str) -> anyhow::Result<bool> {
+    let query = r#"query($address: String!){
+        blockchain {
+            account(address: $address) {
+                info { acc_type }
+            }
+        }
+    }"#;
+    let result = gosh_context
+        .query(query, Some(json!({ "address": address })))
+        .await?;
+    let info = &result["data"]["blockchain"]["account"]["info"];
+    if info.is_null() {
+        return Ok(false);
+    }
+    let info: AccountInfo = serde_json::from_value(info.clone()).map_err(|e| {
+        BridgeError::Decode(format!("Failed to decode account {address} info: {e}"))
+    })?;
+    Ok(info.acc_type == ACCOUNT_ACTIVE)
+}
+
 pub async fn is_root_active(
-    gosh_context: &EverClient,
+    gosh_context: &dyn GoshChain,
     checker_address: &str,
     root_data: &RootData,
 ) -> anyhow::Result<bool> {
@@ -189,22 +219,32 @@ pub async fn is_root_active(
     let res: anyhow::Result<GetNameResult> =
         call_getter(gosh_context, &root_address, ROOT_ABI, "getName", None).await;
     match res {
-        Err(e) => {
-            tracing::info!("Failed to call root getter: {e}");
+        // Only missing root account means that the root is not deployed, other errors must not
+        // cause its redeployment
+        Err(e) if BridgeError::is_not_found(&e) => {
+            tracing::info!("Root is not deployed: {e}");
             Ok(false)
         }
-        Ok(res) => {
-            assert_eq!(
-                res.name, root_data.name,
-                "Root contract name getter does not match expected"
-            );
-            Ok(true)
+        // Uninit and frozen accounts exist and have boc, but their getters fail to run
+        Err(e) if matches!(BridgeError::find(&e), Some(BridgeError::ContractRevert(_))) => {
+            if is_account_active(gosh_context, &root_address).await? {
+                return Err(e);
+            }
+            tracing::info!("Root account {root_address} is not active: {e}");
+            Ok(false)
         }
+        Err(e) => Err(e),
+        Ok(res) if res.name != root_data.name => Err(BridgeError::ValidationMismatch(format!(
+            "Root contract name getter does not match expected: {} != {}",
+            res.name, root_data.name
+        ))
+        .into()),
+        Ok(_) => Ok(true),
     }
 }
 
 pub async fn deploy_root(
-    gosh_context: &EverClient,
+    gosh_context: &dyn GoshChain,
     checker_address: &str,
     root_data: &RootData,
 ) -> anyhow::Result<()> {
@@ -228,11 +268,12 @@ pub async fn deploy_root(
             }
         })),
     )
-    .await
+    .await?;
+    Ok(())
 }
 
 pub async fn get_root_total_supply(
-    gosh_context: &EverClient,
+    gosh_context: &dyn GoshChain,
     root_address: &str,
 ) -> anyhow::Result<u128> {
     let res: GetTotalSupplyResult = call_getter(
@@ -243,4 +284,86 @@ pub async fn get_root_total_supply(
         None,
     ).await?;
     Ok(res.value)
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_root_active;
+    use crate::chain::fake::FakeGosh;
+    use crate::error::BridgeError;
+    use crate::token_root::RootData;
+    use serde_json::{json, Value};
+    use web3::types::Address;
+
+    const CHECKER_ADDRESS: &str = "0:01";
+    const ROOT_ADDRESS: &str = "0:02";
+
+    #[tokio::test]
+    async fn test_is_root_active() -> anyhow::Result<()> {
+        let root = RootData {
+            name: "Tether USD".to_string(),
+            symbol: "USDT".to_string(),
+            decimals: 6,
+            eth_root: Address::from_low_u64_be(1),
+        };
+        let context_with_root = |name_result: Option<Value>| {
+            let context = FakeGosh::new();
+            context.set_getter(
+                CHECKER_ADDRESS,
+                "getRootAddr",
+                None,
+                json!({ "value0": ROOT_ADDRESS }),
+            );
+            if let Some(result) = name_result {
+                context.set_getter(ROOT_ADDRESS, "getName", None, result);
+            }
+            context
+        };
+        let context = context_with_root(None);
+        assert!(!is_root_active(&context, CHECKER_ADDRESS, &root).await?);
+
+        // Unexpected getter output must not be treated as missing root
+        let context = context_with_root(Some(json!({})));
+        let err = is_root_active(&context, CHECKER_ADDRESS, &root)
+            .await
+            .unwrap_err();
+        assert!(matches!(BridgeError::find(&err), Some(BridgeError::Decode(_))));
+
+        let context = context_with_root(Some(json!({ "value0": "Other" })));
+        let err = is_root_active(&context, CHECKER_ADDRESS, &root)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            BridgeError::find(&err),
+            Some(BridgeError::ValidationMismatch(_))
+        ));
+
+        let context = context_with_root(Some(json!({ "value0": "Tether USD" })));
+        assert!(is_root_active(&context, CHECKER_ADDRESS, &root).await?);
+
+        // Getter of uninit account fails in TVM, the root is deployed again
+        let account_info = |acc_type: u8| {
+            let context = context_with_root(None);
+            context.set_getter_revert(ROOT_ADDRESS, "getName");
+            context.set_query(
+                "account(",
+                Some(json!({ "address": ROOT_ADDRESS })),
+                json!({"data": {"blockchain": {"account": {"info": {"acc_type": acc_type}}}}}),
+            );
+            context
+        };
+        let context = account_info(0);
+        assert!(!is_root_active(&context, CHECKER_ADDRESS, &root).await?);
+
+        // Failed getter of active root is an error
+        let context = account_info(1);
+        let err = is_root_active(&context, CHECKER_ADDRESS, &root)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            BridgeError::find(&err),
+            Some(BridgeError::ContractRevert(_))
+        ));
+        Ok(())
+    }
+}
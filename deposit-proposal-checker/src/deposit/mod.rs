@@ -1,47 +1,88 @@
-use common::eth::create_web3_socket;
-use common::gosh::helper::{create_client, load_keys};
-use std::env;
+use common::checker::find_proposals;
+use common::config::BridgeConfig;
+use common::dry_run::DryRun;
+use common::eth::create_eth_client;
+use common::gosh::helper::{create_client, get_validator_keys};
+use common::outcome::Outcome;
+use common::retry::Retry;
+use common::store::{ProposalKind, Store};
 
-use proposal::{approve_proposal, find_proposals};
+use proposal::{approve_proposal, get_validator_id};
 use validate::validate_proposal;
 
 mod proposal;
 mod validate;
 
-pub async fn check_proposals() -> anyhow::Result<()> {
+pub async fn check_proposals(config: &BridgeConfig) -> anyhow::Result<Outcome> {
     // Create client for GOSH
-    let gosh_client = create_client()?;
+    let gosh_client = Retry::new(create_client(&config.gosh)?, &config.retry);
 
-    // Load validator key
-    let key_path = env::var("VALIDATORS_KEY_PATH")
-        .map_err(|e| anyhow::format_err!("Failed to get end VALIDATORS_KEY_PATH : {e}"))?;
-    let keys = load_keys(&key_path)
-        .map_err(|e| anyhow::format_err!("Failed to load validator GOSH keys: {e}"))?;
-    let pubkey = format!("0x{}", keys.public);
-    let keys = Some(keys);
+    // Load validator keys
+    let keys = get_validator_keys(gosh_client.inner(), &config.gosh)?;
 
     // Find proposals in GOSH
-    let proposals = find_proposals(&gosh_client, pubkey).await?;
+    let proposals = find_proposals(&gosh_client, &config.gosh.checker_address).await?;
+    if proposals.is_empty() {
+        return Ok(Outcome::NothingToDo);
+    }
 
     // Create client for ETH
-    let web3s = create_web3_socket().await?;
+    let web3s = create_eth_client(&config.eth, &config.retry).await?;
+
+    // Open local store with votes sent before
+    let store = Store::from_config(&config.store)?;
+
+    // With `--dry-run` votes are printed instead of being sent
+    let sender = DryRun::new(&gosh_client, config.dry_run);
+
+    let mut voted = vec![];
+    let mut failures = vec![];
 
     // Iterate through the proposals list and check whether it is valid
     for proposal in proposals {
         let address = proposal.address.clone();
-        let id = proposal.validator_id.clone();
-        match validate_proposal(&web3s, proposal).await {
-            // If proposal is valid, approve it
-            Ok(()) => match approve_proposal(&gosh_client, address, &id, keys.clone()).await {
-                Ok(()) => {}
+
+        // Get ids of all our validators in the proposal
+        let mut voters = vec![];
+        for key in &keys {
+            let pubkey = format!("0x{}", key.public);
+            if let Some(tx_id) = store.vote(ProposalKind::Deposit, &address, &pubkey)? {
+                tracing::info!("Already voted for proposal {address} with {pubkey}: {tx_id}");
+                continue;
+            }
+            match get_validator_id(&gosh_client, &address, &pubkey).await {
+                Ok(id) => voters.push((id, key, pubkey)),
+                Err(e) => {
+                    tracing::info!(
+                        "Failed to query validator id from proposal {address} for {pubkey}: {e}"
+                    );
+                }
+            }
+        }
+        if voters.is_empty() {
+            continue;
+        }
+
+        // Proposal is validated once and approved with every key
+        if let Err(e) = validate_proposal(&web3s, config.eth.elock_address, &proposal).await {
+            tracing::info!("Proposal {} validation failed: {e}", address);
+            failures.push(format!("{address}: {e}"));
+            continue;
+        }
+        for (id, key, pubkey) in voters {
+            match approve_proposal(&sender, address.clone(), &id, Some(key.clone())).await {
+                Ok(tx_id) => {
+                    store.save_vote(ProposalKind::Deposit, &address, &pubkey, &tx_id)?;
+                    if !voted.contains(&address) {
+                        voted.push(address.clone());
+                    }
+                }
                 Err(e) => {
                     tracing::info!("Proposal approval failed: {e}");
+                    failures.push(format!("{address}: {e}"));
                 }
-            },
-            Err(e) => {
-                tracing::info!("Proposal {} validation failed: {e}", address);
             }
         }
     }
-    Ok(())
+    Ok(Outcome::from_votes(voted, failures))
 }
@@ -1,15 +1,18 @@
 use std::str::FromStr;
 
-use crate::deposit::proposal::Proposal;
+use common::chain::EthChain;
+use common::checker::Proposal;
 use common::elock::deposit::get_deposits;
-use common::elock::{get_elock_address, get_tx_counter};
+use common::elock::get_tx_counter;
 use common::eth::read_block as eth_read_block;
 use common::eth::FullBlock;
-use web3::transports::WebSocket;
-use web3::types::{BlockId, H256};
-use web3::Web3;
+use web3::types::{Address, BlockId, H256};
 
-pub async fn validate_proposal(web3s: &Web3<WebSocket>, proposal: Proposal) -> anyhow::Result<()> {
+pub async fn validate_proposal(
+    web3s: &dyn EthChain,
+    elock_address: Address,
+    proposal: &Proposal,
+) -> anyhow::Result<()> {
     tracing::info!("Validate proposal: {proposal:?}");
     let from_block = BlockId::Hash(
         H256::from_str(&proposal.details.hash)
@@ -19,8 +22,7 @@ pub async fn validate_proposal(web3s: &Web3<WebSocket>, proposal: Proposal) -> a
         H256::from_str(&proposal.details.new_hash)
             .map_err(|e| anyhow::format_err!("Failed to convert proposal from block: {e}"))?,
     );
-    let verifying_transfers = proposal.details.transactions;
-    let elock_address = get_elock_address()?;
+    let verifying_transfers = &proposal.details.transactions;
 
     // Get block numbers for block range from proposal and query tx counters on this blocks
     let from_block_num = {
@@ -65,9 +67,61 @@ pub async fn validate_proposal(web3s: &Web3<WebSocket>, proposal: Proposal) -> a
     // Get real deposits and compare them to transfers from proposal
     let actual_deposits =
         get_deposits(web3s, elock_address, from_block_num, till_block_num).await?;
-    if actual_deposits != verifying_transfers {
+    if &actual_deposits != verifying_transfers {
         anyhow::bail!("Actual transfers do not match proposed: {actual_deposits:?} != {verifying_transfers:?}");
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::validate_proposal;
+    use common::chain::fake::FakeEth;
+    use common::checker::{Proposal, ProposalDetails};
+    use common::elock::COUNTERS_INDEX;
+    use common::eth::FullBlock;
+    use web3::types::{Address, H256, U256, U64};
+
+    fn setup(start_counter: u64, end_counter: u64) -> (FakeEth, Proposal) {
+        let elock_address = Address::from_low_u64_be(1);
+        let web3s = FakeEth::new();
+        for (num, counter) in [(10, start_counter), (12, end_counter)] {
+            web3s.add_block(FullBlock {
+                hash: Some(H256::from_low_u64_be(num)),
+                number: Some(U64::from(num)),
+                ..Default::default()
+            });
+            web3s.set_storage(
+                elock_address,
+                U256::from(COUNTERS_INDEX),
+                Some(U64::from(num)),
+                H256::from_low_u64_be(counter),
+            );
+        }
+        let proposal = Proposal {
+            address: "0:01".to_string(),
+            details: ProposalDetails {
+                hash: format!("{:?}", H256::from_low_u64_be(10)),
+                new_hash: format!("{:?}", H256::from_low_u64_be(12)),
+                transactions: vec![],
+                index: 0,
+                need: 1,
+            },
+        };
+        (web3s, proposal)
+    }
+
+    #[tokio::test]
+    async fn test_validate_empty_proposal() -> anyhow::Result<()> {
+        let (web3s, proposal) = setup(5, 5);
+        validate_proposal(&web3s, Address::from_low_u64_be(1), &proposal).await
+    }
+
+    #[tokio::test]
+    async fn test_validate_counter_mismatch() {
+        let (web3s, proposal) = setup(5, 6);
+        let res = validate_proposal(&web3s, Address::from_low_u64_be(1), &proposal).await;
+        assert!(res.is_err());
+    }
+}
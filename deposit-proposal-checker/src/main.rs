@@ -1,15 +1,24 @@
+use common::config::BridgeConfig;
+use common::helper::take_dry_run_flag;
 use common::helper::tracing::init_default_tracing;
-
-mod deposit;
-
-use crate::deposit::check_proposals;
+use deposit_proposal_checker::deposit::check_proposals;
+use std::env;
+use std::process::ExitCode;
 
 #[tokio::main]
-async fn main() -> anyhow::Result<()> {
+async fn main() -> anyhow::Result<ExitCode> {
     // Load env variables from '.env' file
     dotenv::dotenv().ok();
     // Init tracing in level specified with env 'GOSH_LOG' or "info" level by default
     init_default_tracing();
+    // Load and validate config
+    let mut config = BridgeConfig::load()?;
+    // With `--dry-run` votes are printed instead of being sent
+    let mut args: Vec<String> = env::args().collect();
+    take_dry_run_flag(&mut config, &mut args);
+    if let Some(arg) = args.get(1) {
+        anyhow::bail!("Unknown argument: {arg}");
+    }
     // Find existing proposals and check them
-    check_proposals().await
+    check_proposals(&config).await?.report()
 }
@@ -0,0 +1,393 @@
+use crate::elock::Elock;
+use common::eth::FullBlock;
+use common::token_root::RootData;
+use parking_lot::Mutex;
+use rlp::RlpStream;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use web3::signing::{keccak256, Key, SecretKey, SecretKeyRef};
+use web3::types::{Address, Bytes, Log, TransactionReceipt, H2048, H256, H64, U256, U64};
+
+const DEFAULT_CHAIN_ID: u64 = 11155111;
+const BLOCK_TIME: u64 = 12;
+const GENESIS_TIMESTAMP: u64 = 1_700_000_000;
+const BLOCK_GAS_LIMIT: u64 = 30_000_000;
+const BASE_FEE_PER_GAS: u64 = 7;
+// Root of an empty trie
+const EMPTY_ROOT: &str = "56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421";
+
+// Log which will be included into the next mined block
+struct PendingLog {
+    address: Address,
+    topics: Vec<H256>,
+    data: Vec<u8>,
+    tx_hash: Option<H256>,
+}
+
+pub(crate) struct ChainState {
+    pub chain_id: u64,
+    pub gas_price: U256,
+    finality_depth: u64,
+    blocks: Vec<FullBlock<H256>>,
+    // Storage values history: (address, slot) -> block number -> value
+    storage: HashMap<(Address, U256), BTreeMap<u64, H256>>,
+    balances: HashMap<Address, U256>,
+    nonces: HashMap<Address, U256>,
+    logs: Vec<Value>,
+    pending_logs: Vec<PendingLog>,
+    pending_txs: Vec<TransactionReceipt>,
+    receipts: HashMap<H256, TransactionReceipt>,
+    // Block filter id -> number of the next block to report
+    filters: HashMap<U256, u64>,
+    next_filter_id: u64,
+    // Keys of accounts signing transactions with 'eth_signTransaction', so the mock can stand in
+    // for a remote signer
+    pub signer_keys: HashMap<Address, SecretKey>,
+    pub elock: Elock,
+}
+
+// Scripted ETH chain with simulated ELock contract. Handle is cheap to clone, all clones share the
+// same chain, so a test can keep changing the chain while the server is running.
+#[derive(Clone)]
+pub struct MockChain {
+    pub(crate) state: Arc<Mutex<ChainState>>,
+}
+
+fn header_hash(block: &FullBlock<H256>) -> H256 {
+    let mut stream = RlpStream::new_list(17);
+    stream.append(&block.parent_hash);
+    stream.append(&block.uncles_hash);
+    stream.append(&block.author);
+    stream.append(&block.state_root);
+    stream.append(&block.transactions_root);
+    stream.append(&block.receipts_root);
+    stream.append(&block.logs_bloom.unwrap_or_default());
+    stream.append(&block.difficulty);
+    stream.append(&block.number.unwrap_or_default());
+    stream.append(&block.gas_limit);
+    stream.append(&block.gas_used);
+    stream.append(&block.timestamp);
+    stream.append(&block.extra_data.0);
+    stream.append(&block.mix_hash.unwrap_or_default());
+    stream.append(&block.nonce.unwrap_or_default());
+    stream.append(&block.base_fee_per_gas.unwrap_or_default());
+    stream.append(&block.withdrawals_root.unwrap_or_default());
+    H256::from(keccak256(stream.as_raw()))
+}
+
+fn new_block(parent: Option<&FullBlock<H256>>, transactions: Vec<H256>) -> FullBlock<H256> {
+    let number = parent.and_then(|p| p.number).map_or(0, |n| n.as_u64() + 1);
+    let empty_root: H256 = EMPTY_ROOT.parse().unwrap();
+    let mut block = FullBlock {
+        hash: None,
+        parent_hash: parent.and_then(|p| p.hash).unwrap_or_default(),
+        uncles_hash: H256::from(keccak256(&rlp::EMPTY_LIST_RLP)),
+        state_root: H256::from(keccak256(&number.to_be_bytes())),
+        transactions_root: empty_root,
+        receipts_root: empty_root,
+        number: Some(U64::from(number)),
+        gas_limit: U256::from(BLOCK_GAS_LIMIT),
+        base_fee_per_gas: Some(U256::from(BASE_FEE_PER_GAS)),
+        extra_data: Bytes(b"eth-mock".to_vec()),
+        logs_bloom: Some(H2048::zero()),
+        timestamp: U256::from(GENESIS_TIMESTAMP + number * BLOCK_TIME),
+        total_difficulty: Some(U256::zero()),
+        transactions,
+        size: Some(U256::zero()),
+        mix_hash: Some(H256::zero()),
+        nonce: Some(H64::zero()),
+        withdrawals_root: Some(empty_root),
+        ..Default::default()
+    };
+    block.hash = Some(header_hash(&block));
+    block
+}
+
+impl ChainState {
+    pub fn head_number(&self) -> u64 {
+        self.blocks.len() as u64 - 1
+    }
+
+    pub fn finalized_number(&self) -> u64 {
+        self.head_number().saturating_sub(self.finality_depth)
+    }
+
+    pub fn block_by_number(&self, number: u64) -> Option<&FullBlock<H256>> {
+        self.blocks.get(number as usize)
+    }
+
+    pub fn block_by_hash(&self, hash: H256) -> Option<&FullBlock<H256>> {
+        self.blocks.iter().find(|block| block.hash == Some(hash))
+    }
+
+    // Value is written to the pending block and becomes visible after it is mined
+    pub fn set_storage(&mut self, address: Address, slot: U256, value: H256) {
+        let pending = self.head_number() + 1;
+        self.storage
+            .entry((address, slot))
+            .or_default()
+            .insert(pending, value);
+    }
+
+    pub fn storage_at(&self, address: Address, slot: U256, block: u64) -> H256 {
+        self.storage
+            .get(&(address, slot))
+            .and_then(|history| history.range(..=block).next_back())
+            .map(|(_, value)| *value)
+            .unwrap_or_default()
+    }
+
+    pub fn balance(&self, address: Address) -> U256 {
+        self.balances.get(&address).cloned().unwrap_or_default()
+    }
+
+    pub fn add_balance(&mut self, address: Address, value: U256) {
+        *self.balances.entry(address).or_default() += value;
+    }
+
+    pub fn nonce(&self, address: Address) -> U256 {
+        self.nonces.get(&address).cloned().unwrap_or_default()
+    }
+
+    pub fn increase_nonce(&mut self, address: Address) {
+        *self.nonces.entry(address).or_default() += U256::one();
+    }
+
+    pub fn emit_log(
+        &mut self,
+        address: Address,
+        topics: Vec<H256>,
+        data: Vec<u8>,
+        tx_hash: Option<H256>,
+    ) {
+        self.pending_logs.push(PendingLog {
+            address,
+            topics,
+            data,
+            tx_hash,
+        });
+    }
+
+    pub fn add_transaction(&mut self, hash: H256, from: Address, to: Option<Address>) {
+        self.pending_txs.push(TransactionReceipt {
+            transaction_hash: hash,
+            from,
+            to,
+            cumulative_gas_used: U256::zero(),
+            gas_used: Some(U256::zero()),
+            status: Some(U64::one()),
+            transaction_type: Some(U64::from(2)),
+            effective_gas_price: Some(self.gas_price),
+            ..Default::default()
+        });
+    }
+
+    pub fn receipt(&self, hash: &H256) -> Option<&TransactionReceipt> {
+        self.receipts.get(hash)
+    }
+
+    pub fn logs(&self) -> &[Value] {
+        &self.logs
+    }
+
+    pub fn mine_block(&mut self) -> H256 {
+        let tx_hashes = self
+            .pending_txs
+            .iter()
+            .map(|tx| tx.transaction_hash)
+            .collect();
+        let block = new_block(self.blocks.last(), tx_hashes);
+        let hash = block.hash.unwrap();
+        let number = block.number.unwrap();
+
+        let first_log_index = self.logs.len();
+        for (index, log) in std::mem::take(&mut self.pending_logs)
+            .into_iter()
+            .enumerate()
+        {
+            // Logs emitted outside of transactions get a synthetic transaction hash
+            let tx_hash = log.tx_hash.unwrap_or_else(|| {
+                H256::from(keccak256(
+                    &[hash.as_bytes(), &(index as u64).to_be_bytes()].concat(),
+                ))
+            });
+            let log = Log {
+                address: log.address,
+                topics: log.topics,
+                data: Bytes(log.data),
+                block_hash: Some(hash),
+                block_number: Some(number),
+                transaction_hash: Some(tx_hash),
+                transaction_index: Some(U64::zero()),
+                log_index: Some(U256::from(first_log_index + index)),
+                transaction_log_index: None,
+                log_type: None,
+                removed: Some(false),
+            };
+            self.logs.push(json!(log));
+        }
+
+        for (index, mut receipt) in std::mem::take(&mut self.pending_txs)
+            .into_iter()
+            .enumerate()
+        {
+            receipt.block_hash = Some(hash);
+            receipt.block_number = Some(number);
+            receipt.transaction_index = U64::from(index);
+            self.receipts.insert(receipt.transaction_hash, receipt);
+        }
+
+        tracing::info!("Mined block {number}: {hash:?}");
+        self.blocks.push(block);
+        hash
+    }
+
+    pub fn new_block_filter(&mut self) -> U256 {
+        self.next_filter_id += 1;
+        let id = U256::from(self.next_filter_id);
+        let next_block = self.head_number() + 1;
+        self.filters.insert(id, next_block);
+        id
+    }
+
+    // Every poll of a block filter mines a new block, so confirmations of sent transactions
+    // are collected without manual mining
+    pub fn filter_changes(&mut self, id: U256) -> Option<Vec<H256>> {
+        let next_block = *self.filters.get(&id)?;
+        self.mine_block();
+        let head = self.head_number();
+        self.filters.insert(id, head + 1);
+        Some(
+            (next_block..=head)
+                .filter_map(|number| self.block_by_number(number).and_then(|b| b.hash))
+                .collect(),
+        )
+    }
+
+    pub fn uninstall_filter(&mut self, id: U256) -> bool {
+        self.filters.remove(&id).is_some()
+    }
+}
+
+impl MockChain {
+    // Create chain with the genesis block and ELock deployed at `elock_address`
+    pub fn new(elock_address: Address) -> Self {
+        let mut state = ChainState {
+            chain_id: DEFAULT_CHAIN_ID,
+            gas_price: U256::from(BASE_FEE_PER_GAS),
+            finality_depth: 0,
+            blocks: vec![],
+            storage: HashMap::new(),
+            balances: HashMap::new(),
+            nonces: HashMap::new(),
+            logs: vec![],
+            pending_logs: vec![],
+            pending_txs: vec![],
+            receipts: HashMap::new(),
+            filters: HashMap::new(),
+            next_filter_id: 0,
+            signer_keys: HashMap::new(),
+            elock: Elock::new(elock_address),
+        };
+        state.blocks.push(new_block(None, vec![]));
+        Self {
+            state: Arc::new(Mutex::new(state)),
+        }
+    }
+
+    pub fn elock_address(&self) -> Address {
+        self.state.lock().elock.address
+    }
+
+    pub fn set_chain_id(&self, chain_id: u64) {
+        self.state.lock().chain_id = chain_id;
+    }
+
+    // Number of blocks between the head and the 'finalized' block
+    pub fn set_finality_depth(&self, depth: u64) {
+        self.state.lock().finality_depth = depth;
+    }
+
+    pub fn set_gas_price(&self, gas_price: U256) {
+        self.state.lock().gas_price = gas_price;
+    }
+
+    pub fn set_balance(&self, address: Address, balance: U256) {
+        self.state.lock().balances.insert(address, balance);
+    }
+
+    pub fn add_signer_key(&self, key: SecretKey) {
+        let address = SecretKeyRef::new(&key).address();
+        self.state.lock().signer_keys.insert(address, key);
+    }
+
+    pub fn add_validator(&self, address: Address) {
+        self.state.lock().elock.validators.push(address);
+    }
+
+    // Register ERC20 token root, its metadata is served with `eth_call`
+    pub fn add_token_root(&self, root: RootData) {
+        self.state.lock().elock_add_root(root);
+    }
+
+    pub fn set_last_processed_l2_block(&self, block: U256) {
+        self.state.lock().elock_set_last_processed_l2_block(block);
+    }
+
+    // Deposit tokens to ELock, zero token address stands for ETH
+    pub fn deposit(&self, token: Address, from: Address, pubkey: U256, value: U256) {
+        self.state
+            .lock()
+            .elock_deposit(token, from, pubkey, value, None);
+    }
+
+    pub fn mine_block(&self) -> H256 {
+        self.state.lock().mine_block()
+    }
+
+    pub fn mine_blocks(&self, count: u64) -> H256 {
+        let mut state = self.state.lock();
+        let mut hash = state.blocks.last().and_then(|b| b.hash).unwrap_or_default();
+        for _ in 0..count {
+            hash = state.mine_block();
+        }
+        hash
+    }
+
+    pub fn head(&self) -> FullBlock<H256> {
+        self.state.lock().blocks.last().cloned().unwrap()
+    }
+
+    pub fn block(&self, number: u64) -> Option<FullBlock<H256>> {
+        self.state.lock().block_by_number(number).cloned()
+    }
+
+    pub fn deposit_count(&self) -> u128 {
+        self.state.lock().elock.deposit_count
+    }
+
+    pub fn withdraw_count(&self) -> u128 {
+        self.state.lock().elock.withdraw_count
+    }
+
+    pub fn proposal_keys(&self) -> Vec<U256> {
+        self.state
+            .lock()
+            .elock
+            .proposals
+            .iter()
+            .map(|(key, _)| *key)
+            .collect()
+    }
+
+    pub fn proposal_votes(&self, key: U256) -> usize {
+        self.state
+            .lock()
+            .elock
+            .proposals
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map_or(0, |(_, proposal)| proposal.votes.len())
+    }
+}
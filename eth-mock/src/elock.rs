@@ -0,0 +1,336 @@
+use crate::chain::ChainState;
+use crate::tx::RawTransaction;
+use common::elock::COUNTERS_INDEX;
+use common::helper::abi::{ELOCK_ABI, ERC20_ABI};
+use common::token_root::RootData;
+use std::collections::{HashMap, HashSet};
+use web3::ethabi::{Contract, Token};
+use web3::signing::keccak256;
+use web3::types::{Address, H256, U256};
+
+const LAST_PROCESSED_BLOCK_INDEX: u8 = 3;
+const VOTE_FOR_PROPOSAL_INDEX: u8 = 0x0d;
+
+pub(crate) struct Proposal {
+    pub from_block: U256,
+    pub till_block: U256,
+    pub transfers: Vec<Token>,
+    pub votes: HashSet<Address>,
+}
+
+// Simplified ELock state. Storage layout matches the real contract for the slots read by the
+// bridge: deposit and withdrawal counters in slot 1, last processed GOSH block in slot 3 and
+// validator votes in the proposal votes mapping.
+pub(crate) struct Elock {
+    pub address: Address,
+    pub validators: Vec<Address>,
+    pub roots: Vec<RootData>,
+    pub total_supplies: HashMap<Address, U256>,
+    pub deposit_count: u128,
+    pub withdraw_count: u128,
+    pub last_processed_l2_block: U256,
+    pub proposals: Vec<(U256, Proposal)>,
+}
+
+impl Elock {
+    pub fn new(address: Address) -> Self {
+        Self {
+            address,
+            validators: vec![],
+            roots: vec![],
+            total_supplies: HashMap::new(),
+            deposit_count: 0,
+            withdraw_count: 0,
+            last_processed_l2_block: U256::zero(),
+            proposals: vec![],
+        }
+    }
+
+    fn proposal(&self, key: U256) -> anyhow::Result<&Proposal> {
+        self.proposals
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, proposal)| proposal)
+            .ok_or(anyhow::format_err!(
+                "execution reverted: proposal {key} not found"
+            ))
+    }
+}
+
+fn load_abi(abi: &str) -> Contract {
+    Contract::load(abi.as_bytes()).expect("Failed to load contract abi")
+}
+
+fn event_signature(name: &str) -> H256 {
+    load_abi(ELOCK_ABI)
+        .event(name)
+        .map(|event| event.signature())
+        .expect("ELock event is missing in abi")
+}
+
+fn address_topic(address: Address) -> H256 {
+    let mut topic = H256::zero();
+    topic.as_bytes_mut()[12..].copy_from_slice(address.as_bytes());
+    topic
+}
+
+fn u256_to_h256(value: U256) -> H256 {
+    let mut res = H256::zero();
+    value.to_big_endian(res.as_bytes_mut());
+    res
+}
+
+// Storage slot of validator's vote: keccak256(validator . keccak256(proposal_key . 0x0d))
+fn vote_slot(proposal_key: U256, validator: Address) -> U256 {
+    let inner = keccak256(
+        &[
+            u256_to_h256(proposal_key).as_bytes(),
+            u256_to_h256(U256::from(VOTE_FOR_PROPOSAL_INDEX)).as_bytes(),
+        ]
+        .concat(),
+    );
+    let slot = keccak256(&[address_topic(validator).as_bytes(), &inner].concat());
+    U256::from_big_endian(&slot)
+}
+
+impl ChainState {
+    fn write_elock_counters(&mut self) {
+        let counters =
+            (U256::from(self.elock.withdraw_count) << 128) | U256::from(self.elock.deposit_count);
+        let address = self.elock.address;
+        self.set_storage(address, U256::from(COUNTERS_INDEX), u256_to_h256(counters));
+    }
+
+    pub fn elock_add_root(&mut self, root: RootData) {
+        self.elock.total_supplies.entry(root.eth_root).or_default();
+        self.elock.roots.push(root);
+    }
+
+    pub fn elock_set_last_processed_l2_block(&mut self, block: U256) {
+        self.elock.last_processed_l2_block = block;
+        let address = self.elock.address;
+        self.set_storage(
+            address,
+            U256::from(LAST_PROCESSED_BLOCK_INDEX),
+            u256_to_h256(block),
+        );
+    }
+
+    pub fn elock_deposit(
+        &mut self,
+        token: Address,
+        from: Address,
+        pubkey: U256,
+        value: U256,
+        tx_hash: Option<H256>,
+    ) {
+        tracing::info!("ELock deposit: token={token:?} from={from:?} value={value}");
+        self.elock.deposit_count += 1;
+        *self.elock.total_supplies.entry(token).or_default() += value;
+        self.write_elock_counters();
+
+        let data = web3::ethabi::encode(&[
+            Token::Address(from),
+            Token::Uint(pubkey),
+            Token::Uint(value),
+        ]);
+        let topics = vec![event_signature("Deposited"), address_topic(token)];
+        let address = self.elock.address;
+        self.emit_log(address, topics, data, tx_hash);
+    }
+
+    // Serve `eth_call` to ELock or to one of registered token roots
+    pub fn elock_call(&self, to: Address, data: &[u8]) -> anyhow::Result<Vec<u8>> {
+        if data.len() < 4 {
+            anyhow::bail!("execution reverted: call data is too short");
+        }
+        if to != self.elock.address {
+            return self.root_call(to, data);
+        }
+        let abi = load_abi(ELOCK_ABI);
+        let function = abi
+            .functions()
+            .find(|f| f.short_signature() == data[..4])
+            .ok_or(anyhow::format_err!(
+                "execution reverted: unknown ELock function"
+            ))?;
+        let inputs = function
+            .decode_input(&data[4..])
+            .map_err(|e| anyhow::format_err!("execution reverted: {e}"))?;
+        let elock = &self.elock;
+        let output = match (function.name.as_str(), inputs.as_slice()) {
+            ("getTokenRoots", []) => vec![Token::Array(
+                elock
+                    .roots
+                    .iter()
+                    .map(|root| Token::Address(root.eth_root))
+                    .collect(),
+            )],
+            ("getTotalSupply", [Token::Address(root)]) => vec![Token::Uint(
+                elock.total_supplies.get(root).cloned().unwrap_or_default(),
+            )],
+            ("getValidators", []) => vec![Token::Array(
+                elock
+                    .validators
+                    .iter()
+                    .map(|validator| Token::Address(*validator))
+                    .collect(),
+            )],
+            ("getProposalList", []) => vec![Token::Array(
+                elock
+                    .proposals
+                    .iter()
+                    .map(|(key, _)| Token::Uint(*key))
+                    .collect(),
+            )],
+            ("getProposal", [Token::Uint(key)]) => {
+                let proposal = elock.proposal(*key)?;
+                vec![
+                    Token::Uint(proposal.from_block),
+                    Token::Uint(proposal.till_block),
+                    Token::Array(proposal.transfers.clone()),
+                ]
+            }
+            ("getVotesForWithdrawal", [Token::Uint(key)]) => {
+                vec![Token::Uint(U256::from(elock.proposal(*key)?.votes.len()))]
+            }
+            ("getMyVoteForWithdrawal", [Token::Uint(key), Token::Address(validator)]) => {
+                vec![Token::Bool(elock.proposal(*key)?.votes.contains(validator))]
+            }
+            ("lastProcessedL2Block", []) => vec![Token::Uint(elock.last_processed_l2_block)],
+            ("trxDepositCount", []) => vec![Token::Uint(U256::from(elock.deposit_count))],
+            ("trxWithdrawCount", []) => vec![Token::Uint(U256::from(elock.withdraw_count))],
+            (name, _) => anyhow::bail!("ELock function {name} is not supported by the mock"),
+        };
+        Ok(web3::ethabi::encode(&output))
+    }
+
+    fn root_call(&self, to: Address, data: &[u8]) -> anyhow::Result<Vec<u8>> {
+        let root = self
+            .elock
+            .roots
+            .iter()
+            .find(|root| root.eth_root == to)
+            .ok_or(anyhow::format_err!("No contract deployed at {to:?}"))?;
+        let abi = load_abi(ERC20_ABI);
+        let function = abi
+            .functions()
+            .find(|f| f.short_signature() == data[..4])
+            .ok_or(anyhow::format_err!(
+                "execution reverted: unknown ERC20 function"
+            ))?;
+        let output = match function.name.as_str() {
+            "name" => Token::String(root.name.clone()),
+            "symbol" => Token::String(root.symbol.clone()),
+            "decimals" => Token::Uint(U256::from(root.decimals)),
+            name => anyhow::bail!("ERC20 function {name} is not supported by the mock"),
+        };
+        Ok(web3::ethabi::encode(&[output]))
+    }
+
+    // Apply transaction sent to ELock. Failed transactions are rejected before they get into a
+    // block, as if their gas estimation failed.
+    pub fn elock_transaction(&mut self, tx: &RawTransaction) -> anyhow::Result<()> {
+        let abi = load_abi(ELOCK_ABI);
+        if tx.data.len() < 4 {
+            anyhow::bail!("execution reverted: call data is too short");
+        }
+        let function = abi
+            .functions()
+            .find(|f| f.short_signature() == tx.data[..4])
+            .ok_or(anyhow::format_err!(
+                "execution reverted: unknown ELock function"
+            ))?;
+        let inputs = function
+            .decode_input(&tx.data[4..])
+            .map_err(|e| anyhow::format_err!("execution reverted: {e}"))?;
+        tracing::info!("ELock transaction {} from {:?}", function.name, tx.from);
+
+        let is_validator = self.elock.validators.contains(&tx.from);
+        match (function.name.as_str(), inputs.as_slice()) {
+            ("deposit", [Token::Uint(pubkey)]) => {
+                self.elock_deposit(Address::zero(), tx.from, *pubkey, tx.value, Some(tx.hash));
+            }
+            (
+                "proposeWithdrawal",
+                [Token::Uint(from), Token::Uint(till), Token::Array(transfers)],
+            ) => {
+                if !is_validator {
+                    anyhow::bail!("execution reverted: sender is not a validator");
+                }
+                if *from != self.elock.last_processed_l2_block {
+                    anyhow::bail!("execution reverted: wrong fromBlock");
+                }
+                let key = U256::from_big_endian(&keccak256(&tx.data));
+                if self.elock.proposals.iter().any(|(k, _)| *k == key) {
+                    anyhow::bail!("execution reverted: proposal already exists");
+                }
+                self.elock.proposals.push((
+                    key,
+                    Proposal {
+                        from_block: *from,
+                        till_block: *till,
+                        transfers: transfers.clone(),
+                        votes: HashSet::new(),
+                    },
+                ));
+            }
+            ("voteForWithdrawal", [Token::Uint(key)]) => {
+                if !is_validator {
+                    anyhow::bail!("execution reverted: sender is not a validator");
+                }
+                self.elock.proposal(*key)?;
+                self.elock_vote(*key, tx.from, tx.hash);
+            }
+            (name, _) => anyhow::bail!("ELock function {name} is not supported by the mock"),
+        }
+        Ok(())
+    }
+
+    fn elock_vote(&mut self, key: U256, validator: Address, tx_hash: H256) {
+        let address = self.elock.address;
+        self.set_storage(
+            address,
+            vote_slot(key, validator),
+            u256_to_h256(U256::one()),
+        );
+        let position = self
+            .elock
+            .proposals
+            .iter()
+            .position(|(k, _)| *k == key)
+            .unwrap();
+        let proposal = &mut self.elock.proposals[position].1;
+        proposal.votes.insert(validator);
+
+        // Proposal is executed when more than 2/3 of validators voted for it
+        if proposal.votes.len() * 3 <= self.elock.validators.len() * 2 {
+            return;
+        }
+        let (_, proposal) = self.elock.proposals.remove(position);
+        tracing::info!("Execute withdrawal proposal {key}");
+        for transfer in &proposal.transfers {
+            if let Some([Token::Address(root), Token::Address(to), Token::Uint(value), _]) =
+                transfer.clone().into_tuple().as_deref()
+            {
+                let supply = self.elock.total_supplies.entry(*root).or_default();
+                *supply = supply.saturating_sub(*value);
+                if root.is_zero() {
+                    self.add_balance(*to, *value);
+                }
+                let data = web3::ethabi::encode(&[Token::Uint(*value), Token::Uint(U256::zero())]);
+                let topics = vec![
+                    event_signature("Withdrawal"),
+                    address_topic(*root),
+                    address_topic(*to),
+                ];
+                self.emit_log(address, topics, data, Some(tx_hash));
+            }
+        }
+        self.elock.withdraw_count += proposal.transfers.len() as u128;
+        self.write_elock_counters();
+        self.elock_set_last_processed_l2_block(proposal.till_block);
+        let topics = vec![event_signature("WithdrawExecuted"), u256_to_h256(key)];
+        self.emit_log(address, topics, vec![], Some(tx_hash));
+    }
+}
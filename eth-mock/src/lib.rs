@@ -0,0 +1,9 @@
+mod chain;
+mod elock;
+mod rpc;
+pub mod scenario;
+mod server;
+mod tx;
+
+pub use chain::MockChain;
+pub use server::MockServer;
@@ -0,0 +1,56 @@
+use clap::Parser;
+use common::helper::tracing::init_default_tracing;
+use eth_mock::scenario::Scenario;
+use eth_mock::MockServer;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::time::Duration;
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "eth-mock",
+    version,
+    about = "Local ETH JSON-RPC mock with simulated ELock"
+)]
+struct Args {
+    /// Path to the scenario with initial chain state
+    #[arg(long, env = "ETH_MOCK_SCENARIO")]
+    scenario: PathBuf,
+
+    /// Address of the HTTP endpoint
+    #[arg(long, default_value = "127.0.0.1:8545")]
+    http: SocketAddr,
+
+    /// Address of the WebSocket endpoint
+    #[arg(long, default_value = "127.0.0.1:8546")]
+    ws: SocketAddr,
+
+    /// Mine a new empty block every specified number of seconds
+    #[arg(long)]
+    block_time: Option<u64>,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    init_default_tracing();
+    let args = Args::parse();
+
+    let chain = Scenario::load(&args.scenario)?.build()?;
+    let server = MockServer::bind(chain.clone(), args.http, args.ws).await?;
+    println!("HTTP endpoint: {}", server.http_url());
+    println!("WebSocket endpoint: {}", server.ws_url());
+
+    if let Some(block_time) = args.block_time {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(Duration::from_secs(block_time));
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                chain.mine_block();
+            }
+        });
+    }
+
+    server.join().await;
+    Ok(())
+}
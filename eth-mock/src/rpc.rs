@@ -0,0 +1,202 @@
+use crate::chain::{ChainState, MockChain};
+use crate::tx::{decode_raw_transaction, sign_transaction};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use web3::types::{Address, Bytes, CallRequest, TransactionRequest, H256, U256, U64};
+
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const SERVER_ERROR: i64 = -32000;
+
+fn param<T: DeserializeOwned>(params: &[Value], index: usize) -> anyhow::Result<T> {
+    serde_json::from_value(params.get(index).cloned().unwrap_or(Value::Null))
+        .map_err(|e| anyhow::format_err!("Invalid param #{index}: {e}"))
+}
+
+// Resolve block tag or number, missing tag means the latest block
+fn block_number(state: &ChainState, tag: Option<&Value>) -> anyhow::Result<u64> {
+    match tag.and_then(|t| t.as_str()) {
+        None | Some("latest") | Some("pending") => Ok(state.head_number()),
+        Some("finalized") | Some("safe") => Ok(state.finalized_number()),
+        Some("earliest") => Ok(0),
+        Some(number) => U64::from_str_radix(number.trim_start_matches("0x"), 16)
+            .map(|n| n.as_u64())
+            .map_err(|e| anyhow::format_err!("Invalid block number {number}: {e}")),
+    }
+}
+
+fn matches_filter(filter: &Value, actual: &Value) -> bool {
+    match filter {
+        Value::Null => true,
+        Value::Array(options) => options.iter().any(|option| matches_filter(option, actual)),
+        Value::String(expected) => actual
+            .as_str()
+            .is_some_and(|actual| actual.eq_ignore_ascii_case(expected)),
+        _ => false,
+    }
+}
+
+fn get_logs(state: &ChainState, filter: &Value) -> anyhow::Result<Value> {
+    let from = block_number(state, filter.get("fromBlock"))?;
+    let to = block_number(state, filter.get("toBlock"))?;
+    let address = filter.get("address").cloned().unwrap_or(Value::Null);
+    let topics = match filter.get("topics") {
+        Some(Value::Array(topics)) => topics.clone(),
+        _ => vec![],
+    };
+    let logs = state
+        .logs()
+        .iter()
+        .filter(|log| {
+            let number = log["blockNumber"]
+                .as_str()
+                .and_then(|n| U64::from_str_radix(n.trim_start_matches("0x"), 16).ok())
+                .map_or(0, |n| n.as_u64());
+            number >= from
+                && number <= to
+                && matches_filter(&address, &log["address"])
+                && topics
+                    .iter()
+                    .enumerate()
+                    .all(|(index, topic)| matches_filter(topic, &log["topics"][index]))
+        })
+        .cloned()
+        .collect();
+    Ok(Value::Array(logs))
+}
+
+fn sign_transaction_request(state: &ChainState, tx: TransactionRequest) -> anyhow::Result<Value> {
+    let key = state
+        .signer_keys
+        .get(&tx.from)
+        .ok_or(anyhow::format_err!("No key for account {:?}", tx.from))?;
+    sign_transaction(&tx, state.chain_id, key).map(|raw| json!(Bytes(raw)))
+}
+
+fn send_raw_transaction(state: &mut ChainState, raw: Bytes) -> anyhow::Result<Value> {
+    let tx = decode_raw_transaction(&raw.0)?;
+    if tx.chain_id != state.chain_id {
+        anyhow::bail!("invalid chain id {}", tx.chain_id);
+    }
+    let nonce = state.nonce(tx.from);
+    if tx.nonce != nonce {
+        anyhow::bail!("invalid nonce {}, expected {nonce}", tx.nonce);
+    }
+    match tx.to {
+        Some(to) if to == state.elock.address => state.elock_transaction(&tx)?,
+        Some(to) => state.add_balance(to, tx.value),
+        None => anyhow::bail!("Contract deployment is not supported by the mock"),
+    }
+    state.increase_nonce(tx.from);
+    state.add_transaction(tx.hash, tx.from, tx.to);
+    // Every transaction is mined into its own block
+    state.mine_block();
+    Ok(json!(tx.hash))
+}
+
+impl MockChain {
+    // Handle JSON-RPC request or batch of requests
+    pub(crate) fn handle_request(&self, request: Value) -> Value {
+        match request {
+            Value::Array(requests) => Value::Array(
+                requests
+                    .iter()
+                    .map(|request| self.handle_single(request))
+                    .collect(),
+            ),
+            request => self.handle_single(&request),
+        }
+    }
+
+    fn handle_single(&self, request: &Value) -> Value {
+        let id = request.get("id").cloned().unwrap_or(Value::Null);
+        let method = match request.get("method").and_then(|m| m.as_str()) {
+            Some(method) => method,
+            None => return error_response(id, INVALID_REQUEST, "Missing method".to_string()),
+        };
+        let params = match request.get("params") {
+            Some(Value::Array(params)) => params.clone(),
+            _ => vec![],
+        };
+        tracing::debug!("{method}: {params:?}");
+        match self.dispatch(method, &params) {
+            Some(Ok(result)) => json!({"jsonrpc": "2.0", "id": id, "result": result}),
+            Some(Err(e)) => {
+                tracing::info!("{method} failed: {e}");
+                error_response(id, SERVER_ERROR, e.to_string())
+            }
+            None => error_response(
+                id,
+                METHOD_NOT_FOUND,
+                format!("Method {method} is not supported by the mock"),
+            ),
+        }
+    }
+
+    fn dispatch(&self, method: &str, params: &[Value]) -> Option<anyhow::Result<Value>> {
+        let mut state = self.state.lock();
+        let result = match method {
+            "web3_clientVersion" => Ok(json!("eth-mock")),
+            "net_version" => Ok(json!(state.chain_id.to_string())),
+            "eth_chainId" => Ok(json!(U64::from(state.chain_id))),
+            "eth_blockNumber" => Ok(json!(U64::from(state.head_number()))),
+            "eth_gasPrice" => Ok(json!(state.gas_price)),
+            "eth_getBalance" => param(params, 0).map(|address| json!(state.balance(address))),
+            "eth_getTransactionCount" => {
+                param(params, 0).map(|address| json!(state.nonce(address)))
+            }
+            "eth_getBlockByHash" => {
+                param::<H256>(params, 0).map(|hash| json!(state.block_by_hash(hash)))
+            }
+            "eth_getBlockByNumber" => block_number(&state, params.first())
+                .map(|number| json!(state.block_by_number(number))),
+            "eth_getLogs" => get_logs(&state, params.first().unwrap_or(&Value::Null)),
+            "eth_getStorageAt" => (|| {
+                let address: Address = param(params, 0)?;
+                let slot: U256 = param(params, 1)?;
+                let number = block_number(&state, params.get(2))?;
+                Ok(json!(state.storage_at(address, slot, number)))
+            })(),
+            // Calls are always executed on the latest state
+            "eth_call" => param::<CallRequest>(params, 0).and_then(|request| {
+                let to = request
+                    .to
+                    .ok_or(anyhow::format_err!("Call without recipient"))?;
+                let data = request.data.unwrap_or_default();
+                state
+                    .elock_call(to, &data.0)
+                    .map(|output| json!(Bytes(output)))
+            }),
+            "eth_accounts" => Ok(json!(state.signer_keys.keys().collect::<Vec<_>>())),
+            "eth_signTransaction" => {
+                param(params, 0).and_then(|tx| sign_transaction_request(&state, tx))
+            }
+            "eth_sendRawTransaction" => {
+                param(params, 0).and_then(|raw| send_raw_transaction(&mut state, raw))
+            }
+            "eth_getTransactionReceipt" => {
+                param::<H256>(params, 0).map(|hash| json!(state.receipt(&hash)))
+            }
+            "eth_newBlockFilter" => Ok(json!(state.new_block_filter())),
+            "eth_getFilterChanges" => param::<U256>(params, 0).and_then(|id| {
+                state
+                    .filter_changes(id)
+                    .map(|hashes| json!(hashes))
+                    .ok_or(anyhow::format_err!("filter not found"))
+            }),
+            "eth_uninstallFilter" => {
+                param::<U256>(params, 0).map(|id| json!(state.uninstall_filter(id)))
+            }
+            _ => return None,
+        };
+        Some(result)
+    }
+}
+
+fn error_response(id: Value, code: i64, message: String) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": {"code": code, "message": message},
+    })
+}
@@ -0,0 +1,130 @@
+use crate::chain::MockChain;
+use common::token_root::RootData;
+use serde::Deserialize;
+use std::path::Path;
+use web3::signing::SecretKey;
+use web3::types::{Address, H256, U256};
+
+// Initial chain state loaded from TOML file. See `eth-mock/example.toml`.
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Scenario {
+    pub elock_address: Address,
+    pub chain_id: Option<u64>,
+    #[serde(default)]
+    pub finality_depth: u64,
+    #[serde(default)]
+    pub validators: Vec<Address>,
+    pub last_processed_l2_block: Option<U256>,
+    // Number of blocks mined after genesis
+    #[serde(default)]
+    pub blocks: u64,
+    #[serde(default)]
+    pub token_roots: Vec<TokenRoot>,
+    #[serde(default)]
+    pub deposits: Vec<Deposit>,
+    #[serde(default)]
+    pub balances: Vec<Balance>,
+    // Hex private keys of accounts served with 'eth_signTransaction', test keys only
+    #[serde(default)]
+    pub signer_keys: Vec<H256>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct TokenRoot {
+    pub address: Address,
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Deposit {
+    // Number of block containing the deposit
+    pub block: u64,
+    // Zero address stands for ETH
+    #[serde(default)]
+    pub token: Address,
+    pub from: Address,
+    pub pubkey: U256,
+    // Decimal amount in the smallest token units
+    pub value: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Balance {
+    pub address: Address,
+    // Decimal amount in wei
+    pub value: String,
+}
+
+fn parse_amount(value: &str) -> anyhow::Result<U256> {
+    U256::from_dec_str(value).map_err(|e| anyhow::format_err!("Invalid amount {value}: {e:?}"))
+}
+
+impl Scenario {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::format_err!("Failed to read scenario {}: {e}", path.display()))?;
+        toml::from_str(&content)
+            .map_err(|e| anyhow::format_err!("Failed to parse scenario {}: {e}", path.display()))
+    }
+
+    pub fn build(&self) -> anyhow::Result<MockChain> {
+        let chain = MockChain::new(self.elock_address);
+        if let Some(chain_id) = self.chain_id {
+            chain.set_chain_id(chain_id);
+        }
+        chain.set_finality_depth(self.finality_depth);
+        for validator in &self.validators {
+            chain.add_validator(*validator);
+        }
+        for root in &self.token_roots {
+            chain.add_token_root(RootData {
+                name: root.name.clone(),
+                symbol: root.symbol.clone(),
+                decimals: root.decimals,
+                eth_root: root.address,
+            });
+        }
+        for balance in &self.balances {
+            chain.set_balance(balance.address, parse_amount(&balance.value)?);
+        }
+        for key in &self.signer_keys {
+            chain.add_signer_key(
+                SecretKey::from_slice(key.as_bytes())
+                    .map_err(|e| anyhow::format_err!("Invalid signer key: {e}"))?,
+            );
+        }
+        if let Some(block) = self.last_processed_l2_block {
+            chain.set_last_processed_l2_block(block);
+        }
+
+        if let Some(deposit) = self
+            .deposits
+            .iter()
+            .find(|d| d.block == 0 || d.block > self.blocks)
+        {
+            anyhow::bail!(
+                "Deposit block {} is out of the scenario range 1..={}",
+                deposit.block,
+                self.blocks
+            );
+        }
+        for number in 1..=self.blocks {
+            for deposit in self.deposits.iter().filter(|d| d.block == number) {
+                chain.deposit(
+                    deposit.token,
+                    deposit.from,
+                    deposit.pubkey,
+                    parse_amount(&deposit.value)?,
+                );
+            }
+            chain.mine_block();
+        }
+        Ok(chain)
+    }
+}
@@ -0,0 +1,302 @@
+use crate::chain::MockChain;
+use futures::{SinkExt, StreamExt};
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Request, Response};
+use serde_json::{json, Value};
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::Message;
+
+const PARSE_ERROR: i64 = -32700;
+
+fn parse_request(chain: &MockChain, body: &[u8]) -> Value {
+    match serde_json::from_slice(body) {
+        Ok(request) => chain.handle_request(request),
+        Err(e) => json!({
+            "jsonrpc": "2.0",
+            "id": null,
+            "error": {"code": PARSE_ERROR, "message": format!("Parse error: {e}")},
+        }),
+    }
+}
+
+async fn handle_http(
+    chain: MockChain,
+    request: Request<Body>,
+) -> Result<Response<Body>, Infallible> {
+    let response = match hyper::body::to_bytes(request.into_body()).await {
+        Ok(body) => parse_request(&chain, &body),
+        Err(e) => json!({
+            "jsonrpc": "2.0",
+            "id": null,
+            "error": {"code": PARSE_ERROR, "message": format!("Failed to read body: {e}")},
+        }),
+    };
+    Ok(Response::builder()
+        .header("Content-Type", "application/json")
+        .body(Body::from(response.to_string()))
+        .unwrap())
+}
+
+async fn handle_ws(chain: MockChain, stream: TcpStream) -> anyhow::Result<()> {
+    let (mut write, mut read) = tokio_tungstenite::accept_async(stream)
+        .await
+        .map_err(|e| anyhow::format_err!("WebSocket handshake failed: {e}"))?
+        .split();
+    while let Some(message) = read.next().await {
+        let response = match message? {
+            Message::Text(text) => parse_request(&chain, text.as_bytes()),
+            Message::Binary(data) => parse_request(&chain, &data),
+            Message::Close(_) => break,
+            _ => continue,
+        };
+        write.send(Message::Text(response.to_string())).await?;
+    }
+    Ok(())
+}
+
+// JSON-RPC server exposing mock chain over HTTP and WebSocket. Server is stopped on drop.
+pub struct MockServer {
+    http_addr: SocketAddr,
+    ws_addr: SocketAddr,
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl MockServer {
+    // Start server on random local ports
+    pub async fn start(chain: MockChain) -> anyhow::Result<Self> {
+        let local: SocketAddr = ([127, 0, 0, 1], 0).into();
+        Self::bind(chain, local, local).await
+    }
+
+    pub async fn bind(
+        chain: MockChain,
+        http_addr: SocketAddr,
+        ws_addr: SocketAddr,
+    ) -> anyhow::Result<Self> {
+        let http_chain = chain.clone();
+        let http_server = hyper::Server::try_bind(&http_addr)
+            .map_err(|e| anyhow::format_err!("Failed to bind HTTP server to {http_addr}: {e}"))?
+            .serve(make_service_fn(move |_| {
+                let chain = http_chain.clone();
+                async move {
+                    Ok::<_, Infallible>(service_fn(move |request| {
+                        handle_http(chain.clone(), request)
+                    }))
+                }
+            }));
+        let http_addr = http_server.local_addr();
+        let http_task = tokio::spawn(async move {
+            if let Err(e) = http_server.await {
+                tracing::error!("HTTP server failed: {e}");
+            }
+        });
+
+        let ws_listener = TcpListener::bind(ws_addr).await.map_err(|e| {
+            anyhow::format_err!("Failed to bind WebSocket server to {ws_addr}: {e}")
+        })?;
+        let ws_addr = ws_listener.local_addr()?;
+        let ws_task = tokio::spawn(async move {
+            loop {
+                let stream = match ws_listener.accept().await {
+                    Ok((stream, _)) => stream,
+                    Err(e) => {
+                        tracing::error!("Failed to accept WebSocket connection: {e}");
+                        continue;
+                    }
+                };
+                let chain = chain.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = handle_ws(chain, stream).await {
+                        tracing::info!("WebSocket connection closed: {e}");
+                    }
+                });
+            }
+        });
+
+        tracing::info!("ETH mock is listening on http://{http_addr} and ws://{ws_addr}");
+        Ok(Self {
+            http_addr,
+            ws_addr,
+            tasks: vec![http_task, ws_task],
+        })
+    }
+
+    pub fn http_url(&self) -> String {
+        format!("http://{}", self.http_addr)
+    }
+
+    pub fn ws_url(&self) -> String {
+        format!("ws://{}", self.ws_addr)
+    }
+
+    // Wait until server tasks are finished
+    pub async fn join(mut self) {
+        for task in std::mem::take(&mut self.tasks) {
+            let _ = task.await;
+        }
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MockServer;
+    use crate::chain::MockChain;
+    use common::config::{EthConfig, RemoteSignerConfig};
+    use common::elock::deposit::get_deposits;
+    use common::elock::get_tx_counter;
+    use common::eth::encoder::serialize_block;
+    use common::eth::signer::{EthSigner, RemoteSigner};
+    use common::eth::{call_function, create_web3, read_block, EthTransport};
+    use common::token_root::RootData;
+    use web3::ethabi::Token;
+    use web3::signing::{Key, SecretKey, SecretKeyRef};
+    use web3::types::{Address, BlockId, BlockNumber, U256, U64};
+    use web3::Web3;
+
+    fn eth_config(network: String, elock_address: Address) -> EthConfig {
+        EthConfig {
+            network,
+            extra_networks: vec![],
+            quorum: 1,
+            elock_address,
+            confirmations: 1,
+            private_key_path: None,
+            keystore_password_path: None,
+            signer: None,
+            validator_address: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_read_deposits_over_http() -> anyhow::Result<()> {
+        let elock = Address::repeat_byte(0xe1);
+        let root = Address::repeat_byte(0x70);
+        let chain = MockChain::new(elock);
+        chain.set_finality_depth(2);
+        chain.add_token_root(RootData {
+            name: "Tether USD".to_string(),
+            symbol: "USDT".to_string(),
+            decimals: 6,
+            eth_root: root,
+        });
+        chain.mine_block();
+        chain.deposit(
+            Address::zero(),
+            Address::repeat_byte(1),
+            U256::from(7),
+            U256::from(100),
+        );
+        chain.deposit(
+            root,
+            Address::repeat_byte(2),
+            U256::from(8),
+            U256::from(200),
+        );
+        chain.mine_blocks(3);
+
+        let server = MockServer::start(chain.clone()).await?;
+        let web3s = create_web3(&eth_config(server.http_url(), elock)).await?;
+
+        let finalized = read_block(&web3s, BlockId::Number(BlockNumber::Finalized)).await?;
+        assert_eq!(finalized.number, Some(U64::from(2)));
+        serialize_block(&finalized)?;
+
+        let counter = get_tx_counter(&web3s, elock, U64::from(2)).await?;
+        assert_eq!(counter, U256::from(2));
+        let counter = get_tx_counter(&web3s, elock, U64::from(1)).await?;
+        assert_eq!(counter, U256::zero());
+
+        let deposits = get_deposits(&web3s, elock, U64::from(1), U64::from(2)).await?;
+        assert_eq!(deposits.len(), 2);
+        assert_eq!(deposits[0].root.symbol, "gth");
+        assert_eq!(deposits[0].data.value, 100);
+        assert_eq!(deposits[1].root.symbol, "USDT");
+        assert_eq!(deposits[1].data.value, 200);
+        Ok(())
+    }
+
+    // Propose withdrawal of a deposit and vote for it by the only validator
+    async fn withdraw(
+        chain: &MockChain,
+        web3s: &Web3<EthTransport>,
+        signer: &EthSigner,
+    ) -> anyhow::Result<()> {
+        let elock = chain.elock_address();
+        chain.add_validator(signer.address());
+        chain.set_balance(signer.address(), U256::exp10(18));
+        chain.deposit(
+            Address::zero(),
+            Address::repeat_byte(1),
+            U256::from(7),
+            U256::from(100),
+        );
+        chain.mine_block();
+
+        let receiver = Address::repeat_byte(0xaa);
+        let transfers = vec![Token::Tuple(vec![
+            Token::Address(Address::zero()),
+            Token::Address(receiver),
+            Token::Uint(U256::from(60)),
+            Token::Uint(U256::from(1)),
+        ])];
+        let params = vec![
+            Token::Uint(U256::zero()),
+            Token::Uint(U256::from(5)),
+            Token::Array(transfers),
+        ];
+        call_function(web3s, elock, signer, "proposeWithdrawal", params, 1).await?;
+        let proposals = chain.proposal_keys();
+        assert_eq!(proposals.len(), 1);
+
+        let params = vec![Token::Uint(proposals[0])];
+        call_function(web3s, elock, signer, "voteForWithdrawal", params, 1).await?;
+        assert!(chain.proposal_keys().is_empty());
+        assert_eq!(chain.withdraw_count(), 1);
+        assert_eq!(web3s.eth().balance(receiver, None).await?, U256::from(60));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_withdrawal_over_ws() -> anyhow::Result<()> {
+        let elock = Address::repeat_byte(0xe1);
+        let chain = MockChain::new(elock);
+        let server = MockServer::start(chain.clone()).await?;
+        let web3s = create_web3(&eth_config(server.ws_url(), elock)).await?;
+
+        let key = SecretKey::from_slice(&[0x11; 32])?;
+        withdraw(&chain, &web3s, &EthSigner::Local(key)).await
+    }
+
+    #[tokio::test]
+    async fn test_withdrawal_with_remote_signer() -> anyhow::Result<()> {
+        let elock = Address::repeat_byte(0xe1);
+        let chain = MockChain::new(elock);
+        let key = SecretKey::from_slice(&[0x22; 32])?;
+        let address = SecretKeyRef::new(&key).address();
+        chain.add_signer_key(key);
+        // The mock serves both the chain and the signer
+        let server = MockServer::start(chain.clone()).await?;
+        let web3s = create_web3(&eth_config(server.ws_url(), elock)).await?;
+
+        let config = RemoteSignerConfig {
+            url: server.http_url(),
+            address: Address::repeat_byte(0x33),
+        };
+        assert!(RemoteSigner::connect(&config).await.is_err());
+
+        let config = RemoteSignerConfig { address, ..config };
+        let signer = EthSigner::Remote(RemoteSigner::connect(&config).await?);
+        withdraw(&chain, &web3s, &signer).await
+    }
+}
@@ -0,0 +1,115 @@
+use rlp::{Rlp, RlpStream};
+use web3::signing::{keccak256, recover, Key, SecretKey, SecretKeyRef};
+use web3::types::{Address, TransactionRequest, H256, U256, U64};
+
+const EIP1559_TX_TYPE: u8 = 2;
+const EIP1559_FIELDS_CNT: usize = 12;
+const EIP1559_UNSIGNED_FIELDS_CNT: usize = 9;
+
+pub(crate) struct RawTransaction {
+    pub hash: H256,
+    pub chain_id: u64,
+    pub nonce: U256,
+    pub from: Address,
+    pub to: Option<Address>,
+    pub value: U256,
+    pub data: Vec<u8>,
+}
+
+fn rlp_error(e: rlp::DecoderError) -> anyhow::Error {
+    anyhow::format_err!("Failed to decode raw transaction: {e}")
+}
+
+// Decode signed EIP-1559 transaction and recover its sender. The bridge sends only transactions
+// of this type, so others are rejected.
+pub(crate) fn decode_raw_transaction(raw: &[u8]) -> anyhow::Result<RawTransaction> {
+    match raw.first() {
+        Some(&EIP1559_TX_TYPE) => {}
+        _ => anyhow::bail!("Only EIP-1559 transactions are supported"),
+    }
+    let rlp = Rlp::new(&raw[1..]);
+    if rlp.item_count().map_err(rlp_error)? != EIP1559_FIELDS_CNT {
+        anyhow::bail!("Wrong number of fields in EIP-1559 transaction");
+    }
+
+    // Sender signs hash of the transaction type and payload without signature
+    let mut unsigned = RlpStream::new_list(EIP1559_UNSIGNED_FIELDS_CNT);
+    for index in 0..EIP1559_UNSIGNED_FIELDS_CNT {
+        unsigned.append_raw(rlp.at(index).map_err(rlp_error)?.as_raw(), 1);
+    }
+    let message = keccak256(&[&[EIP1559_TX_TYPE], unsigned.as_raw()].concat());
+
+    let recovery_id: u64 = rlp.val_at(9).map_err(rlp_error)?;
+    let r: U256 = rlp.val_at(10).map_err(rlp_error)?;
+    let s: U256 = rlp.val_at(11).map_err(rlp_error)?;
+    let mut signature = [0_u8; 64];
+    r.to_big_endian(&mut signature[..32]);
+    s.to_big_endian(&mut signature[32..]);
+    let from = recover(&message, &signature, recovery_id as i32)
+        .map_err(|e| anyhow::format_err!("Failed to recover transaction sender: {e:?}"))?;
+
+    let to = rlp.at(5).map_err(rlp_error)?;
+    let to = if to.is_empty() {
+        None
+    } else {
+        Some(to.as_val::<Address>().map_err(rlp_error)?)
+    };
+
+    Ok(RawTransaction {
+        hash: H256::from(keccak256(raw)),
+        chain_id: rlp.val_at(0).map_err(rlp_error)?,
+        nonce: rlp.val_at(1).map_err(rlp_error)?,
+        from,
+        to,
+        value: rlp.val_at(6).map_err(rlp_error)?,
+        data: rlp.val_at(7).map_err(rlp_error)?,
+    })
+}
+
+// Sign EIP-1559 transaction the way a remote signer answers 'eth_signTransaction'
+pub(crate) fn sign_transaction(
+    tx: &TransactionRequest,
+    chain_id: u64,
+    key: &SecretKey,
+) -> anyhow::Result<Vec<u8>> {
+    if tx.transaction_type != Some(U64::from(EIP1559_TX_TYPE)) {
+        anyhow::bail!("Only EIP-1559 transactions are supported");
+    }
+    let missing = |field: &str| anyhow::format_err!("Transaction has no {field}");
+    let nonce = tx.nonce.ok_or_else(|| missing("nonce"))?;
+    let max_priority_fee = tx
+        .max_priority_fee_per_gas
+        .ok_or_else(|| missing("maxPriorityFeePerGas"))?;
+    let max_fee = tx.max_fee_per_gas.ok_or_else(|| missing("maxFeePerGas"))?;
+    let gas = tx.gas.ok_or_else(|| missing("gas"))?;
+
+    let append_payload = |stream: &mut RlpStream| {
+        stream.append(&chain_id);
+        stream.append(&nonce);
+        stream.append(&max_priority_fee);
+        stream.append(&max_fee);
+        stream.append(&gas);
+        match &tx.to {
+            Some(to) => stream.append(to),
+            None => stream.append_empty_data(),
+        };
+        stream.append(&tx.value.unwrap_or_default());
+        stream.append(&tx.data.clone().unwrap_or_default().0);
+        // Access list
+        stream.begin_list(0);
+    };
+    let mut unsigned = RlpStream::new_list(EIP1559_UNSIGNED_FIELDS_CNT);
+    append_payload(&mut unsigned);
+    let message = keccak256(&[&[EIP1559_TX_TYPE], unsigned.as_raw()].concat());
+    let signature = SecretKeyRef::new(key)
+        .sign(&message, None)
+        .map_err(|e| anyhow::format_err!("Failed to sign transaction: {e:?}"))?;
+
+    // Without chain id signature `v` is 27 or 28, typed transactions keep only the parity
+    let mut signed = RlpStream::new_list(EIP1559_FIELDS_CNT);
+    append_payload(&mut signed);
+    signed.append(&(signature.v - 27));
+    signed.append(&U256::from_big_endian(signature.r.as_bytes()));
+    signed.append(&U256::from_big_endian(signature.s.as_bytes()));
+    Ok([&[EIP1559_TX_TYPE], signed.as_raw()].concat())
+}
@@ -0,0 +1,150 @@
+use clap::{Args, Parser, Subcommand};
+use std::path::PathBuf;
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "gosh-bridge",
+    version,
+    about = "GOSH <-> ETH bridge validator tools"
+)]
+pub struct Cli {
+    /// Path to the TOML config file
+    #[arg(long, global = true, env = "BRIDGE_CONFIG")]
+    pub config: Option<PathBuf>,
+
+    /// Network profile from the config file
+    #[arg(long, global = true, env = "BRIDGE_PROFILE")]
+    pub profile: Option<String>,
+
+    /// Run all reads and validation, but print transactions and messages instead of sending them
+    #[arg(long, global = true)]
+    pub dry_run: bool,
+
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Propose new ETH blocks to GOSH
+    Propose(ProposeArgs),
+
+    /// Deposit (ETH -> GOSH) flow
+    #[command(subcommand)]
+    Deposit(DepositCommand),
+
+    /// Withdrawal (GOSH -> ETH) flow
+    #[command(subcommand)]
+    Withdraw(WithdrawCommand),
+
+    /// Print ELock events since the last GOSH block saved in ELock
+    Events,
+
+    /// Print bridge telemetry in JSON
+    Telemetry(TelemetryArgs),
+
+    /// Print the latest GOSH and ETH blocks
+    LastBlocks,
+
+    /// Manage the validator ETH key and GOSH key pair
+    #[command(subcommand)]
+    Keys(KeysCommand),
+}
+
+#[derive(Args, Debug)]
+pub struct ProposeArgs {
+    /// Run in loop with interval from the config
+    #[arg(long)]
+    pub daemon: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct TelemetryArgs {
+    /// Collect telemetry in loop and serve it as Prometheus metrics
+    #[arg(long)]
+    pub serve: bool,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum DepositCommand {
+    /// Validate deposit proposals in GOSH and vote for them
+    Check,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum WithdrawCommand {
+    /// Check withdrawal proposals in ELock and vote for them
+    Check,
+
+    /// Create a new withdrawal proposal in ELock
+    Create,
+
+    /// Print GOSH burns that are not withdrawn yet
+    FindBurns,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum KeysCommand {
+    /// Encrypt a plaintext ETH key into a V3 JSON keystore
+    Import {
+        /// Plaintext hex ETH key
+        #[arg(long, env = "ETH_PRIVATE_KEY_PATH")]
+        key: PathBuf,
+
+        /// Directory for the keystore, it is named by the key address
+        #[arg(long)]
+        output_dir: PathBuf,
+
+        /// File with the keystore password
+        #[arg(long, env = "ETH_KEYSTORE_PASSWORD_PATH")]
+        password_file: Option<PathBuf>,
+    },
+
+    /// Print the plaintext hex ETH key from a keystore
+    Export {
+        /// V3 JSON keystore
+        #[arg(long, env = "ETH_PRIVATE_KEY_PATH")]
+        key: PathBuf,
+
+        /// File with the keystore password
+        #[arg(long, env = "ETH_KEYSTORE_PASSWORD_PATH")]
+        password_file: Option<PathBuf>,
+    },
+
+    /// Print the validator address derived from the ETH key
+    Inspect {
+        /// Plaintext hex ETH key or V3 JSON keystore
+        #[arg(long, env = "ETH_PRIVATE_KEY_PATH")]
+        key: PathBuf,
+
+        /// File with the keystore password
+        #[arg(long, env = "ETH_KEYSTORE_PASSWORD_PATH")]
+        password_file: Option<PathBuf>,
+    },
+
+    /// Encrypt a GOSH validator key pair, it is read from stdin if --key is not set
+    GoshImport {
+        /// Plaintext key pair JSON
+        #[arg(long)]
+        key: Option<PathBuf>,
+
+        /// Encrypted key pair file to create
+        #[arg(long)]
+        output: PathBuf,
+
+        /// File with the key pair password
+        #[arg(long, env = "VALIDATORS_KEY_PASSWORD_PATH")]
+        password_file: Option<PathBuf>,
+    },
+
+    /// Print the public key of a GOSH validator key pair
+    GoshInspect {
+        /// Plaintext or encrypted key pair JSON
+        #[arg(long, env = "VALIDATORS_KEY_PATH")]
+        key: PathBuf,
+
+        /// File with the key pair password
+        #[arg(long, env = "VALIDATORS_KEY_PASSWORD_PATH")]
+        password_file: Option<PathBuf>,
+    },
+}
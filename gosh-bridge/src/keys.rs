@@ -0,0 +1,72 @@
+use crate::cli::KeysCommand;
+use common::eth::keystore::{import_key, is_encrypted, key_address, load_key};
+use common::gosh::keystore::{import_keys, load_keys};
+use serde_json::json;
+
+// Key commands print JSON to stdout, so their output can be used in scripts
+pub fn run_keys(command: &KeysCommand) -> anyhow::Result<()> {
+    match command {
+        KeysCommand::Import {
+            key,
+            output_dir,
+            password_file,
+        } => {
+            let (address, keystore) = import_key(key, output_dir, password_file.as_deref())?;
+            tracing::info!(
+                "Key is encrypted into {}, remove the plaintext key {}",
+                keystore.display(),
+                key.display()
+            );
+            println!(
+                "{}",
+                json!({
+                    "address": format!("{address:?}"),
+                    "keystore": keystore.display().to_string(),
+                })
+            );
+        }
+        KeysCommand::Export { key, password_file } => {
+            if !is_encrypted(key)? {
+                anyhow::bail!("{} is not a keystore", key.display());
+            }
+            let secret = load_key(key, password_file.as_deref())?;
+            println!(
+                "{}",
+                json!({
+                    "address": format!("{:?}", key_address(&secret)),
+                    "private_key": secret.display_secret().to_string(),
+                })
+            );
+        }
+        KeysCommand::Inspect { key, password_file } => {
+            let encrypted = is_encrypted(key)?;
+            let secret = load_key(key, password_file.as_deref())?;
+            println!(
+                "{}",
+                json!({
+                    "address": format!("{:?}", key_address(&secret)),
+                    "encrypted": encrypted,
+                })
+            );
+        }
+        KeysCommand::GoshImport {
+            key,
+            output,
+            password_file,
+        } => {
+            let keys = import_keys(key.as_deref(), output, password_file.as_deref())?;
+            println!(
+                "{}",
+                json!({
+                    "public": format!("0x{}", keys.public),
+                    "keys": output.display().to_string(),
+                })
+            );
+        }
+        KeysCommand::GoshInspect { key, password_file } => {
+            let keys = load_keys(key, password_file.as_deref())?;
+            println!("{}", json!({ "public": format!("0x{}", keys.public) }));
+        }
+    }
+    Ok(())
+}
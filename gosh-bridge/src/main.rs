@@ -0,0 +1,75 @@
+use crate::cli::{Cli, Command, DepositCommand, WithdrawCommand};
+use crate::keys::run_keys;
+use clap::Parser;
+use common::config::BridgeConfig;
+use common::eth::events::get_all_events;
+use common::helper::get_last_blocks;
+use common::helper::tracing::init_default_tracing;
+use deposit_proposal_checker::deposit::check_proposals;
+use gosh_proposer::proposer::daemon::run_daemon;
+use gosh_proposer::proposer::propose_eth_blocks;
+use l2_telemetry::serve::serve_metrics;
+use l2_telemetry::telemetry::get_telemetry;
+use std::process::ExitCode;
+use withdraw_proposal_checker::withdraw::burn::find_all_burns;
+use withdraw_proposal_checker::withdraw::validator::{
+    check_proposals_and_accept, create_new_proposal,
+};
+
+mod cli;
+mod keys;
+
+// Exit codes: 0 on success, 1 if the command failed, 2 on wrong command line arguments, 3 if the
+// flow skipped work it had to do, 4 if the ETH block saved in GOSH checker is not canonical (see
+// `Outcome`)
+#[tokio::main]
+async fn main() -> anyhow::Result<ExitCode> {
+    // Load env variables from '.env' file before parsing, so config path and profile can be set there
+    dotenv::dotenv().ok();
+    let cli = Cli::parse();
+    // Init tracing in level specified with env 'GOSH_LOG' or "info" level by default
+    init_default_tracing();
+    // Keys are managed without the bridge config
+    if let Command::Keys(command) = &cli.command {
+        return run_keys(command).map(|_| ExitCode::SUCCESS);
+    }
+    // Load and validate config
+    let mut config = BridgeConfig::load_with(cli.config.as_deref(), cli.profile.as_deref())?;
+    if cli.dry_run {
+        config.set_dry_run();
+    }
+
+    // Flows print their outcome as JSON, other commands print their own output
+    let outcome = match cli.command {
+        Command::Propose(args) => {
+            if args.daemon {
+                run_daemon(&config).await.map(|_| None)
+            } else {
+                propose_eth_blocks(&config).await.map(Some)
+            }
+        }
+        Command::Deposit(DepositCommand::Check) => check_proposals(&config).await.map(Some),
+        Command::Withdraw(WithdrawCommand::Check) => {
+            check_proposals_and_accept(&config).await.map(Some)
+        }
+        Command::Withdraw(WithdrawCommand::Create) => create_new_proposal(&config).await.map(Some),
+        Command::Withdraw(WithdrawCommand::FindBurns) => {
+            find_all_burns(&config).await.map(|_| None)
+        }
+        Command::Events => get_all_events(&config).await.map(|_| None),
+        Command::Telemetry(args) => {
+            if args.serve {
+                serve_metrics(&config).await.map(|_| None)
+            } else {
+                get_telemetry(&config).await.map(|_| None)
+            }
+        }
+        Command::LastBlocks => get_last_blocks(&config).await.map(|_| None),
+        Command::Keys(_) => unreachable!("keys are managed before the config is loaded"),
+    }?;
+
+    match outcome {
+        Some(outcome) => outcome.report(),
+        None => Ok(ExitCode::SUCCESS),
+    }
+}
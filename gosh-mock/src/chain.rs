@@ -0,0 +1,171 @@
+use parking_lot::Mutex;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+const MASTERCHAIN_ID: i32 = -1;
+// Page size of the real GraphQL API
+const DEFAULT_PAGE_SIZE: usize = 50;
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Block {
+    // Block hash without `block/` prefix
+    pub id: String,
+    pub seq_no: u32,
+    #[serde(default = "masterchain")]
+    pub workchain_id: i32,
+}
+
+fn masterchain() -> i32 {
+    MASTERCHAIN_ID
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct InMessage {
+    // Message hash without `message/` prefix
+    pub id: String,
+    // Base64 encoded message body BOC
+    pub body: Option<String>,
+    // 0 - internal, 1 - external inbound, 2 - external outbound
+    #[serde(default)]
+    pub msg_type: u8,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Transaction {
+    // Transaction hash without `transaction/` prefix
+    pub id: String,
+    pub lt: u64,
+    pub block_id: String,
+    // Seq no of the masterchain block which commits the transaction
+    pub master_seq_no: u32,
+    #[serde(default)]
+    pub aborted: bool,
+    pub in_message: Option<InMessage>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Account {
+    pub balance: u128,
+    // Base64 encoded account BOC, required to run getters locally
+    pub boc: Option<String>,
+    pub transactions: Vec<Transaction>,
+}
+
+// Message posted with `postRequests` mutation
+#[derive(Debug, Clone, PartialEq)]
+pub struct SentMessage {
+    pub id: String,
+    pub body: String,
+}
+
+pub(crate) struct GoshState {
+    pub blocks: Vec<Block>,
+    pub accounts: BTreeMap<String, Account>,
+    pub sent_messages: Vec<SentMessage>,
+    pub page_size: usize,
+}
+
+impl GoshState {
+    pub fn master_blocks(&self) -> impl Iterator<Item = &Block> {
+        self.blocks
+            .iter()
+            .filter(|block| block.workchain_id == MASTERCHAIN_ID)
+    }
+
+    pub fn account(&self, address: &str) -> Option<&Account> {
+        self.accounts.get(&address.to_lowercase())
+    }
+}
+
+// Fixture data served by the GraphQL stand-in. Handle is cheap to clone, all clones share the
+// same state, so a test can add blocks and transactions while the server is running.
+#[derive(Clone)]
+pub struct MockGosh {
+    pub(crate) state: Arc<Mutex<GoshState>>,
+}
+
+impl Default for MockGosh {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockGosh {
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(GoshState {
+                blocks: vec![],
+                accounts: BTreeMap::new(),
+                sent_messages: vec![],
+                page_size: DEFAULT_PAGE_SIZE,
+            })),
+        }
+    }
+
+    // Max number of edges returned in one page of a connection
+    pub fn set_page_size(&self, page_size: usize) {
+        self.state.lock().page_size = page_size.max(1);
+    }
+
+    pub fn add_block(&self, block: Block) {
+        let mut state = self.state.lock();
+        state.blocks.push(block);
+        state.blocks.sort_by_key(|block| block.seq_no);
+    }
+
+    // Add masterchain block with the next seq no
+    pub fn add_master_block(&self, id: &str) -> u32 {
+        let seq_no = self
+            .state
+            .lock()
+            .master_blocks()
+            .last()
+            .map_or(1, |block| block.seq_no + 1);
+        self.add_block(Block {
+            id: id.to_string(),
+            seq_no,
+            workchain_id: MASTERCHAIN_ID,
+        });
+        seq_no
+    }
+
+    pub fn add_account(&self, address: &str, balance: u128, boc: Option<String>) {
+        self.state.lock().accounts.insert(
+            address.to_lowercase(),
+            Account {
+                balance,
+                boc,
+                transactions: vec![],
+            },
+        );
+    }
+
+    pub fn set_balance(&self, address: &str, balance: u128) -> anyhow::Result<()> {
+        self.state
+            .lock()
+            .accounts
+            .get_mut(&address.to_lowercase())
+            .ok_or(anyhow::format_err!("Account {address} not found"))?
+            .balance = balance;
+        Ok(())
+    }
+
+    pub fn add_transaction(&self, address: &str, transaction: Transaction) -> anyhow::Result<()> {
+        let mut state = self.state.lock();
+        let account = state
+            .accounts
+            .get_mut(&address.to_lowercase())
+            .ok_or(anyhow::format_err!("Account {address} not found"))?;
+        account.transactions.push(transaction);
+        account.transactions.sort_by_key(|tx| tx.lt);
+        Ok(())
+    }
+
+    pub fn sent_messages(&self) -> Vec<SentMessage> {
+        self.state.lock().sent_messages.clone()
+    }
+}
@@ -0,0 +1,58 @@
+use crate::chain::{Block, MockGosh, Transaction};
+use serde::Deserialize;
+use std::path::Path;
+
+// Data served by the mock loaded from TOML file. See `gosh-mock/example.toml`.
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Fixture {
+    pub page_size: Option<usize>,
+    #[serde(default)]
+    pub blocks: Vec<Block>,
+    #[serde(default)]
+    pub accounts: Vec<Account>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Account {
+    pub address: String,
+    // Decimal balance in nanotokens
+    #[serde(default)]
+    pub balance: String,
+    pub boc: Option<String>,
+    #[serde(default)]
+    pub transactions: Vec<Transaction>,
+}
+
+impl Fixture {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::format_err!("Failed to read fixture {}: {e}", path.display()))?;
+        toml::from_str(&content)
+            .map_err(|e| anyhow::format_err!("Failed to parse fixture {}: {e}", path.display()))
+    }
+
+    pub fn build(&self) -> anyhow::Result<MockGosh> {
+        let gosh = MockGosh::new();
+        if let Some(page_size) = self.page_size {
+            gosh.set_page_size(page_size);
+        }
+        for block in &self.blocks {
+            gosh.add_block(block.clone());
+        }
+        for account in &self.accounts {
+            let balance = match account.balance.as_str() {
+                "" => 0,
+                balance => balance.parse::<u128>().map_err(|e| {
+                    anyhow::format_err!("Invalid balance of {}: {e}", account.address)
+                })?,
+            };
+            gosh.add_account(&account.address, balance, account.boc.clone());
+            for transaction in &account.transactions {
+                gosh.add_transaction(&account.address, transaction.clone())?;
+            }
+        }
+        Ok(gosh)
+    }
+}
@@ -0,0 +1,380 @@
+use crate::chain::{Account, GoshState, MockGosh, SentMessage, Transaction};
+use graphql_parser::query::{Definition, OperationDefinition, Selection, SelectionSet};
+use serde_json::{json, Map, Value};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+type Field<'a> = graphql_parser::query::Field<'a, String>;
+type GraphqlValue<'a> = graphql_parser::query::Value<'a, String>;
+
+const MOCK_VERSION: &str = "0.0.0-mock";
+// Fields of `BigInt` type, formatted according to their `format` argument
+const BIG_INT_FIELDS: [&str; 2] = ["balance", "lt"];
+
+struct Context<'a> {
+    state: &'a mut GoshState,
+    variables: &'a Map<String, Value>,
+}
+
+impl Context<'_> {
+    fn argument(&self, field: &Field, name: &str) -> Value {
+        field
+            .arguments
+            .iter()
+            .find(|(arg, _)| arg == name)
+            .map_or(Value::Null, |(_, value)| self.value(value))
+    }
+
+    fn value(&self, value: &GraphqlValue) -> Value {
+        match value {
+            GraphqlValue::Variable(name) => self.variables.get(name).cloned().unwrap_or_default(),
+            GraphqlValue::Int(number) => json!(number.as_i64()),
+            GraphqlValue::Float(number) => json!(number),
+            GraphqlValue::String(string) => json!(string),
+            GraphqlValue::Boolean(boolean) => json!(boolean),
+            GraphqlValue::Null => Value::Null,
+            GraphqlValue::Enum(name) => json!(name),
+            GraphqlValue::List(list) => list.iter().map(|v| self.value(v)).collect(),
+            GraphqlValue::Object(object) => Value::Object(
+                object
+                    .iter()
+                    .map(|(key, v)| (key.clone(), self.value(v)))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+fn fields<'a, 'b>(
+    selection_set: &'b SelectionSet<'a, String>,
+) -> impl Iterator<Item = &'b Field<'a>> {
+    selection_set
+        .items
+        .iter()
+        .filter_map(|selection| match selection {
+            Selection::Field(field) => Some(field),
+            _ => None,
+        })
+}
+
+fn response_key(field: &Field) -> String {
+    field.alias.clone().unwrap_or(field.name.clone())
+}
+
+fn format_big_int(value: &Value, format: &Value) -> Value {
+    match (
+        value.as_str().and_then(|v| v.parse::<u128>().ok()),
+        format.as_str(),
+    ) {
+        (Some(number), Some("DEC")) => json!(number.to_string()),
+        (Some(number), _) => json!(format!("0x{number:x}")),
+        (None, _) => value.clone(),
+    }
+}
+
+// Select requested fields from the resolved object
+fn project(context: &Context, value: &Value, selection_set: &SelectionSet<String>) -> Value {
+    match value {
+        Value::Array(items) => items
+            .iter()
+            .map(|item| project(context, item, selection_set))
+            .collect(),
+        Value::Object(object) => Value::Object(
+            fields(selection_set)
+                .map(|field| {
+                    let value = object.get(&field.name).cloned().unwrap_or_default();
+                    let value = if !field.selection_set.items.is_empty() {
+                        project(context, &value, &field.selection_set)
+                    } else if BIG_INT_FIELDS.contains(&field.name.as_str()) {
+                        format_big_int(&value, &context.argument(field, "format"))
+                    } else {
+                        value
+                    };
+                    (response_key(field), value)
+                })
+                .collect(),
+        ),
+        _ => Value::Null,
+    }
+}
+
+fn as_u32(value: &Value) -> Option<u32> {
+    value.as_u64().and_then(|v| u32::try_from(v).ok())
+}
+
+fn as_usize(value: &Value) -> Option<usize> {
+    value.as_u64().and_then(|v| usize::try_from(v).ok())
+}
+
+fn in_range(seq_no: u32, range: &Value) -> bool {
+    // Range start is inclusive and end is exclusive
+    as_u32(&range["start"]).map_or(true, |start| seq_no >= start)
+        && as_u32(&range["end"]).map_or(true, |end| seq_no < end)
+}
+
+// Relay style connection, nodes must be sorted by their cursor
+fn connection(context: &Context, field: &Field, nodes: Vec<(String, Value)>) -> Value {
+    let after = context.argument(field, "after");
+    let after = after.as_str().filter(|cursor| !cursor.is_empty());
+    let before = context.argument(field, "before");
+    let before = before.as_str().filter(|cursor| !cursor.is_empty());
+    let nodes: Vec<(String, Value)> = nodes
+        .into_iter()
+        .filter(|(cursor, _)| after.map_or(true, |after| cursor.as_str() > after))
+        .filter(|(cursor, _)| before.map_or(true, |before| cursor.as_str() < before))
+        .collect();
+
+    let page_size = context.state.page_size;
+    let total = nodes.len();
+    let (start, end) = match (
+        as_usize(&context.argument(field, "first")),
+        as_usize(&context.argument(field, "last")),
+    ) {
+        (_, Some(last)) => (total.saturating_sub(last.min(page_size)), total),
+        (first, None) => (0, first.unwrap_or(page_size).min(page_size).min(total)),
+    };
+    let page = &nodes[start..end];
+    let edges: Vec<Value> = page
+        .iter()
+        .map(|(cursor, node)| json!({"cursor": cursor, "node": node}))
+        .collect();
+    let value = json!({
+        "edges": edges,
+        "pageInfo": {
+            "hasNextPage": end < total,
+            "hasPreviousPage": start > 0,
+            "startCursor": page.first().map_or(after.unwrap_or_default(), |(c, _)| c.as_str()),
+            "endCursor": page.last().map_or(after.unwrap_or_default(), |(c, _)| c.as_str()),
+        },
+    });
+    project(context, &value, &field.selection_set)
+}
+
+fn block_node(state: &GoshState, id: &str) -> Option<Value> {
+    state
+        .blocks
+        .iter()
+        .find(|block| block.id == id)
+        .map(|block| {
+            json!({
+                "id": format!("block/{}", block.id),
+                "hash": block.id,
+                "seq_no": block.seq_no,
+                "workchain_id": block.workchain_id,
+            })
+        })
+}
+
+fn transaction_node(address: &str, tx: &Transaction) -> Value {
+    let in_message = tx.in_message.as_ref().map(|message| {
+        json!({
+            "id": format!("message/{}", message.id),
+            "hash": message.id,
+            "body": message.body,
+            "msg_type": message.msg_type,
+            "dst": address,
+        })
+    });
+    json!({
+        "id": format!("transaction/{}", tx.id),
+        "hash": tx.id,
+        "account_addr": address,
+        "lt": tx.lt.to_string(),
+        "block_id": tx.block_id,
+        "master_seq_no": tx.master_seq_no,
+        "aborted": tx.aborted,
+        "in_message": in_message,
+    })
+}
+
+fn account_info(address: &str, account: &Account) -> Value {
+    json!({
+        "id": address,
+        "address": address,
+        "balance": account.balance.to_string(),
+        "boc": account.boc,
+    })
+}
+
+fn resolve_account(
+    context: &Context,
+    address: &str,
+    selection_set: &SelectionSet<String>,
+) -> Value {
+    let account = match context.state.account(address) {
+        Some(account) => account,
+        None => return Value::Null,
+    };
+    Value::Object(
+        fields(selection_set)
+            .map(|field| {
+                let value = match field.name.as_str() {
+                    "info" => project(
+                        context,
+                        &account_info(address, account),
+                        &field.selection_set,
+                    ),
+                    "transactions" => {
+                        let range = context.argument(field, "master_seq_no_range");
+                        let nodes = account
+                            .transactions
+                            .iter()
+                            .filter(|tx| in_range(tx.master_seq_no, &range))
+                            .map(|tx| (format!("{:016x}", tx.lt), transaction_node(address, tx)))
+                            .collect();
+                        connection(context, field, nodes)
+                    }
+                    _ => Value::Null,
+                };
+                (response_key(field), value)
+            })
+            .collect(),
+    )
+}
+
+fn resolve_blockchain(
+    context: &Context,
+    selection_set: &SelectionSet<String>,
+) -> anyhow::Result<Value> {
+    let mut result = Map::new();
+    for field in fields(selection_set) {
+        let value = match field.name.as_str() {
+            "block" => {
+                let hash = context.argument(field, "hash");
+                let hash = hash
+                    .as_str()
+                    .unwrap_or_default()
+                    .trim_start_matches("block/");
+                block_node(context.state, hash).map_or(Value::Null, |block| {
+                    project(context, &block, &field.selection_set)
+                })
+            }
+            "blocks" => {
+                let workchain = context.argument(field, "workchain").as_i64();
+                let range = context.argument(field, "master_seq_no_range");
+                let nodes = context
+                    .state
+                    .blocks
+                    .iter()
+                    .filter(|block| workchain.map_or(true, |w| i64::from(block.workchain_id) == w))
+                    .filter(|block| in_range(block.seq_no, &range))
+                    .filter_map(|block| {
+                        let node = block_node(context.state, &block.id)?;
+                        Some((format!("{:08x}", block.seq_no), node))
+                    })
+                    .collect();
+                connection(context, field, nodes)
+            }
+            "account" => {
+                let address = context.argument(field, "address");
+                let address = address
+                    .as_str()
+                    .ok_or(anyhow::format_err!("Argument 'address' is required"))?;
+                resolve_account(context, address, &field.selection_set)
+            }
+            name => anyhow::bail!("Field blockchain.{name} is not supported by the mock"),
+        };
+        result.insert(response_key(field), value);
+    }
+    Ok(Value::Object(result))
+}
+
+// `accounts` collection, only `id: {eq: ...}` filter is supported
+fn resolve_accounts(context: &Context, field: &Field) -> Value {
+    let filter = context.argument(field, "filter");
+    let id = filter["id"]["eq"].as_str().map(|id| id.to_lowercase());
+    let limit = as_usize(&context.argument(field, "limit")).unwrap_or(context.state.page_size);
+    let accounts: Vec<Value> = context
+        .state
+        .accounts
+        .iter()
+        .filter(|(address, _)| id.as_ref().map_or(true, |id| *address == id))
+        .take(limit)
+        .map(|(address, account)| account_info(address, account))
+        .collect();
+    project(context, &Value::Array(accounts), &field.selection_set)
+}
+
+fn post_requests(context: &mut Context, field: &Field) -> anyhow::Result<Value> {
+    let requests = context.argument(field, "requests");
+    let requests = requests
+        .as_array()
+        .ok_or(anyhow::format_err!("Argument 'requests' is required"))?;
+    for request in requests {
+        let message = SentMessage {
+            id: request["id"].as_str().unwrap_or_default().to_string(),
+            body: request["body"].as_str().unwrap_or_default().to_string(),
+        };
+        tracing::info!("Received message {}", message.id);
+        context.state.sent_messages.push(message);
+    }
+    Ok(Value::Null)
+}
+
+fn resolve_root(context: &mut Context, field: &Field) -> anyhow::Result<Value> {
+    match field.name.as_str() {
+        "info" => {
+            let time = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis() as u64;
+            let info = json!({
+                "version": MOCK_VERSION,
+                "time": time,
+                "latency": 0,
+                "lastBlockTime": time,
+                "rempEnabled": false,
+            });
+            Ok(project(context, &info, &field.selection_set))
+        }
+        "blockchain" => resolve_blockchain(context, &field.selection_set),
+        "accounts" => Ok(resolve_accounts(context, field)),
+        "postRequests" => post_requests(context, field),
+        name => anyhow::bail!("Field {name} is not supported by the mock"),
+    }
+}
+
+fn execute(state: &mut GoshState, request: &Value) -> anyhow::Result<Value> {
+    let query = request["query"]
+        .as_str()
+        .ok_or(anyhow::format_err!("Request does not contain query"))?;
+    let document = graphql_parser::parse_query::<String>(query)
+        .map_err(|e| anyhow::format_err!("Failed to parse query: {e}"))?;
+    let selection_set = document
+        .definitions
+        .iter()
+        .find_map(|definition| match definition {
+            Definition::Operation(OperationDefinition::SelectionSet(set)) => Some(set),
+            Definition::Operation(OperationDefinition::Query(query)) => Some(&query.selection_set),
+            Definition::Operation(OperationDefinition::Mutation(mutation)) => {
+                Some(&mutation.selection_set)
+            }
+            _ => None,
+        })
+        .ok_or(anyhow::format_err!("Query does not contain an operation"))?;
+    let variables = match &request["variables"] {
+        Value::Object(variables) => variables.clone(),
+        _ => Map::new(),
+    };
+
+    let mut context = Context {
+        state,
+        variables: &variables,
+    };
+    let mut data = Map::new();
+    for field in fields(selection_set) {
+        let value = resolve_root(&mut context, field)?;
+        data.insert(response_key(field), value);
+    }
+    Ok(json!({ "data": data }))
+}
+
+impl MockGosh {
+    // Execute GraphQL request `{"query": ..., "variables": ...}`
+    pub(crate) fn handle_request(&self, request: &Value) -> Value {
+        let mut state = self.state.lock();
+        execute(&mut state, request).unwrap_or_else(|e| {
+            tracing::info!("GraphQL request failed: {e}");
+            json!({
+                "data": null,
+                "errors": [{"message": e.to_string()}],
+            })
+        })
+    }
+}
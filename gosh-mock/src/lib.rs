@@ -0,0 +1,7 @@
+mod chain;
+pub mod fixture;
+mod graphql;
+mod server;
+
+pub use chain::{Block, InMessage, MockGosh, SentMessage, Transaction};
+pub use server::MockServer;
@@ -0,0 +1,35 @@
+use clap::Parser;
+use common::helper::tracing::init_default_tracing;
+use gosh_mock::fixture::Fixture;
+use gosh_mock::MockServer;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "gosh-mock",
+    version,
+    about = "Local GOSH GraphQL endpoint serving fixture data"
+)]
+struct Args {
+    /// Path to the fixture with blocks, accounts and transactions
+    #[arg(long, env = "GOSH_MOCK_FIXTURE")]
+    fixture: PathBuf,
+
+    /// Address of the GraphQL endpoint
+    #[arg(long, default_value = "127.0.0.1:8080")]
+    listen: SocketAddr,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    init_default_tracing();
+    let args = Args::parse();
+
+    let gosh = Fixture::load(&args.fixture)?.build()?;
+    let server = MockServer::bind(gosh, args.listen).await?;
+    println!("GraphQL endpoint: {}", server.url());
+
+    server.join().await;
+    Ok(())
+}
@@ -0,0 +1,256 @@
+use crate::chain::MockGosh;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, StatusCode};
+use serde_json::{json, Value};
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use tokio::task::JoinHandle;
+
+// Read GraphQL request from JSON body of POST or from query string of GET request
+async fn read_request(request: Request<Body>) -> anyhow::Result<Value> {
+    if request.method() == Method::GET {
+        let mut result = json!({});
+        let query = request.uri().query().unwrap_or_default();
+        for (key, value) in form_urlencoded::parse(query.as_bytes()) {
+            match key.as_ref() {
+                "query" => result["query"] = json!(value),
+                "variables" => result["variables"] = serde_json::from_str(&value)?,
+                _ => {}
+            }
+        }
+        return Ok(result);
+    }
+    let body = hyper::body::to_bytes(request.into_body()).await?;
+    serde_json::from_slice(&body).map_err(|e| anyhow::format_err!("Failed to parse request: {e}"))
+}
+
+async fn handle_http(gosh: MockGosh, request: Request<Body>) -> Result<Response<Body>, Infallible> {
+    let (status, response) = match read_request(request).await {
+        Ok(request) => (StatusCode::OK, gosh.handle_request(&request)),
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            json!({"data": null, "errors": [{"message": e.to_string()}]}),
+        ),
+    };
+    Ok(Response::builder()
+        .status(status)
+        .header("Content-Type", "application/json")
+        .body(Body::from(response.to_string()))
+        .unwrap())
+}
+
+// GraphQL server exposing fixture data over HTTP. Server is stopped on drop.
+pub struct MockServer {
+    addr: SocketAddr,
+    task: Option<JoinHandle<()>>,
+}
+
+impl MockServer {
+    // Start server on a random local port
+    pub async fn start(gosh: MockGosh) -> anyhow::Result<Self> {
+        Self::bind(gosh, ([127, 0, 0, 1], 0).into()).await
+    }
+
+    pub async fn bind(gosh: MockGosh, addr: SocketAddr) -> anyhow::Result<Self> {
+        let server = hyper::Server::try_bind(&addr)
+            .map_err(|e| anyhow::format_err!("Failed to bind GraphQL server to {addr}: {e}"))?
+            .serve(make_service_fn(move |_| {
+                let gosh = gosh.clone();
+                async move {
+                    Ok::<_, Infallible>(service_fn(move |request| {
+                        handle_http(gosh.clone(), request)
+                    }))
+                }
+            }));
+        let addr = server.local_addr();
+        let task = tokio::spawn(async move {
+            if let Err(e) = server.await {
+                tracing::error!("GraphQL server failed: {e}");
+            }
+        });
+        tracing::info!("GOSH mock is listening on http://{addr}");
+        Ok(Self {
+            addr,
+            task: Some(task),
+        })
+    }
+
+    // Endpoint for `gosh.endpoints` config, GraphQL is served on any path
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    // Wait until server task is finished
+    pub async fn join(mut self) {
+        if let Some(task) = self.task.take() {
+            let _ = task.await;
+        }
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        if let Some(task) = &self.task {
+            task.abort();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MockServer;
+    use crate::chain::{InMessage, MockGosh, SentMessage, Transaction};
+    use async_trait::async_trait;
+    use common::chain::{DecodedMessageBody, GoshChain};
+    use common::gosh::balance::query_balance;
+    use common::gosh::block::{get_latest_master_block, get_master_block_seq_no};
+    use common::gosh::message::query_messages;
+    use hyper::{Body, Client, Method, Request};
+    use serde_json::{json, Value};
+    use ton_client::crypto::KeyPair;
+
+    const RECEIVER: &str = "0:1111111111111111111111111111111111111111111111111111111111111111";
+
+    // Sends GraphQL queries to the mock the same way as the SDK does in HTTP mode
+    struct GraphqlClient {
+        url: String,
+    }
+
+    #[async_trait]
+    impl GoshChain for GraphqlClient {
+        async fn run_getter(
+            &self,
+            _address: &str,
+            _abi: &str,
+            _function: &str,
+            _args: Option<Value>,
+        ) -> anyhow::Result<Value> {
+            anyhow::bail!("Getters are not supported by the test client")
+        }
+
+        async fn call_function(
+            &self,
+            _address: &str,
+            _abi: &str,
+            _keys: Option<KeyPair>,
+            _function: &str,
+            _args: Option<Value>,
+        ) -> anyhow::Result<String> {
+            anyhow::bail!("Calls are not supported by the test client")
+        }
+
+        async fn query(&self, query: &str, variables: Option<Value>) -> anyhow::Result<Value> {
+            let request = Request::builder()
+                .method(Method::POST)
+                .uri(format!("{}/graphql", self.url))
+                .header("Content-Type", "application/json")
+                .body(Body::from(
+                    json!({"query": query, "variables": variables}).to_string(),
+                ))?;
+            let response = Client::new().request(request).await?;
+            let body = hyper::body::to_bytes(response.into_body()).await?;
+            let result: Value = serde_json::from_slice(&body)?;
+            if let Some(errors) = result.get("errors") {
+                anyhow::bail!("Query failed: {errors}");
+            }
+            Ok(result)
+        }
+
+        async fn decode_message_body(
+            &self,
+            _abi: &str,
+            _body: &str,
+        ) -> anyhow::Result<DecodedMessageBody> {
+            anyhow::bail!("Decoding is not supported by the test client")
+        }
+    }
+
+    fn transaction(lt: u64, master_seq_no: u32, aborted: bool) -> Transaction {
+        Transaction {
+            id: format!("{lt:064x}"),
+            lt,
+            block_id: format!("{master_seq_no:064x}"),
+            master_seq_no,
+            aborted,
+            in_message: Some(InMessage {
+                id: format!("{:064x}", lt + 1),
+                body: Some("te6ccgEBAQEAAgAAAA==".to_string()),
+                msg_type: 0,
+            }),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_blocks_and_balance() -> anyhow::Result<()> {
+        let gosh = MockGosh::new();
+        gosh.add_master_block("aa");
+        let seq_no = gosh.add_master_block("bb");
+        gosh.add_account(
+            RECEIVER,
+            1_500_000_000,
+            Some("te6ccgEBAQEAAgAAAA==".to_string()),
+        );
+
+        let server = MockServer::start(gosh.clone()).await?;
+        let client = GraphqlClient { url: server.url() };
+
+        let block = get_latest_master_block(&client).await?;
+        assert_eq!(block.seq_no, u128::from(seq_no));
+        assert_eq!(block.block_id, "bb");
+        assert_eq!(get_master_block_seq_no(&client, "aa").await?, 1);
+        assert_eq!(query_balance(&client, RECEIVER).await?, 1_500_000_000);
+
+        // Account state as it is queried by `run_getter`
+        let result = client
+            .query(
+                "query($id: String){ accounts(filter: {id: {eq: $id}}, limit: 1) { boc } }",
+                Some(json!({ "id": RECEIVER })),
+            )
+            .await?;
+        assert_eq!(
+            result["data"]["accounts"],
+            json!([{"boc": "te6ccgEBAQEAAgAAAA=="}])
+        );
+
+        client
+            .query(
+                "mutation($requests: [Request]) { postRequests(requests: $requests) }",
+                Some(json!({"requests": [{"id": "msg", "body": "body", "expireAt": 0}]})),
+            )
+            .await?;
+        assert_eq!(
+            gosh.sent_messages(),
+            vec![SentMessage {
+                id: "msg".to_string(),
+                body: "body".to_string()
+            }]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_query_messages_pagination() -> anyhow::Result<()> {
+        let gosh = MockGosh::new();
+        gosh.set_page_size(2);
+        gosh.add_account(RECEIVER, 0, None);
+        gosh.add_transaction(RECEIVER, transaction(10, 1, false))?;
+        gosh.add_transaction(RECEIVER, transaction(20, 2, false))?;
+        gosh.add_transaction(RECEIVER, transaction(30, 2, true))?;
+        gosh.add_transaction(RECEIVER, transaction(40, 3, false))?;
+        gosh.add_transaction(RECEIVER, transaction(50, 4, false))?;
+        gosh.add_transaction(RECEIVER, transaction(60, 5, false))?;
+
+        let server = MockServer::start(gosh).await?;
+        let client = GraphqlClient { url: server.url() };
+
+        let messages = query_messages(&client, RECEIVER, 2, 5).await?;
+        let lts: Vec<u128> = messages.iter().map(|message| message.lt).collect();
+        assert_eq!(lts, vec![20, 40, 50]);
+        assert_eq!(messages[0].tx_id, format!("{:064x}", 20));
+        assert_eq!(messages[0].id, format!("{:064x}", 21));
+
+        let messages = query_messages(&client, RECEIVER, 6, 10).await?;
+        assert!(messages.is_empty());
+        Ok(())
+    }
+}
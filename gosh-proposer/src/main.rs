@@ -1,14 +1,27 @@
-mod proposer;
-
-use crate::proposer::propose_eth_blocks;
+use common::config::BridgeConfig;
+use common::helper::take_dry_run_flag;
 use common::helper::tracing::init_default_tracing;
+use gosh_proposer::proposer::daemon::run_daemon;
+use gosh_proposer::proposer::propose_eth_blocks;
+use std::env;
+use std::process::ExitCode;
 
 #[tokio::main]
-async fn main() -> anyhow::Result<()> {
+async fn main() -> anyhow::Result<ExitCode> {
     // Load env variables from '.env' file
     dotenv::dotenv().ok();
     // Init tracing in level specified with env 'GOSH_LOG' or "info" level by default
     init_default_tracing();
-    // Propose eth blocks to GOSH
-    propose_eth_blocks().await
+    // Load and validate config
+    let mut config = BridgeConfig::load()?;
+
+    let mut args: Vec<String> = env::args().collect();
+    take_dry_run_flag(&mut config, &mut args);
+    match args.get(1).map(|s| s.as_str()) {
+        // Propose eth blocks to GOSH in loop
+        Some("--daemon") => run_daemon(&config).await.map(|_| ExitCode::SUCCESS),
+        Some(arg) => anyhow::bail!("Unknown argument: {arg}"),
+        // Propose eth blocks to GOSH once
+        None => propose_eth_blocks(&config).await?.report(),
+    }
 }
@@ -0,0 +1,65 @@
+use crate::proposer::leader::Leader;
+use crate::proposer::propose_new_blocks;
+use common::config::BridgeConfig;
+use common::dry_run::DryRun;
+use common::eth::create_eth_client;
+use common::gosh::helper::create_client;
+use common::retry::Retry;
+use common::store::Store;
+use tokio::signal::unix::{signal, SignalKind};
+use tokio::time::MissedTickBehavior;
+
+pub async fn run_daemon(config: &BridgeConfig) -> anyhow::Result<()> {
+    let interval = config.proposer.interval;
+    tracing::info!("Start proposer daemon with interval {interval:?}");
+
+    // Clients and store are created once and reused between cycles
+    let client = Retry::new(create_client(&config.gosh)?, &config.retry);
+    let mut web3s = create_eth_client(&config.eth, &config.retry).await?;
+    let store = Store::from_config(&config.store)?;
+    let leader = Leader::from_config(client.inner(), config)?;
+    // With `--dry-run` GOSH messages are printed instead of being sent
+    let client = DryRun::new(&client, config.dry_run);
+
+    let mut sigterm = signal(SignalKind::terminate())
+        .map_err(|e| anyhow::format_err!("Failed to set SIGTERM handler: {e}"))?;
+    let mut sigint = signal(SignalKind::interrupt())
+        .map_err(|e| anyhow::format_err!("Failed to set SIGINT handler: {e}"))?;
+
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        // Signals received during a cycle are buffered, so the current cycle is always finished
+        // before shutdown
+        tokio::select! {
+            _ = ticker.tick() => {}
+            _ = sigterm.recv() => {
+                tracing::info!("Received SIGTERM, stop proposer daemon");
+                break;
+            }
+            _ = sigint.recv() => {
+                tracing::info!("Received SIGINT, stop proposer daemon");
+                break;
+            }
+        }
+
+        // Check that ETH connection is still alive and reconnect otherwise
+        if let Err(e) = web3s.primary().inner().eth().block_number().await {
+            tracing::warn!("ETH connection failed, reconnecting: {e}");
+            web3s = match create_eth_client(&config.eth, &config.retry).await {
+                Ok(web3s) => web3s,
+                Err(e) => {
+                    tracing::error!("Failed to reconnect to ETH: {e}");
+                    continue;
+                }
+            };
+        }
+
+        match propose_new_blocks(config, &web3s, &client, &store, leader.as_ref()).await {
+            Ok(outcome) => tracing::info!("Propose cycle finished: {outcome:?}"),
+            Err(e) => tracing::error!("Propose cycle failed: {e}"),
+        }
+    }
+    Ok(())
+}
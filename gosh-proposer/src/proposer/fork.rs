@@ -0,0 +1,147 @@
+use common::chain::EthChain;
+use common::error::BridgeError;
+use common::eth::{read_block_cached, FullBlock};
+use common::outcome::Outcome;
+use common::store::Store;
+use web3::types::{BlockId, BlockNumber, H256};
+
+// Orphaned blocks are searched for the fork point only this deep, providers usually do not keep
+// older non-canonical blocks anyway
+const MAX_FORK_SEARCH_DEPTH: u64 = 128;
+
+// Block saved in GOSH checker is not in the canonical ETH chain
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fork {
+    pub saved_block: FullBlock<H256>,
+    // Hash of the canonical block with the same number
+    pub canonical_hash: H256,
+    // Last block common for the saved and the canonical chains, if it was found
+    pub ancestor: Option<FullBlock<H256>>,
+}
+
+impl Fork {
+    // Number of orphaned blocks from the fork point up to the saved one
+    pub fn depth(&self) -> Option<u64> {
+        let saved = self.saved_block.number?;
+        let ancestor = self.ancestor.as_ref()?.number?;
+        Some((saved - ancestor).as_u64())
+    }
+
+    pub fn outcome(&self) -> Outcome {
+        let format_hash = |block: &FullBlock<H256>| format!("{:?}", block.hash.unwrap_or_default());
+        Outcome::Reorg {
+            saved_block: self.saved_block.number.unwrap_or_default().as_u64(),
+            saved_hash: format_hash(&self.saved_block),
+            canonical_hash: format!("{:?}", self.canonical_hash),
+            fork_block: self
+                .ancestor
+                .as_ref()
+                .map(|ancestor| ancestor.number.unwrap_or_default().as_u64()),
+            fork_hash: self.ancestor.as_ref().map(format_hash),
+            depth: self.depth(),
+        }
+    }
+}
+
+async fn canonical_hash(web3s: &dyn EthChain, block: &FullBlock<H256>) -> anyhow::Result<H256> {
+    let number = block.number.ok_or(anyhow::format_err!(
+        "ETH block {:?} has no number",
+        block.hash
+    ))?;
+    web3s
+        .read_block(BlockId::Number(BlockNumber::Number(number)))
+        .await?
+        .hash
+        .ok_or(anyhow::format_err!(
+            "Canonical ETH block {number} has no hash"
+        ))
+}
+
+// Check that the block saved in GOSH checker is canonical and find the fork point otherwise
+pub async fn find_fork(
+    web3s: &dyn EthChain,
+    store: &Store,
+    saved_block: &FullBlock<H256>,
+) -> anyhow::Result<Option<Fork>> {
+    let canonical = canonical_hash(web3s, saved_block).await?;
+    if saved_block.hash == Some(canonical) {
+        return Ok(None);
+    }
+
+    // Walk back through the orphaned chain until it meets the canonical one
+    let mut ancestor = None;
+    let mut block = saved_block.clone();
+    for _ in 0..MAX_FORK_SEARCH_DEPTH {
+        block = match read_block_cached(web3s, store, BlockId::Hash(block.parent_hash)).await {
+            Ok(block) => block,
+            Err(e) if BridgeError::is_not_found(&e) => {
+                tracing::warn!("Failed to find fork point, orphaned block is not available: {e}");
+                break;
+            }
+            Err(e) => return Err(e),
+        };
+        if block.hash == Some(canonical_hash(web3s, &block).await?) {
+            ancestor = Some(block);
+            break;
+        }
+    }
+
+    Ok(Some(Fork {
+        saved_block: saved_block.clone(),
+        canonical_hash: canonical,
+        ancestor,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::find_fork;
+    use common::chain::fake::FakeEth;
+    use common::config::StoreConfig;
+    use common::eth::FullBlock;
+    use common::store::Store;
+    use web3::types::{H256, U64};
+
+    fn block(number: u64, hash: u64, parent: u64) -> FullBlock<H256> {
+        FullBlock {
+            hash: Some(H256::from_low_u64_be(hash)),
+            number: Some(U64::from(number)),
+            parent_hash: H256::from_low_u64_be(parent),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_find_fork() -> anyhow::Result<()> {
+        let store = Store::from_config(&StoreConfig { path: None })?;
+        let eth = FakeEth::new();
+        // Canonical chain 10 <- 11 <- 12 <- 13, orphaned blocks 111 <- 112 fork after 10
+        for (number, hash, parent) in [(10, 10, 9), (11, 11, 10), (12, 12, 11), (13, 13, 12)] {
+            eth.add_block(block(number, hash, parent));
+        }
+        eth.add_block(block(11, 111, 10));
+        eth.add_block(block(12, 112, 111));
+
+        assert_eq!(find_fork(&eth, &store, &block(12, 12, 11)).await?, None);
+
+        let fork = find_fork(&eth, &store, &block(12, 112, 111))
+            .await?
+            .unwrap();
+        assert_eq!(fork.canonical_hash, H256::from_low_u64_be(12));
+        assert_eq!(fork.ancestor, Some(block(10, 10, 9)));
+        assert_eq!(fork.depth(), Some(2));
+
+        let fork = find_fork(&eth, &store, &block(13, 113, 112))
+            .await?
+            .unwrap();
+        assert_eq!(fork.depth(), Some(3));
+
+        // Orphaned parent is not available, fork point is unknown
+        let fork = find_fork(&eth, &store, &block(12, 212, 211))
+            .await?
+            .unwrap();
+        assert_eq!(fork.ancestor, None);
+        assert_eq!(fork.depth(), None);
+        Ok(())
+    }
+}
@@ -0,0 +1,287 @@
+use common::chain::GoshChain;
+use common::checker::find_proposals;
+use common::config::BridgeConfig;
+use common::error::BridgeError;
+use common::gosh::helper::{get_validator_keys, EverClient};
+use common::gosh::validators::{get_proposal_validator_set, get_validator_set, normalize_pubkey};
+use common::store::Store;
+use std::str::FromStr;
+use web3::types::H256;
+
+// Rotation of proposers between validators. The turn depends only on data all validators see the
+// same: number of the ETH block saved in the checker, number of the last final ETH block and the
+// validator set of proposals ('getValidatorsSet'). The first leader for the saved block is chosen by its number, and if no
+// proposal is accepted in `window` ETH blocks, the next validator takes over.
+pub struct Leader {
+    // Public keys of validators run by this node
+    pubkeys: Vec<String>,
+    window: u64,
+}
+
+// Number of windows passed since the saved block
+fn turn(saved_block: u64, last_block: u64, window: u64) -> u64 {
+    last_block.saturating_sub(saved_block + 1) / window
+}
+
+// Index of the validator whose turn it is to propose blocks after the saved one
+pub fn leader_index(saved_block: u64, last_block: u64, window: u64, validators: usize) -> usize {
+    ((saved_block + turn(saved_block, last_block, window)) % validators as u64) as usize
+}
+
+impl Leader {
+    pub fn new(pubkeys: Vec<String>, window: u64) -> Self {
+        Self {
+            pubkeys: pubkeys
+                .into_iter()
+                .map(|pubkey| normalize_pubkey(&pubkey))
+                .collect(),
+            window,
+        }
+    }
+
+    // Leader rotation is enabled if the turn window is configured
+    pub fn from_config(
+        context: &EverClient,
+        config: &BridgeConfig,
+    ) -> anyhow::Result<Option<Self>> {
+        match config.proposer.leader_window {
+            Some(window) => {
+                let keys = get_validator_keys(context, &config.gosh)?;
+                tracing::info!("Leader rotation is enabled with window of {window} ETH blocks");
+                Ok(Some(Self::new(
+                    keys.into_iter().map(|key| key.public).collect(),
+                    window,
+                )))
+            }
+            None => Ok(None),
+        }
+    }
+
+    // Check whether this node should propose blocks after the saved one. Returns the reason to
+    // wait if it should not.
+    pub async fn check_turn(
+        &self,
+        client: &dyn GoshChain,
+        store: &Store,
+        checker_address: &str,
+        saved_hash: H256,
+        saved_block: u64,
+        last_block: u64,
+    ) -> anyhow::Result<Option<String>> {
+        // Proposal for the saved block waits for votes during the turn it was seen in. If it was
+        // not accepted in that turn, the next leader proposes again.
+        let current_turn = turn(saved_block, last_block, self.window);
+        let proposals = find_proposals(client, checker_address).await?;
+        for proposal in proposals
+            .iter()
+            .filter(|proposal| H256::from_str(&proposal.details.hash).ok() == Some(saved_hash))
+        {
+            let seen_at = store.proposal_seen_at(&proposal.address, last_block)?;
+            if turn(saved_block, seen_at, self.window) == current_turn {
+                return Ok(Some(format!(
+                    "proposal {} for block {saved_hash:?} already exists",
+                    proposal.address
+                )));
+            }
+            tracing::warn!(
+                "Proposal {} for block {saved_hash:?} seen on ETH block {seen_at} is stalled",
+                proposal.address
+            );
+        }
+
+        // Validator ids are assigned by proposals, so the set is taken from the proposal for the
+        // saved block or the latest one. It must match the current config, which is the only
+        // source before the first proposal, otherwise validators may disagree on the turn.
+        let validators = get_validator_set(client).await?;
+        let proposal = proposals
+            .iter()
+            .find(|proposal| H256::from_str(&proposal.details.hash).ok() == Some(saved_hash))
+            .or(proposals.last());
+        if let Some(proposal) = proposal {
+            let proposal_validators = get_proposal_validator_set(client, &proposal.address).await?;
+            if proposal_validators != validators {
+                return Err(BridgeError::ValidationMismatch(format!(
+                    "Validator set of proposal {} does not match GOSH config: {proposal_validators:?} != {validators:?}",
+                    proposal.address
+                ))
+                .into());
+            }
+        }
+        if validators.is_empty() {
+            anyhow::bail!("GOSH validator set is empty");
+        }
+        let leader = leader_index(saved_block, last_block, self.window, validators.len());
+        if self.pubkeys.contains(&validators[leader]) {
+            tracing::info!(
+                "Turn of validator {leader} (0x{}) to propose",
+                validators[leader]
+            );
+            Ok(None)
+        } else {
+            Ok(Some(format!(
+                "turn of validator {leader} (0x{}) to propose",
+                validators[leader]
+            )))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{leader_index, Leader};
+    use common::chain::fake::FakeGosh;
+    use common::error::BridgeError;
+    use common::store::Store;
+    use serde_json::json;
+    use web3::types::H256;
+
+    #[test]
+    fn test_leader_index() {
+        // Every new saved block starts with the next validator
+        assert_eq!(leader_index(100, 101, 10, 4), 0);
+        assert_eq!(leader_index(101, 102, 10, 4), 1);
+        // The same leader during the window, then the next one takes over
+        assert_eq!(leader_index(100, 110, 10, 4), 0);
+        assert_eq!(leader_index(100, 111, 10, 4), 1);
+        assert_eq!(leader_index(100, 141, 10, 4), 0);
+    }
+
+    #[tokio::test]
+    async fn test_check_turn() -> anyhow::Result<()> {
+        let client = FakeGosh::new();
+        client.set_query(
+            "key_blocks",
+            None,
+            json!({"data": {"blockchain": {"key_blocks": {"edges": [{"node": {"master": {
+                "config": {"p34": {"list": [{"public_key": "aa"}, {"public_key": "bb"}]}}
+            }}}]}}}}),
+        );
+        client.set_getter("0:01", "getAllProposalAddr", None, json!({"value0": []}));
+        let store = Store::in_memory()?;
+        let leader = Leader::new(vec!["0xBB".to_string()], 10);
+        let saved_hash = H256::from_low_u64_be(1);
+
+        assert!(leader
+            .check_turn(&client, &store, "0:01", saved_hash, 100, 101)
+            .await?
+            .is_some());
+        assert_eq!(
+            leader
+                .check_turn(&client, &store, "0:01", saved_hash, 100, 111)
+                .await?,
+            None
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_stalled_proposal() -> anyhow::Result<()> {
+        let client = FakeGosh::new();
+        client.set_query(
+            "key_blocks",
+            None,
+            json!({"data": {"blockchain": {"key_blocks": {"edges": [{"node": {"master": {
+                "config": {"p34": {"list": [{"public_key": "aa"}, {"public_key": "bb"}]}}
+            }}}]}}}}),
+        );
+        let saved_hash = H256::from_low_u64_be(1);
+        client.set_getter(
+            "0:01",
+            "getAllProposalAddr",
+            None,
+            json!({"value0": ["0:02"]}),
+        );
+        client.set_getter(
+            "0:02",
+            "getDetails",
+            None,
+            json!({
+                "hash": format!("{saved_hash:?}"),
+                "newhash": format!("{:?}", H256::from_low_u64_be(2)),
+                "transactions": [],
+                "index": "0",
+                "need": "2",
+            }),
+        );
+        client.set_getter(
+            "0:02",
+            "getValidatorsSet",
+            None,
+            json!({"value0": {"0": format!("0x{:064x}", 0xaa), "1": format!("0x{:064x}", 0xbb)}}),
+        );
+        let store = Store::in_memory()?;
+        let first = Leader::new(vec!["0xAA".to_string()], 10);
+        let second = Leader::new(vec!["0xBB".to_string()], 10);
+
+        // Proposal of the first leader blocks others during its turn
+        assert!(first
+            .check_turn(&client, &store, "0:01", saved_hash, 100, 105)
+            .await?
+            .is_some());
+        assert!(second
+            .check_turn(&client, &store, "0:01", saved_hash, 100, 110)
+            .await?
+            .is_some());
+        // It was not accepted in the turn, so the next leader takes over
+        assert_eq!(
+            second
+                .check_turn(&client, &store, "0:01", saved_hash, 100, 111)
+                .await?,
+            None
+        );
+        assert!(first
+            .check_turn(&client, &store, "0:01", saved_hash, 100, 111)
+            .await?
+            .is_some());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_validator_set_mismatch() -> anyhow::Result<()> {
+        let client = FakeGosh::new();
+        client.set_query(
+            "key_blocks",
+            None,
+            json!({"data": {"blockchain": {"key_blocks": {"edges": [{"node": {"master": {
+                "config": {"p34": {"list": [{"public_key": "aa"}, {"public_key": "bb"}]}}
+            }}}]}}}}),
+        );
+        client.set_getter(
+            "0:01",
+            "getAllProposalAddr",
+            None,
+            json!({"value0": ["0:02"]}),
+        );
+        client.set_getter(
+            "0:02",
+            "getDetails",
+            None,
+            json!({
+                "hash": format!("{:?}", H256::from_low_u64_be(1)),
+                "newhash": format!("{:?}", H256::from_low_u64_be(2)),
+                "transactions": [],
+                "index": "0",
+                "need": "2",
+            }),
+        );
+        client.set_getter(
+            "0:02",
+            "getValidatorsSet",
+            None,
+            json!({"value0": {"0": format!("0x{:064x}", 0xbb), "1": format!("0x{:064x}", 0xaa)}}),
+        );
+        let store = Store::in_memory()?;
+        let leader = Leader::new(vec!["0xBB".to_string()], 10);
+
+        // Validators could disagree on the turn, so nobody proposes
+        let err = leader
+            .check_turn(&client, &store, "0:01", H256::from_low_u64_be(3), 100, 111)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            BridgeError::find(&err),
+            Some(BridgeError::ValidationMismatch(_))
+        ));
+        Ok(())
+    }
+}
@@ -1,50 +1,106 @@
+use crate::proposer::fork::find_fork;
+use crate::proposer::leader::Leader;
 use crate::proposer::propose::propose_blocks;
-use common::eth::{create_web3_socket, read_block};
+use common::chain::{EthChain, GoshChain};
+use common::config::BridgeConfig;
+use common::dry_run::DryRun;
+use common::eth::encoder::serialize_block;
+use common::eth::{check_chain, create_eth_client, read_block_cached, read_blocks};
 use common::gosh::helper::create_client;
-use std::env;
-use std::str::FromStr;
+use common::outcome::Outcome;
+use common::retry::Retry;
+use common::store::Store;
 
-use common::checker::{get_block_from_checker, get_checker_address};
+use common::checker::get_block_from_checker;
 use web3::types::{BlockId, BlockNumber};
 
+pub mod daemon;
+mod fork;
+mod leader;
 mod propose;
 
-const DEFAULT_MAX_BLOCK_IN_ONE_CHUNK: u64 = 20;
-
-pub async fn propose_eth_blocks() -> anyhow::Result<()> {
+pub async fn propose_eth_blocks(config: &BridgeConfig) -> anyhow::Result<Outcome> {
     // Create client for ETH
-    let web3s = create_web3_socket().await?;
+    let web3s = create_eth_client(&config.eth, &config.retry).await?;
 
     // Create client for GOSH
-    let client = create_client()?;
+    let client = Retry::new(create_client(&config.gosh)?, &config.retry);
+
+    // Open local store
+    let store = Store::from_config(&config.store)?;
+
+    let leader = Leader::from_config(client.inner(), config)?;
 
+    // With `--dry-run` GOSH messages are printed instead of being sent
+    let client = DryRun::new(&client, config.dry_run);
+
+    propose_new_blocks(config, &web3s, &client, &store, leader.as_ref()).await
+}
+
+// Run one propose cycle with already created ETH and GOSH clients
+pub async fn propose_new_blocks(
+    config: &BridgeConfig,
+    web3s: &dyn EthChain,
+    client: &dyn GoshChain,
+    store: &Store,
+    leader: Option<&Leader>,
+) -> anyhow::Result<Outcome> {
     // Get checker address
-    let checker_address = get_checker_address()?;
+    let checker_address = &config.gosh.checker_address;
 
     // Get oldest saved block hash from GOSH checker
-    let first_block_hash = get_block_from_checker(&client, &checker_address).await?;
-    let first_block_number = read_block(&web3s, BlockId::Hash(first_block_hash))
-        .await?
-        .number
-        .ok_or(anyhow::format_err!(
-            "Failed to read Eth block with hash from GOSH checker: {}",
-            web3::helpers::to_string(&first_block_hash)
-        ))?;
+    let first_block_hash = get_block_from_checker(client, checker_address).await?;
+    let first_block = read_block_cached(web3s, store, BlockId::Hash(first_block_hash)).await?;
+    let first_block_number = first_block.number.ok_or(anyhow::format_err!(
+        "Failed to read Eth block with hash from GOSH checker: {}",
+        web3::helpers::to_string(&first_block_hash)
+    ))?;
 
     tracing::info!("Saved block number: {}", first_block_number.as_u64());
 
-    // Get the latest GOSH block
-    let mut block_id = BlockId::Number(BlockNumber::Finalized);
-    let last_block_number = read_block(&web3s, block_id)
+    // Get the latest ETH block of the configured finality
+    let finality = config.proposer.finality;
+    let last_block_number = read_block_cached(web3s, store, BlockId::Number(finality))
         .await?
         .number
         .ok_or(anyhow::format_err!("Failed to read latest Eth block"))?;
-    tracing::info!("Last block number: {}", last_block_number.as_u64());
+    tracing::info!(
+        "Last {finality:?} block number: {}",
+        last_block_number.as_u64()
+    );
 
     // exit if the latest ETH block is already set
     if last_block_number <= first_block_number {
         tracing::info!("Saved block in GOSH is newer than queried finalized block. {last_block_number} <= {first_block_number}");
-        std::process::exit(0);
+        return Ok(Outcome::NothingToDo);
+    }
+
+    // Blocks can't be proposed on top of an orphaned block. It has to be replaced in the checker by
+    // the owner, so the proposer stops and reports the fork point.
+    if let Some(fork) = find_fork(web3s, store, &first_block).await? {
+        tracing::error!(
+            "Block saved in GOSH checker is not canonical, fork depth {:?}: {fork:?}",
+            fork.depth()
+        );
+        return Ok(fork.outcome());
+    }
+
+    // With leader rotation only the validator whose turn it is proposes
+    if let Some(leader) = leader {
+        if let Some(reason) = leader
+            .check_turn(
+                client,
+                store,
+                checker_address,
+                first_block_hash,
+                first_block_number.as_u64(),
+                last_block_number.as_u64(),
+            )
+            .await?
+        {
+            tracing::info!("Skip proposing: {reason}");
+            return Ok(Outcome::Skipped { reason });
+        }
     }
 
     let mut block_diff = (last_block_number - first_block_number).as_u64();
@@ -54,38 +110,53 @@ pub async fn propose_eth_blocks() -> anyhow::Result<()> {
     );
 
     // Get maximum block amount for one message
-    let max_blocks = env::var("MAX_BLOCK_IN_ONE_CHUNK")
-        .ok()
-        .and_then(|s| u64::from_str(&s).ok())
-        .unwrap_or(DEFAULT_MAX_BLOCK_IN_ONE_CHUNK);
+    let max_blocks = config.proposer.max_block_in_one_chunk;
 
     // If current distance to the latest block is too great, send a maximal batch
     if block_diff > max_blocks {
-        block_id = BlockId::Number(BlockNumber::Number(first_block_number + max_blocks));
-        tracing::info!("Difference in block numbers is too high, send till the block {block_id:?}");
+        tracing::info!(
+            "Difference in block numbers is too high, send till the block {}",
+            first_block_number + max_blocks
+        );
         block_diff = max_blocks;
     }
 
-    // Query blocks
-    let mut blocks = vec![];
-    for _ in 0..block_diff {
-        // Read block
-        let next_block = read_block(&web3s, block_id).await?;
-
-        // Get hash of the previous block
-        block_id = BlockId::Hash(next_block.parent_hash);
-        blocks.push(next_block);
+    // Query blocks by number concurrently, newest first
+    let blocks = read_blocks(
+        web3s,
+        store,
+        first_block_number + 1,
+        first_block_number + block_diff,
+        config.proposer.fetch_concurrency,
+    )
+    .await?;
+
+    // Block with a header that can't be verified is not proposed. The next run reads it again, so
+    // a bad provider response does not stop the proposer, but it is reported.
+    for block in &blocks {
+        if let Err(e) = serialize_block(block) {
+            tracing::error!("Failed to verify ETH block: {e}");
+            return Ok(Outcome::Skipped {
+                reason: format!("Failed to verify ETH block: {e}"),
+            });
+        }
     }
 
-    // Check that we reached the last saved block
-    assert_eq!(
-        blocks.last().unwrap().parent_hash,
-        first_block_hash,
-        "Wrong last queried block"
-    );
+    // Check that blocks continue the last saved one
+    check_chain(&blocks, first_block_hash)?;
 
-    // get transfers for queried blocks and propose them
-    propose_blocks(&web3s, &client, blocks, &checker_address).await?;
+    // Deposits of blocks which are not finalized yet are not cached
+    let finalized = config.proposer.finality == BlockNumber::Finalized;
 
-    Ok(())
+    // get transfers for queried blocks and propose them
+    propose_blocks(
+        web3s,
+        client,
+        store,
+        blocks,
+        &config.eth,
+        checker_address,
+        finalized,
+    )
+    .await
 }
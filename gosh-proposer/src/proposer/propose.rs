@@ -1,51 +1,70 @@
-use common::elock::deposit::get_deposits;
+use anyhow::Context;
+use common::chain::{EthChain, GoshChain};
+use common::config::EthConfig;
+use common::elock::deposit::get_deposits_cached;
+use common::elock::get_tx_counter;
 use common::elock::transfer::TransferPatch;
-use common::elock::{get_elock_address, get_tx_counter};
 use common::eth::encoder::serialize_block;
 use common::eth::FullBlock;
 use common::gosh::call_function;
-use common::gosh::helper::EverClient;
 use common::helper::abi::CHECKER_ABI;
+use common::outcome::Outcome;
+use common::store::{ProposalKind, ProposalRecord, Store};
 use common::token_root::{deploy_root, is_root_active};
 use serde_json::json;
 use std::collections::HashSet;
-use web3::transports::WebSocket;
-use web3::types::H256;
-use web3::Web3;
+use web3::types::{H256, U256};
 
 pub async fn propose_blocks(
-    web3s: &Web3<WebSocket>,
-    client: &EverClient,
+    web3s: &dyn EthChain,
+    client: &dyn GoshChain,
+    store: &Store,
     blocks: Vec<FullBlock<H256>>,
+    eth_config: &EthConfig,
     checker_address: &str,
-) -> anyhow::Result<()> {
+    finalized: bool,
+) -> anyhow::Result<Outcome> {
     tracing::info!("start propose block");
 
     // ELock contract address
-    let elock_address = get_elock_address()?;
+    let elock_address = eth_config.elock_address;
 
     // Get starting tx counter
     let start_block_number = blocks.last().unwrap().number.unwrap();
     // We should check tx counter on the block before starting to get changes from the first block
     let starting_tx_counter = get_tx_counter(web3s, elock_address, start_block_number - 1)
         .await
-        .map_err(|e| anyhow::format_err!("Failed to get ELock tx counter: {e}"))?;
+        .context("Failed to get ELock tx counter")?;
     tracing::info!("Start tx counter on {start_block_number}: {starting_tx_counter}");
     // Get final tx counter
     let final_block_number = blocks.first().unwrap().number.unwrap();
     let final_tx_counter = get_tx_counter(web3s, elock_address, final_block_number)
         .await
-        .map_err(|e| anyhow::format_err!("Failed to get ELock tx counter: {e}"))?;
+        .context("Failed to get ELock tx counter")?;
     tracing::info!("Final tx counter on {final_block_number}: {final_tx_counter}");
 
     let all_transfers: Vec<TransferPatch> = {
-        let transfers =
-            get_deposits(web3s, elock_address, start_block_number, final_block_number).await?;
-        assert_eq!(
-            transfers.len(),
-            (final_tx_counter - starting_tx_counter).as_usize(),
-            "Number of deposits does not match tx counter"
-        );
+        let transfers = get_deposits_cached(
+            web3s,
+            store,
+            elock_address,
+            start_block_number,
+            final_block_number,
+            blocks.first().unwrap().hash.unwrap_or_default(),
+            finalized,
+        )
+        .await?;
+        // Counter comes from contract state and deposits from logs, a mismatch means that one of
+        // them is incomplete and the proposal would lose or invent transfers
+        if starting_tx_counter + U256::from(transfers.len()) != final_tx_counter {
+            let reason = format!(
+                "Found {} ELock deposits in blocks {start_block_number}..={final_block_number}, \
+                but tx counter changed from {starting_tx_counter} to {final_tx_counter}",
+                transfers.len()
+            );
+            tracing::error!("{reason}");
+            return Ok(Outcome::Skipped { reason });
+        }
         check_roots(client, checker_address, &transfers).await?;
         transfers
     };
@@ -55,9 +74,9 @@ pub async fn propose_blocks(
     // Iterate through blocks and check whether we need to look for transfers
     for block in blocks.iter().rev() {
         // Format blocks before sending them to the checker contract
-        let hash = format!("{:?}", block.hash.unwrap());
-        let data = serialize_block(block)
-            .map_err(|e| anyhow::format_err!("Failed to serialize ETH block: {e}"))?;
+        let data = serialize_block(block).context("Failed to serialize ETH block")?;
+        // Hash is present in verified block
+        let hash = format!("{:?}", block.hash.unwrap_or_default());
         let data_str = data
             .iter()
             .fold(String::new(), |acc, el| format!("{acc}{:02x}", el));
@@ -71,7 +90,7 @@ pub async fn propose_blocks(
         "transactions": all_transfers,
     });
 
-    call_function(
+    let tx_id = call_function(
         client,
         checker_address,
         CHECKER_ABI,
@@ -80,12 +99,25 @@ pub async fn propose_blocks(
         Some(args),
     )
     .await
-    .map_err(|e| anyhow::format_err!("Failed to call GOSH function: {e}"))?;
-    Ok(())
+    .context("Failed to call GOSH function")?;
+
+    let proposal = ProposalRecord {
+        kind: ProposalKind::Deposit,
+        from_block: format!("{:?}", blocks.last().unwrap().parent_hash),
+        till_block: format!("{:?}", blocks.first().unwrap().hash.unwrap()),
+        transfers: all_transfers.len(),
+        tx_id,
+    };
+    store.save_proposal(&proposal)?;
+    Ok(Outcome::Proposed {
+        from: proposal.from_block,
+        till: proposal.till_block,
+        transfers: proposal.transfers,
+    })
 }
 
 async fn check_roots(
-    gosh_context: &EverClient,
+    gosh_context: &dyn GoshChain,
     checker_address: &str,
     transfers: &Vec<TransferPatch>,
 ) -> anyhow::Result<()> {
@@ -93,12 +125,9 @@ async fn check_roots(
     for transfer in transfers {
         let token_root = web3::helpers::to_string(&transfer.root.eth_root).replace('"', "");
         if !deployed_roots.contains(&token_root) {
-            match is_root_active(gosh_context, checker_address, &transfer.root).await {
-                Ok(true) => {}
-                _ => {
-                    deploy_root(gosh_context, checker_address, &transfer.root).await?;
-                }
-            };
+            if !is_root_active(gosh_context, checker_address, &transfer.root).await? {
+                deploy_root(gosh_context, checker_address, &transfer.root).await?;
+            }
             deployed_roots.insert(token_root);
         }
     }
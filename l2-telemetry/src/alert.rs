@@ -0,0 +1,234 @@
+use crate::sink::{BufferedSink, HttpSink};
+use crate::telemetry::Telemetry;
+use common::config::{AlertOp, AlertRuleConfig, BridgeConfig};
+use common::store::{AlertState, Store};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+// Rules over telemetry snapshots. State of every rule is kept in the store, so alerts are only
+// emitted when the rule starts or stops firing, not on every run.
+pub struct Alerts {
+    rules: Vec<AlertRuleConfig>,
+    store: Store,
+    webhook: Option<BufferedSink>,
+}
+
+fn op_str(op: AlertOp) -> &'static str {
+    match op {
+        AlertOp::Greater => ">",
+        AlertOp::GreaterOrEqual => ">=",
+        AlertOp::Less => "<",
+        AlertOp::LessOrEqual => "<=",
+    }
+}
+
+fn field_value(snapshot: &Value, field: &str) -> anyhow::Result<u128> {
+    // Big values are serialized as strings
+    let value = match snapshot.get(field) {
+        Some(Value::Number(number)) => number.to_string(),
+        Some(Value::String(value)) => value.clone(),
+        _ => anyhow::bail!("Telemetry field {field} is not found or is not a number"),
+    };
+    value
+        .parse::<u128>()
+        .map_err(|e| anyhow::format_err!("Telemetry field {field} is not a number: {value}: {e}"))
+}
+
+// Returns whether the rule condition holds, value observed in this run and alert message
+fn check_rule(
+    rule: &AlertRuleConfig,
+    telemetry: &Telemetry,
+    snapshot: &Value,
+) -> anyhow::Result<(bool, String, String)> {
+    match rule {
+        AlertRuleConfig::Threshold {
+            field, op, value, ..
+        } => {
+            let actual = field_value(snapshot, field)?;
+            let active = match op {
+                AlertOp::Greater => actual > *value,
+                AlertOp::GreaterOrEqual => actual >= *value,
+                AlertOp::Less => actual < *value,
+                AlertOp::LessOrEqual => actual <= *value,
+            };
+            let message = format!("{field} is {actual}, threshold: {} {value}", op_str(*op));
+            Ok((active, actual.to_string(), message))
+        }
+        AlertRuleConfig::SupplyMismatch { tolerance, .. } => {
+            let gosh_supplies: HashMap<_, _> = telemetry
+                .glock_total_supply
+                .iter()
+                .map(|supply| (supply.root.eth_root, supply.value))
+                .collect();
+            let mut mismatches = vec![];
+            for supply in &telemetry.elock_total_supplies {
+                let gosh_supply = gosh_supplies
+                    .get(&supply.root.eth_root)
+                    .copied()
+                    .unwrap_or_default();
+                if supply.value.abs_diff(gosh_supply) > *tolerance {
+                    mismatches.push(format!(
+                        "{} ELock {} GOSH {gosh_supply}",
+                        supply.root.symbol, supply.value
+                    ));
+                }
+            }
+            let observation = mismatches.join(", ");
+            let message = format!("ELock and GOSH supplies differ: {observation}");
+            Ok((!mismatches.is_empty(), observation, message))
+        }
+        AlertRuleConfig::ProposalsStuck { .. } => {
+            let mut proposals: Vec<&String> = telemetry.glock_proposals.keys().collect();
+            proposals.sort();
+            let observation = format!(
+                "GOSH proposals: [{}], ELock proposals: {}",
+                proposals
+                    .iter()
+                    .map(|address| address.as_str())
+                    .collect::<Vec<&str>>()
+                    .join(", "),
+                telemetry.elock_proposals_cnt
+            );
+            let active = telemetry.glock_proposals_cnt != 0 || telemetry.elock_proposals_cnt != 0;
+            let message = format!("Proposals are not processed: {observation}");
+            Ok((active, observation, message))
+        }
+    }
+}
+
+impl Alerts {
+    pub fn from_config(config: &BridgeConfig) -> anyhow::Result<Self> {
+        let webhook = match &config.telemetry.alert_webhook {
+            Some(url) => Some(BufferedSink::new(
+                Box::new(HttpSink::new(url, &HashMap::new(), None)?),
+                config.telemetry.sink_buffer_size,
+                config.telemetry.sink_retries,
+            )),
+            None => None,
+        };
+        Ok(Self {
+            rules: config.telemetry.alerts.clone(),
+            store: Store::from_config(&config.store)?,
+            webhook,
+        })
+    }
+
+    pub async fn evaluate(&mut self, telemetry: &Telemetry) -> anyhow::Result<()> {
+        let snapshot = json!(telemetry);
+        for rule in &self.rules {
+            let name = rule.name();
+            let (active, observation, message) = match check_rule(rule, telemetry, &snapshot) {
+                Ok(result) => result,
+                Err(e) => {
+                    tracing::error!("Failed to evaluate alert {name}: {e}");
+                    continue;
+                }
+            };
+            let previous = self.store.alert_state(name)?.unwrap_or_default();
+            let runs = if active && previous.observation == observation {
+                previous.runs.saturating_add(1)
+            } else if active {
+                1
+            } else {
+                0
+            };
+            let firing = match rule {
+                AlertRuleConfig::ProposalsStuck { runs: limit, .. } => runs >= *limit,
+                _ => active,
+            };
+
+            if firing != previous.firing {
+                let status = if firing { "firing" } else { "resolved" };
+                if firing {
+                    tracing::warn!("Alert {name} is firing: {message}");
+                } else {
+                    tracing::info!("Alert {name} is resolved: {message}");
+                }
+                if let Some(webhook) = &mut self.webhook {
+                    let alert = json!({
+                        "alert": name,
+                        "status": status,
+                        "message": message,
+                        "value": observation,
+                    });
+                    if let Err(e) = webhook.push(alert).await {
+                        tracing::error!("Failed to send alert {name}: {e}");
+                    }
+                }
+            }
+
+            self.store.save_alert_state(
+                name,
+                &AlertState {
+                    firing,
+                    observation,
+                    runs,
+                },
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Alerts;
+    use crate::telemetry::Telemetry;
+    use common::config::{AlertOp, AlertRuleConfig};
+    use common::store::Store;
+    use serde_json::json;
+
+    fn alerts(rules: Vec<AlertRuleConfig>) -> Alerts {
+        Alerts {
+            rules,
+            store: Store::in_memory().unwrap(),
+            webhook: None,
+        }
+    }
+
+    fn firing(alerts: &Alerts, name: &str) -> bool {
+        alerts.store.alert_state(name).unwrap().unwrap().firing
+    }
+
+    #[tokio::test]
+    async fn test_threshold_and_stuck_proposals() -> anyhow::Result<()> {
+        let mut alerts = alerts(vec![
+            AlertRuleConfig::Threshold {
+                name: "eth_lag".to_string(),
+                field: "eth_block_diff".to_string(),
+                op: AlertOp::Greater,
+                value: 100,
+            },
+            AlertRuleConfig::ProposalsStuck {
+                name: "stuck".to_string(),
+                runs: 2,
+            },
+        ]);
+
+        let mut telemetry = Telemetry {
+            eth_block_diff: 150,
+            glock_proposals_cnt: 1,
+            ..Default::default()
+        };
+        telemetry
+            .glock_proposals
+            .insert("0:01".to_string(), json!([]));
+        alerts.evaluate(&telemetry).await?;
+        assert!(firing(&alerts, "eth_lag"));
+        assert!(!firing(&alerts, "stuck"));
+
+        telemetry.eth_block_diff = 10;
+        alerts.evaluate(&telemetry).await?;
+        assert!(!firing(&alerts, "eth_lag"));
+        assert!(firing(&alerts, "stuck"));
+
+        // Another proposal is pending, so the previous one was processed
+        telemetry.glock_proposals.clear();
+        telemetry
+            .glock_proposals
+            .insert("0:02".to_string(), json!([]));
+        alerts.evaluate(&telemetry).await?;
+        assert!(!firing(&alerts, "stuck"));
+        Ok(())
+    }
+}
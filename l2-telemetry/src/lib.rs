@@ -0,0 +1,5 @@
+pub mod alert;
+pub mod metrics;
+pub mod serve;
+pub mod sink;
+pub mod telemetry;
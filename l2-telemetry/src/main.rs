@@ -1,11 +1,19 @@
-use crate::telemetry::get_telemetry;
+use common::config::BridgeConfig;
 use common::helper::tracing::init_default_tracing;
-
-mod telemetry;
+use l2_telemetry::serve::serve_metrics;
+use l2_telemetry::telemetry::get_telemetry;
+use std::env;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     dotenv::dotenv().ok();
     init_default_tracing();
-    get_telemetry().await
+    let config = BridgeConfig::load()?;
+
+    let args: Vec<String> = env::args().collect();
+    match args.get(1).map(|arg| arg.as_str()) {
+        Some("serve") => serve_metrics(&config).await,
+        Some(_) => anyhow::bail!("Unknown subcommand"),
+        None => get_telemetry(&config).await,
+    }
 }
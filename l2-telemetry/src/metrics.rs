@@ -0,0 +1,306 @@
+use crate::telemetry::Telemetry;
+use common::token_root::RootData;
+use std::fmt::Write;
+
+const PREFIX: &str = "l2";
+
+// Builder of the Prometheus text exposition format
+#[derive(Default)]
+pub struct Metrics {
+    output: String,
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn root_labels(root: &RootData) -> Vec<(&'static str, String)> {
+    vec![
+        ("symbol", root.symbol.clone()),
+        ("eth_root", format!("{:?}", root.eth_root)),
+    ]
+}
+
+impl Metrics {
+    // Start new metric family. Must be called once before its samples.
+    pub fn family(&mut self, name: &str, help: &str) -> &mut Self {
+        let _ = writeln!(self.output, "# HELP {PREFIX}_{name} {help}");
+        let _ = writeln!(self.output, "# TYPE {PREFIX}_{name} gauge");
+        self
+    }
+
+    pub fn sample(
+        &mut self,
+        name: &str,
+        labels: &[(&str, String)],
+        value: impl std::fmt::Display,
+    ) -> &mut Self {
+        let _ = write!(self.output, "{PREFIX}_{name}");
+        if !labels.is_empty() {
+            let labels = labels
+                .iter()
+                .map(|(key, value)| format!("{key}=\"{}\"", escape_label(value)))
+                .collect::<Vec<String>>()
+                .join(",");
+            let _ = write!(self.output, "{{{labels}}}");
+        }
+        let _ = writeln!(self.output, " {value}");
+        self
+    }
+
+    pub fn gauge(&mut self, name: &str, help: &str, value: impl std::fmt::Display) -> &mut Self {
+        self.family(name, help).sample(name, &[], value)
+    }
+
+    pub fn render(self) -> String {
+        self.output
+    }
+}
+
+// Convert telemetry snapshot to metrics. Values are printed as integers, so big token amounts
+// are not rounded in the output.
+pub fn telemetry_metrics(telemetry: &Telemetry, metrics: &mut Metrics) {
+    metrics
+        .gauge(
+            "glock_eth_block",
+            "Number of the last ETH block saved in GOSH checker",
+            telemetry.glock_eth_block,
+        )
+        .gauge(
+            "last_eth_block",
+            "Number of the last finalized ETH block",
+            telemetry.last_eth_block,
+        )
+        .gauge(
+            "eth_block_diff",
+            "Number of ETH blocks not yet saved in GOSH checker",
+            telemetry.eth_block_diff,
+        )
+        .gauge(
+            "elock_gosh_block_seq_no",
+            "Seq no of the last GOSH master block saved in ELock",
+            telemetry.elock_gosh_block_seq_no,
+        )
+        .gauge(
+            "last_gosh_block_seq_no",
+            "Seq no of the last GOSH master block",
+            telemetry.last_gosh_block_seq_no,
+        )
+        .gauge(
+            "gosh_block_diff",
+            "Number of GOSH master blocks not yet saved in ELock",
+            telemetry.gosh_block_diff,
+        )
+        .gauge(
+            "queued_burns_cnt",
+            "Number of GOSH burns waiting for withdrawal",
+            telemetry.queued_burns_cnt,
+        )
+        .gauge(
+            "queued_burns_total_value",
+            "Total value of GOSH burns waiting for withdrawal",
+            telemetry.queued_burns_total_value,
+        );
+
+    metrics.family(
+        "queued_burns_root_cnt",
+        "Number of GOSH burns waiting for withdrawal per token root",
+    );
+    for burns in &telemetry.queued_burns {
+        metrics.sample(
+            "queued_burns_root_cnt",
+            &root_labels(&burns.root),
+            burns.cnt,
+        );
+    }
+    metrics.family(
+        "queued_burns_root_value",
+        "Value of GOSH burns waiting for withdrawal per token root",
+    );
+    for burns in &telemetry.queued_burns {
+        metrics.sample(
+            "queued_burns_root_value",
+            &root_labels(&burns.root),
+            burns.total_value,
+        );
+    }
+
+    metrics
+        .gauge(
+            "elock_deposit_counter",
+            "Number of deposits made to ELock",
+            telemetry.elock_deposit_counter,
+        )
+        .gauge(
+            "elock_withdrawal_counter",
+            "Number of withdrawals made from ELock",
+            telemetry.elock_withdrawal_counter,
+        );
+    metrics.family(
+        "elock_total_supply",
+        "Total supply locked in ELock per token root",
+    );
+    for supply in &telemetry.elock_total_supplies {
+        metrics.sample(
+            "elock_total_supply",
+            &root_labels(&supply.root),
+            supply.value,
+        );
+    }
+    metrics.family("glock_total_supply", "Total supply of GOSH token root");
+    for supply in &telemetry.glock_total_supply {
+        let mut labels = root_labels(&supply.root);
+        labels.push(("gosh_root", supply.gosh_root.clone()));
+        metrics.sample("glock_total_supply", &labels, supply.value);
+    }
+
+    metrics
+        .gauge(
+            "elock_proposals_cnt",
+            "Number of withdrawal proposals in ELock",
+            telemetry.elock_proposals_cnt,
+        )
+        .gauge(
+            "glock_proposals_cnt",
+            "Number of deposit proposals in GOSH checker",
+            telemetry.glock_proposals_cnt,
+        )
+        .gauge(
+            "elock_balance",
+            "ELock balance in wei",
+            telemetry.elock_balance,
+        );
+
+    metrics.family("validator_balance", "ETH validator balance in wei");
+    let mut validators: Vec<_> = telemetry.validators_balances.iter().collect();
+    validators.sort();
+    for (validator, balance) in validators {
+        metrics.sample(
+            "validator_balance",
+            &[("validator", format!("{validator:?}"))],
+            balance,
+        );
+    }
+    if !telemetry.validators_balances.is_empty() {
+        metrics.gauge(
+            "min_validator_balance",
+            "Minimal ETH validator balance in wei",
+            telemetry.min_validator_balance,
+        );
+    }
+
+    metrics
+        .gauge(
+            "glock_checker_balance",
+            "GOSH checker balance in nanotokens",
+            telemetry.glock_checker_balance,
+        )
+        .gauge(
+            "elock_collected_commissions",
+            "Commissions collected in ELock in wei",
+            telemetry.elock_collected_commissions,
+        )
+        .gauge(
+            "glock_total_commissions",
+            "Total commissions collected by GOSH token roots",
+            telemetry.glock_total_commissions,
+        );
+    metrics.family(
+        "glock_current_commissions",
+        "Commissions collected by GOSH token root",
+    );
+    for commission in &telemetry.glock_current_commissions {
+        metrics.sample(
+            "glock_current_commissions",
+            &root_labels(&commission.root),
+            commission.value,
+        );
+    }
+
+    metrics
+        .gauge(
+            "eth_gas_price",
+            "Current ETH gas price in wei",
+            telemetry.current_eth_gas_price,
+        )
+        .gauge(
+            "approximate_elock_commissions",
+            "Approximate cost of the next withdrawal in wei",
+            telemetry.current_approximate_elock_commissions,
+        )
+        .gauge(
+            "approximate_elock_commissions_per_person",
+            "Approximate cost of the next withdrawal per burn in wei",
+            telemetry.current_approximate_elock_commissions_per_person,
+        );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{telemetry_metrics, Metrics};
+    use crate::telemetry::{RootValue, Telemetry};
+    use common::token_root::RootData;
+    use std::collections::HashMap;
+    use web3::types::Address;
+
+    #[test]
+    fn test_render_metrics() {
+        let root = RootData {
+            name: "Tether USD".to_string(),
+            symbol: "USDT".to_string(),
+            decimals: 6,
+            eth_root: Address::from_low_u64_be(1),
+        };
+        let telemetry = Telemetry {
+            glock_eth_block: 90,
+            last_eth_block: 100,
+            eth_block_diff: 10,
+            elock_gosh_block_seq_no: 5,
+            elock_gosh_block_id: "aa".to_string(),
+            last_gosh_block_seq_no: 7,
+            last_gosh_block_id: "bb".to_string(),
+            gosh_block_diff: 2,
+            queued_burns_cnt: 0,
+            queued_burns_total_value: 0,
+            queued_burns: vec![],
+            elock_deposit_counter: 3,
+            elock_withdrawal_counter: 4,
+            elock_total_supplies: vec![RootValue {
+                root,
+                value: u128::MAX,
+            }],
+            elock_proposals_cnt: 1,
+            glock_proposals_cnt: 0,
+            glock_proposals: HashMap::new(),
+            elock_balance: 10,
+            validators_balances: HashMap::from([(Address::from_low_u64_be(2), 20)]),
+            min_validator_balance: 20,
+            glock_total_supply: vec![],
+            glock_checker_balance: 30,
+            elock_collected_commissions: 0,
+            glock_total_commissions: 0,
+            glock_current_commissions: vec![],
+            current_eth_gas_price: 1_000_000_000,
+            current_approximate_elock_commissions: 0,
+            current_approximate_elock_commissions_per_person: 0,
+        };
+        let mut metrics = Metrics::default();
+        telemetry_metrics(&telemetry, &mut metrics);
+        let output = metrics.render();
+
+        assert!(output.contains("# TYPE l2_eth_block_diff gauge\nl2_eth_block_diff 10\n"));
+        assert!(output.contains(&format!(
+            "l2_elock_total_supply{{symbol=\"USDT\",eth_root=\"{:?}\"}} {}\n",
+            Address::from_low_u64_be(1),
+            u128::MAX
+        )));
+        assert!(output.contains(&format!(
+            "l2_validator_balance{{validator=\"{:?}\"}} 20\n",
+            Address::from_low_u64_be(2)
+        )));
+        assert!(output.contains("l2_min_validator_balance 20\n"));
+    }
+}
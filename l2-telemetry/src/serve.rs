@@ -0,0 +1,160 @@
+use crate::alert::Alerts;
+use crate::metrics::{telemetry_metrics, Metrics};
+use crate::sink::{create_sinks, send_to_sinks};
+use crate::telemetry::collect_telemetry;
+use common::config::BridgeConfig;
+use common::eth::create_eth_client;
+use common::gosh::helper::create_client;
+use common::retry::Retry;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, StatusCode};
+use parking_lot::Mutex;
+use serde_json::json;
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::signal::unix::{signal, SignalKind};
+use tokio::time::MissedTickBehavior;
+
+#[derive(Default)]
+struct State {
+    // Metrics of the last successful collection
+    telemetry: Option<String>,
+    last_success: Option<u64>,
+    up: bool,
+}
+
+impl State {
+    fn render(&self) -> String {
+        let mut metrics = Metrics::default();
+        metrics.gauge(
+            "telemetry_up",
+            "Whether the last telemetry collection succeeded",
+            u8::from(self.up),
+        );
+        if let Some(timestamp) = self.last_success {
+            metrics.gauge(
+                "telemetry_last_success_timestamp_seconds",
+                "Time of the last successful telemetry collection",
+                timestamp,
+            );
+        }
+        let mut output = metrics.render();
+        if let Some(telemetry) = &self.telemetry {
+            output.push_str(telemetry);
+        }
+        output
+    }
+}
+
+async fn handle_http(
+    state: Arc<Mutex<State>>,
+    request: Request<Body>,
+) -> Result<Response<Body>, Infallible> {
+    let response = match (request.method(), request.uri().path()) {
+        (&Method::GET, "/metrics") => Response::builder()
+            .header("Content-Type", "text/plain; version=0.0.4")
+            .body(Body::from(state.lock().render())),
+        _ => Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .body(Body::empty()),
+    };
+    Ok(response.unwrap())
+}
+
+// Collect telemetry with interval from the config, evaluate alert rules, send it to the configured
+// sinks and expose it as Prometheus metrics on `/metrics`. Metrics of the last successful collection are kept if
+// the next one fails.
+pub async fn serve_metrics(config: &BridgeConfig) -> anyhow::Result<()> {
+    let listen = config.telemetry.listen;
+    let interval = config.telemetry.interval;
+    tracing::info!("Serve telemetry metrics on {listen} with interval {interval:?}");
+
+    let gosh_context = Retry::new(create_client(&config.gosh)?, &config.retry);
+    let mut web3s = create_eth_client(&config.eth, &config.retry).await?;
+    let mut sinks = create_sinks(&config.telemetry, false)?;
+    let mut alerts = Alerts::from_config(config)?;
+
+    let state = Arc::new(Mutex::new(State::default()));
+    let server_state = state.clone();
+    let server = hyper::Server::try_bind(&listen)
+        .map_err(|e| anyhow::format_err!("Failed to bind metrics server to {listen}: {e}"))?
+        .serve(make_service_fn(move |_| {
+            let state = server_state.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request| {
+                    handle_http(state.clone(), request)
+                }))
+            }
+        }));
+    let server = tokio::spawn(async move {
+        if let Err(e) = server.await {
+            tracing::error!("Metrics server failed: {e}");
+        }
+    });
+
+    let mut sigterm = signal(SignalKind::terminate())
+        .map_err(|e| anyhow::format_err!("Failed to set SIGTERM handler: {e}"))?;
+    let mut sigint = signal(SignalKind::interrupt())
+        .map_err(|e| anyhow::format_err!("Failed to set SIGINT handler: {e}"))?;
+
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {}
+            _ = sigterm.recv() => {
+                tracing::info!("Received SIGTERM, stop telemetry server");
+                break;
+            }
+            _ = sigint.recv() => {
+                tracing::info!("Received SIGINT, stop telemetry server");
+                break;
+            }
+        }
+
+        // Check that ETH connection is still alive and reconnect otherwise
+        if let Err(e) = web3s.primary().inner().eth().block_number().await {
+            tracing::warn!("ETH connection failed, reconnecting: {e}");
+            web3s = match create_eth_client(&config.eth, &config.retry).await {
+                Ok(web3s) => web3s,
+                Err(e) => {
+                    tracing::error!("Failed to reconnect to ETH: {e}");
+                    state.lock().up = false;
+                    continue;
+                }
+            };
+        }
+
+        match collect_telemetry(config, &web3s, &gosh_context).await {
+            Ok(telemetry) => {
+                let mut metrics = Metrics::default();
+                telemetry_metrics(&telemetry, &mut metrics);
+                let timestamp = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|duration| duration.as_secs())
+                    .unwrap_or_default();
+                {
+                    let mut state = state.lock();
+                    state.telemetry = Some(metrics.render());
+                    state.last_success = Some(timestamp);
+                    state.up = true;
+                }
+                if let Err(e) = alerts.evaluate(&telemetry).await {
+                    tracing::error!("Failed to evaluate alerts: {e}");
+                }
+                if let Err(e) = send_to_sinks(&mut sinks, &json!(telemetry)).await {
+                    tracing::error!("Failed to send telemetry: {e}");
+                }
+            }
+            Err(e) => {
+                tracing::error!("Failed to collect telemetry: {e}");
+                state.lock().up = false;
+            }
+        }
+    }
+
+    server.abort();
+    Ok(())
+}
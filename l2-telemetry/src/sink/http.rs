@@ -0,0 +1,93 @@
+use super::TelemetrySink;
+use async_trait::async_trait;
+use common::config::redact_url;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::time::Duration;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+// Sends snapshot as JSON body of POST request. With PostgREST (e.g. Supabase) the url should
+// point to the table: https://<project>.supabase.co/rest/v1/l2_state
+pub struct HttpSink {
+    url: String,
+    client: reqwest::Client,
+}
+
+impl HttpSink {
+    pub fn new(
+        url: &str,
+        headers: &HashMap<String, String>,
+        api_key_env: Option<&str>,
+    ) -> anyhow::Result<Self> {
+        let mut header_map = HeaderMap::new();
+        for (name, value) in headers {
+            header_map.insert(HeaderName::from_str(name)?, HeaderValue::from_str(value)?);
+        }
+        if let Some(name) = api_key_env {
+            let key = std::env::var(name)
+                .map_err(|e| anyhow::format_err!("Failed to read API key from env {name}: {e}"))?;
+            let mut value = HeaderValue::from_str(&key)?;
+            value.set_sensitive(true);
+            header_map.insert("apikey", value);
+            let mut value = HeaderValue::from_str(&format!("Bearer {key}"))?;
+            value.set_sensitive(true);
+            header_map.insert(reqwest::header::AUTHORIZATION, value);
+        }
+        let client = reqwest::Client::builder()
+            .default_headers(header_map)
+            .timeout(REQUEST_TIMEOUT)
+            .build()?;
+        Ok(Self {
+            url: url.to_string(),
+            client,
+        })
+    }
+}
+
+#[async_trait]
+impl TelemetrySink for HttpSink {
+    fn name(&self) -> String {
+        format!("http {}", redact_url(&self.url))
+    }
+
+    async fn send(&self, snapshot: &Value) -> anyhow::Result<()> {
+        // Errors of reqwest contain the url, it may have credentials
+        let response = self
+            .client
+            .post(&self.url)
+            .json(snapshot)
+            .send()
+            .await
+            .map_err(|e| e.without_url())?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            anyhow::bail!("Request failed with status {status}: {body}");
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HttpSink;
+    use crate::sink::TelemetrySink;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    #[tokio::test]
+    async fn test_redacted_name() -> anyhow::Result<()> {
+        let sink = HttpSink::new(
+            "http://127.0.0.1:1/hooks/secret-token?key=secret",
+            &HashMap::new(),
+            None,
+        )?;
+        assert_eq!(sink.name(), "http http://127.0.0.1:1/***");
+        let err = sink.send(&json!({})).await.unwrap_err();
+        assert!(!err.to_string().contains("secret"));
+        Ok(())
+    }
+}
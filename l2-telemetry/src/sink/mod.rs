@@ -0,0 +1,183 @@
+use async_trait::async_trait;
+use common::config::{TelemetryConfig, TelemetrySinkConfig};
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+mod file;
+mod http;
+mod sqlite;
+mod stdout;
+
+pub use file::FileSink;
+pub use http::HttpSink;
+pub use sqlite::SqliteSink;
+pub use stdout::StdoutSink;
+
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+
+// Destination of telemetry snapshots
+#[async_trait]
+pub trait TelemetrySink: Send + Sync {
+    fn name(&self) -> String;
+
+    async fn send(&self, snapshot: &Value) -> anyhow::Result<()>;
+}
+
+pub fn create_sink(config: &TelemetrySinkConfig) -> anyhow::Result<Box<dyn TelemetrySink>> {
+    Ok(match config {
+        TelemetrySinkConfig::Stdout => Box::new(StdoutSink),
+        TelemetrySinkConfig::File { path } => Box::new(FileSink::new(path)),
+        TelemetrySinkConfig::Http {
+            url,
+            headers,
+            api_key_env,
+        } => Box::new(HttpSink::new(url, headers, api_key_env.as_deref())?),
+        TelemetrySinkConfig::Sqlite { path } => Box::new(SqliteSink::open(path)?),
+    })
+}
+
+// Sink wrapper that keeps snapshots which were not sent and sends them before the next one,
+// so short outages of the sink do not create gaps in the data
+pub struct BufferedSink {
+    sink: Box<dyn TelemetrySink>,
+    buffer: VecDeque<Value>,
+    capacity: usize,
+    retries: u32,
+}
+
+impl BufferedSink {
+    pub fn new(sink: Box<dyn TelemetrySink>, capacity: usize, retries: u32) -> Self {
+        Self {
+            sink,
+            buffer: VecDeque::new(),
+            capacity,
+            retries,
+        }
+    }
+
+    pub fn pending(&self) -> usize {
+        self.buffer.len()
+    }
+
+    pub async fn push(&mut self, snapshot: Value) -> anyhow::Result<()> {
+        if self.buffer.len() >= self.capacity {
+            tracing::warn!(
+                "Telemetry sink {} buffer is full, drop the oldest snapshot",
+                self.sink.name()
+            );
+            self.buffer.pop_front();
+        }
+        self.buffer.push_back(snapshot);
+        self.flush().await
+    }
+
+    pub async fn flush(&mut self) -> anyhow::Result<()> {
+        while let Some(snapshot) = self.buffer.front() {
+            let mut attempt = 0;
+            while let Err(e) = self.sink.send(snapshot).await {
+                if attempt >= self.retries {
+                    anyhow::bail!(
+                        "Failed to send telemetry to {}, {} snapshot(s) are buffered: {e}",
+                        self.sink.name(),
+                        self.buffer.len()
+                    );
+                }
+                attempt += 1;
+                tracing::info!(
+                    "Failed to send telemetry to {}, retry {attempt}: {e}",
+                    self.sink.name()
+                );
+                tokio::time::sleep(RETRY_DELAY * attempt).await;
+            }
+            self.buffer.pop_front();
+        }
+        Ok(())
+    }
+}
+
+// Create sinks from the config. If no sinks are configured, stdout is used when
+// `default_stdout` is set.
+pub fn create_sinks(
+    config: &TelemetryConfig,
+    default_stdout: bool,
+) -> anyhow::Result<Vec<BufferedSink>> {
+    let mut sinks = config
+        .sinks
+        .iter()
+        .map(|sink| {
+            create_sink(sink)
+                .map(|sink| BufferedSink::new(sink, config.sink_buffer_size, config.sink_retries))
+        })
+        .collect::<anyhow::Result<Vec<BufferedSink>>>()?;
+    if sinks.is_empty() && default_stdout {
+        sinks.push(BufferedSink::new(
+            Box::new(StdoutSink),
+            config.sink_buffer_size,
+            config.sink_retries,
+        ));
+    }
+    Ok(sinks)
+}
+
+// Send snapshot to every sink. Failure of one sink does not prevent sending to the others.
+pub async fn send_to_sinks(sinks: &mut [BufferedSink], snapshot: &Value) -> anyhow::Result<()> {
+    let mut errors = vec![];
+    for sink in sinks {
+        if let Err(e) = sink.push(snapshot.clone()).await {
+            errors.push(e.to_string());
+        }
+    }
+    if !errors.is_empty() {
+        anyhow::bail!("{}", errors.join("; "));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BufferedSink, TelemetrySink};
+    use async_trait::async_trait;
+    use parking_lot::Mutex;
+    use serde_json::{json, Value};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    #[derive(Default)]
+    struct TestSink {
+        available: AtomicBool,
+        received: Mutex<Vec<Value>>,
+    }
+
+    #[async_trait]
+    impl TelemetrySink for Arc<TestSink> {
+        fn name(&self) -> String {
+            "test".to_string()
+        }
+
+        async fn send(&self, snapshot: &Value) -> anyhow::Result<()> {
+            if !self.available.load(Ordering::SeqCst) {
+                anyhow::bail!("unavailable");
+            }
+            self.received.lock().push(snapshot.clone());
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_buffer_while_unavailable() {
+        let sink = Arc::new(TestSink::default());
+        let mut buffered = BufferedSink::new(Box::new(sink.clone()), 2, 0);
+
+        assert!(buffered.push(json!(1)).await.is_err());
+        assert!(buffered.push(json!(2)).await.is_err());
+        assert!(buffered.push(json!(3)).await.is_err());
+        assert_eq!(buffered.pending(), 2);
+
+        sink.available.store(true, Ordering::SeqCst);
+        buffered.flush().await.unwrap();
+        buffered.push(json!(4)).await.unwrap();
+        assert_eq!(buffered.pending(), 0);
+        assert_eq!(*sink.received.lock(), vec![json!(2), json!(3), json!(4)]);
+    }
+}
@@ -0,0 +1,50 @@
+use super::TelemetrySink;
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use rusqlite::{params, Connection};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS telemetry (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    created_at INTEGER NOT NULL,
+    data TEXT NOT NULL
+);
+";
+
+// Saves snapshots as JSON rows of 'telemetry' table
+pub struct SqliteSink {
+    path: PathBuf,
+    connection: Mutex<Connection>,
+}
+
+impl SqliteSink {
+    pub fn open(path: &Path) -> anyhow::Result<Self> {
+        let connection = Connection::open(path).map_err(|e| {
+            anyhow::format_err!("Failed to open telemetry database {}: {e}", path.display())
+        })?;
+        connection.execute_batch(SCHEMA)?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            connection: Mutex::new(connection),
+        })
+    }
+}
+
+#[async_trait]
+impl TelemetrySink for SqliteSink {
+    fn name(&self) -> String {
+        format!("sqlite {}", self.path.display())
+    }
+
+    async fn send(&self, snapshot: &Value) -> anyhow::Result<()> {
+        let created_at = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+        self.connection.lock().execute(
+            "INSERT INTO telemetry (created_at, data) VALUES (?1, ?2)",
+            params![created_at, snapshot.to_string()],
+        )?;
+        Ok(())
+    }
+}
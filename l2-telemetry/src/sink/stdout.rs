@@ -0,0 +1,17 @@
+use super::TelemetrySink;
+use async_trait::async_trait;
+use serde_json::Value;
+
+pub struct StdoutSink;
+
+#[async_trait]
+impl TelemetrySink for StdoutSink {
+    fn name(&self) -> String {
+        "stdout".to_string()
+    }
+
+    async fn send(&self, snapshot: &Value) -> anyhow::Result<()> {
+        println!("{}", serde_json::to_string_pretty(snapshot)?);
+        Ok(())
+    }
+}
@@ -1,22 +1,25 @@
-use common::checker::{get_block_from_checker, get_checker_address};
+use crate::alert::Alerts;
+use crate::sink::{create_sinks, send_to_sinks};
+use common::chain::{EthChain, GoshChain};
+use common::checker::get_block_from_checker;
+use common::config::BridgeConfig;
 use common::elock::transfer::TransferPatch;
-use common::elock::{
-    get_elock_address, get_last_gosh_block_id, get_storage, COUNTERS_INDEX,
-};
-use common::eth::{create_web3_socket, read_block};
+use common::elock::{get_last_gosh_block_id, get_storage, COUNTERS_INDEX};
+use common::eth::{create_eth_client, read_block};
 use common::gosh::block::{get_latest_master_block, get_master_block_seq_no};
 use common::gosh::burn::find_burns;
 use common::gosh::call_getter;
 use common::gosh::helper::create_client;
 use common::helper::abi::{CHECKER_ABI, ELOCK_ABI, PROPOSAL_ABI};
 use common::helper::{deserialize_uint, serialize_u128};
+use common::retry::Retry;
 use common::token_root::eth::get_root_data;
 use common::token_root::{get_root_address, get_root_owner_balance, get_root_total_supply, RootData};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::collections::HashMap;
 use std::str::FromStr;
-use web3::contract::{Contract, Options};
+use web3::ethabi::Token;
 use web3::types::{Address, BlockId, BlockNumber, U256};
 use common::elock;
 use common::gosh::balance::query_balance;
@@ -26,80 +29,80 @@ const ELOCK_WITHDRAWAL_COMMISSION: u128 = 400_000;
 const ELOCK_TRANSFER_COMMISSION: u128 = 21_000;
 
 #[derive(Serialize, Clone)]
-struct BurnStatistic {
-    root: RootData,
+pub struct BurnStatistic {
+    pub root: RootData,
     #[serde(serialize_with = "serialize_u128")]
-    total_value: u128,
-    cnt: usize,
+    pub total_value: u128,
+    pub cnt: usize,
 }
 
 #[derive(Serialize)]
-struct RootValue {
-    root: RootData,
+pub struct RootValue {
+    pub root: RootData,
     #[serde(serialize_with = "serialize_u128")]
-    value: u128,
+    pub value: u128,
 }
 
 #[derive(Serialize)]
-struct ExtendedRootValue {
-    root: RootData,
+pub struct ExtendedRootValue {
+    pub root: RootData,
     #[serde(serialize_with = "serialize_u128")]
-    value: u128,
-    gosh_root: String,
+    pub value: u128,
+    pub gosh_root: String,
 }
 
-#[derive(Serialize)]
-struct Telemetry {
-    glock_eth_block: u64,
-    last_eth_block: u64,
-    eth_block_diff: u64,
+#[derive(Serialize, Default)]
+pub struct Telemetry {
+    pub glock_eth_block: u64,
+    pub last_eth_block: u64,
+    pub eth_block_diff: u64,
 
     #[serde(serialize_with = "serialize_u128")]
-    elock_gosh_block_seq_no: u128,
-    elock_gosh_block_id: String,
+    pub elock_gosh_block_seq_no: u128,
+    pub elock_gosh_block_id: String,
     #[serde(serialize_with = "serialize_u128")]
-    last_gosh_block_seq_no: u128,
-    last_gosh_block_id: String,
+    pub last_gosh_block_seq_no: u128,
+    pub last_gosh_block_id: String,
     #[serde(serialize_with = "serialize_u128")]
-    gosh_block_diff: u128,
+    pub gosh_block_diff: u128,
 
-    queued_burns_cnt: usize,
+    pub queued_burns_cnt: usize,
     #[serde(serialize_with = "serialize_u128")]
-    queued_burns_total_value: u128,
-    queued_burns: Vec<BurnStatistic>,
+    pub queued_burns_total_value: u128,
+    pub queued_burns: Vec<BurnStatistic>,
 
     #[serde(serialize_with = "serialize_u128")]
-    elock_deposit_counter: u128,
+    pub elock_deposit_counter: u128,
     #[serde(serialize_with = "serialize_u128")]
-    elock_withdrawal_counter: u128,
-    elock_total_supplies: Vec<RootValue>,
+    pub elock_withdrawal_counter: u128,
+    pub elock_total_supplies: Vec<RootValue>,
 
-    elock_proposals_cnt: usize,
+    pub elock_proposals_cnt: usize,
 
-    glock_proposals_cnt: usize,
-    glock_proposals: HashMap<String, Value>,
+    pub glock_proposals_cnt: usize,
+    pub glock_proposals: HashMap<String, Value>,
 
     #[serde(serialize_with = "serialize_u128")]
-    elock_balance: u128,
-    validators_balances: HashMap<Address, u128>,
-    min_validator_balance: u128,
+    pub elock_balance: u128,
+    pub validators_balances: HashMap<Address, u128>,
+    pub min_validator_balance: u128,
 
-    glock_total_supply: Vec<ExtendedRootValue>,
-    glock_checker_balance: u128,
+    pub glock_total_supply: Vec<ExtendedRootValue>,
+    pub glock_checker_balance: u128,
 
     #[serde(serialize_with = "serialize_u128")]
-    elock_collected_commissions: u128,
+    pub elock_collected_commissions: u128,
 
     #[serde(serialize_with = "serialize_u128")]
-    glock_total_commissions: u128,
-    glock_current_commissions: Vec<RootValue>,
+    pub glock_total_commissions: u128,
+    pub glock_current_commissions: Vec<RootValue>,
 
     #[serde(serialize_with = "serialize_u128")]
-    current_eth_gas_price: u128,
+    pub current_eth_gas_price: u128,
     #[serde(serialize_with = "serialize_u128")]
-    current_approximate_elock_commissions: u128,
+    pub current_approximate_elock_commissions: u128,
     #[serde(serialize_with = "serialize_u128")]
-    current_approximate_elock_commissions_per_person: u128,
+    pub current_approximate_elock_commissions_per_person: u128,
 }
 
 #[derive(Deserialize)]
@@ -130,36 +133,69 @@ struct ProposalDetails {
     _need: u128,
 }
 
-pub async fn get_telemetry() -> anyhow::Result<()> {
+pub async fn get_telemetry(config: &BridgeConfig) -> anyhow::Result<()> {
     tracing::info!("Get telemetry");
-    let gosh_context = create_client()?;
-    let checker_address = get_checker_address()?;
+    let gosh_context = Retry::new(create_client(&config.gosh)?, &config.retry);
+    let web3s = create_eth_client(&config.eth, &config.retry).await?;
+
+    let mut sinks = create_sinks(&config.telemetry, true)?;
+    let mut alerts = Alerts::from_config(config)?;
+
+    let telemetry = collect_telemetry(config, &web3s, &gosh_context).await?;
+
+    alerts.evaluate(&telemetry).await?;
+    send_to_sinks(&mut sinks, &json!(telemetry)).await
+}
+
+async fn query_elock_list(
+    web3s: &dyn EthChain,
+    elock_address: Address,
+    function: &str,
+) -> anyhow::Result<Vec<Token>> {
+    web3s
+        .query(elock_address, ELOCK_ABI, function, vec![])
+        .await
+        .map_err(|e| anyhow::format_err!("Failed to call ELock getter {function}: {e}"))?
+        .into_iter()
+        .next()
+        .and_then(|token| token.into_array())
+        .ok_or(anyhow::format_err!("Wrong ELock {function} output"))
+}
 
-    let web3s = create_web3_socket().await?;
-    let elock_address = get_elock_address()?;
-    let elock_abi = web3::ethabi::Contract::load(ELOCK_ABI.as_bytes())?;
-    let elock_contract = Contract::new(web3s.eth(), elock_address, elock_abi);
+pub async fn collect_telemetry(
+    config: &BridgeConfig,
+    web3s: &dyn EthChain,
+    gosh_context: &dyn GoshChain,
+) -> anyhow::Result<Telemetry> {
+    let checker_address = config.gosh.checker_address.clone();
+    let elock_address = config.eth.elock_address;
 
-    let block_from_elock = get_last_gosh_block_id(elock_address, &web3s).await?;
+    let block_from_elock = get_last_gosh_block_id(elock_address, web3s).await?;
 
-    let first_seq_no = get_master_block_seq_no(&gosh_context, &block_from_elock)
+    let first_seq_no = get_master_block_seq_no(gosh_context, &block_from_elock)
         .await
         .map_err(|e| anyhow::format_err!("Failed to get seq no for block from ETH: {e}"))?;
 
-    let current_master_block = get_latest_master_block(&gosh_context)
+    let current_master_block = get_latest_master_block(gosh_context)
         .await
         .map_err(|e| anyhow::format_err!("Failed to get latest GOSH block: {e}"))?;
 
     let gosh_block_diff = current_master_block.seq_no - first_seq_no;
 
-    let burns = find_burns(&gosh_context, first_seq_no, current_master_block.seq_no).await?;
+    let burns = find_burns(
+        gosh_context,
+        &checker_address,
+        first_seq_no,
+        current_master_block.seq_no,
+    )
+    .await?;
 
     let mut burns_map = HashMap::new();
     let queued_burns_cnt = burns.len();
     let mut queued_burns_total_value = 0;
     for burn in burns {
         queued_burns_total_value += burn.value;
-        let root_data = get_root_data(&web3s, Address::from_str(&burn.eth_root)?).await?;
+        let root_data = get_root_data(web3s, Address::from_str(&burn.eth_root)?).await?;
         let entry = burns_map.entry(burn.eth_root).or_insert(BurnStatistic {
             root: root_data,
             total_value: 0,
@@ -169,8 +205,8 @@ pub async fn get_telemetry() -> anyhow::Result<()> {
         entry.cnt += 1;
     }
 
-    let first_block_hash = get_block_from_checker(&gosh_context, &checker_address).await?;
-    let first_block_number = read_block(&web3s, BlockId::Hash(first_block_hash))
+    let first_block_hash = get_block_from_checker(gosh_context, &checker_address).await?;
+    let first_block_number = read_block(web3s, BlockId::Hash(first_block_hash))
         .await?
         .number
         .ok_or(anyhow::format_err!(
@@ -179,14 +215,14 @@ pub async fn get_telemetry() -> anyhow::Result<()> {
         ))?;
 
     let block_id = BlockId::Number(BlockNumber::Finalized);
-    let last_block_number = read_block(&web3s, block_id)
+    let last_block_number = read_block(web3s, block_id)
         .await?
         .number
         .ok_or(anyhow::format_err!("Failed to read latest Eth block"))?;
 
     let eth_block_diff = last_block_number - first_block_number;
 
-    let counters = get_storage(&web3s, elock_address, last_block_number, COUNTERS_INDEX).await?;
+    let counters = get_storage(web3s, elock_address, last_block_number, COUNTERS_INDEX).await?;
     tracing::info!("ELock counters: {}", web3::helpers::to_string(&counters));
     let counters_str = web3::helpers::to_string(&counters)
         .replace('"', "")
@@ -195,22 +231,19 @@ pub async fn get_telemetry() -> anyhow::Result<()> {
     let rx_counter = U256::from_str_radix(&counters_str[0..32], 16)?;
     let tx_counter = U256::from_str_radix(&counters_str[32..64], 16)?;
 
-    let elock_total = elock::get_total_supplies(&web3s, &elock_contract).await?;
+    let elock_total = elock::get_total_supplies(web3s, elock_address).await?;
     let elock_total_supplies = Vec::from_iter(elock_total.into_iter())
         .into_iter().map(|(root, value)| RootValue{
             root,
             value
         }).collect();
 
-    let proposals: Vec<U256> = elock_contract
-        .query("getProposalList", (), None, Options::default(), None)
-        .await
-        .map_err(|e| anyhow::format_err!("Failed to call ELock getter getProposalList: {e}"))?;
+    let proposals = query_elock_list(web3s, elock_address, "getProposalList").await?;
 
     let elock_proposals_cnt = proposals.len();
 
     let proposal_addresses: AllProposals = call_getter(
-        &gosh_context,
+        gosh_context,
         &checker_address,
         CHECKER_ABI,
         "getAllProposalAddr",
@@ -223,7 +256,7 @@ pub async fn get_telemetry() -> anyhow::Result<()> {
     let mut glock_proposals = HashMap::new();
     for proposal_address in proposal_addresses.addresses {
         match call_getter::<ProposalDetails>(
-            &gosh_context,
+            gosh_context,
             &proposal_address,
             PROPOSAL_ABI,
             "getDetails",
@@ -254,20 +287,23 @@ pub async fn get_telemetry() -> anyhow::Result<()> {
     }
 
     let elock_balance = web3s
-        .eth()
         .balance(elock_address, Some(BlockNumber::Number(last_block_number)))
         .await?;
 
-    let validators: Vec<Address> = elock_contract
-        .query("getValidators", (), None, Options::default(), None)
-        .await
-        .map_err(|e| anyhow::format_err!("Failed to call ELock getter getProposalList: {e}"))?;
+    let validators = query_elock_list(web3s, elock_address, "getValidators")
+        .await?
+        .into_iter()
+        .map(|token| {
+            token
+                .into_address()
+                .ok_or(anyhow::format_err!("Wrong ELock validator address"))
+        })
+        .collect::<anyhow::Result<Vec<Address>>>()?;
 
     let mut validators_balances = HashMap::new();
     let mut min_validator_balance = u128::MAX;
     for validator in validators {
         let balance = web3s
-            .eth()
             .balance(validator, Some(BlockNumber::Number(last_block_number)))
             .await?
             .as_u128();
@@ -278,7 +314,7 @@ pub async fn get_telemetry() -> anyhow::Result<()> {
     }
 
     let elock_collected_commissions = get_storage(
-        &web3s,
+        web3s,
         elock_address,
         last_block_number,
         COLLECTED_COMMISSIONS_INDEX,
@@ -291,7 +327,7 @@ pub async fn get_telemetry() -> anyhow::Result<()> {
     let elock_collected_commissions =
         U256::from_str_radix(&elock_collected_commissions_str, 16)?.as_u128();
 
-    let current_eth_gas_price = web3s.eth().gas_price().await?.as_u128();
+    let current_eth_gas_price = web3s.gas_price().await?.as_u128();
 
     let current_approximate_elock_commissions = (ELOCK_WITHDRAWAL_COMMISSION * ((elock_proposals_cnt + 1) as u128) + // + 1  because usually there no proposals and one will definitely be created for withdrawal
         ELOCK_TRANSFER_COMMISSION * (queued_burns_cnt as u128))
@@ -305,27 +341,28 @@ pub async fn get_telemetry() -> anyhow::Result<()> {
 
 
     let all_token_roots = elock::get_token_roots(
-        &elock_contract
+        web3s,
+        elock_address,
     ).await?;
     let mut all_roots_commissions = vec![];
     let mut all_roots_supplies = vec![];
     for root in all_token_roots {
         let data = get_root_data(
-            &web3s,
+            web3s,
             root,
         ).await?;
         let address = get_root_address(
-            &gosh_context,
+            gosh_context,
             &checker_address,
             &data
         ).await?;
         let balance = get_root_owner_balance(
-            &gosh_context,
+            gosh_context,
             &address,
         ).await.unwrap_or(0);
         all_roots_commissions.push((data.clone(), balance));
         let total_supply = get_root_total_supply(
-            &gosh_context, &address
+            gosh_context, &address
         ).await.unwrap_or(0);
         all_roots_supplies.push((data, total_supply, address));
     }
@@ -350,7 +387,7 @@ pub async fn get_telemetry() -> anyhow::Result<()> {
     let queued_burns: Vec<BurnStatistic> = burns_map.values().cloned().collect();
 
     let glock_checker_balance = query_balance(
-        &gosh_context,
+        gosh_context,
         &checker_address
     ).await?;
 
@@ -395,6 +432,5 @@ pub async fn get_telemetry() -> anyhow::Result<()> {
         current_approximate_elock_commissions_per_person,
     };
 
-    println!("{}", serde_json::to_string_pretty(&json!(telemetry))?);
-    Ok(())
+    Ok(telemetry)
 }
@@ -0,0 +1,49 @@
+# Save ETH block headers as fixtures for the RLP encoder test.
+#
+# Usage: python3 tools/fetch_header_fixtures.py <RPC_URL> <network> [<fork>=<block_number> ...]
+# Without fork arguments the first blocks of the mainnet forks are fetched.
+
+import json
+import sys
+import urllib.request
+
+OUTPUT_DIR = 'common/fixtures/headers'
+
+MAINNET_FORKS = {
+    'london': 12965000,
+    'shanghai': 17034870,
+    'cancun': 19426587,
+    'prague': 22431084,
+}
+
+
+def get_block(url, number):
+    request = urllib.request.Request(
+        url,
+        data=json.dumps({
+            'jsonrpc': '2.0',
+            'id': 1,
+            'method': 'eth_getBlockByNumber',
+            'params': [hex(number), False],
+        }).encode(),
+        headers={'Content-Type': 'application/json'},
+    )
+    with urllib.request.urlopen(request) as response:
+        result = json.loads(response.read())
+    if result.get('result') is None:
+        raise Exception(f'Failed to get block {number}: {result}')
+    return result['result']
+
+
+url = sys.argv[1]
+network = sys.argv[2]
+forks = MAINNET_FORKS
+if len(sys.argv) > 3:
+    forks = {fork: int(number) for fork, number in (arg.split('=') for arg in sys.argv[3:])}
+
+for fork, number in forks.items():
+    block = get_block(url, number)
+    path = f'{OUTPUT_DIR}/{network}-{fork}-{number}.json'
+    with open(path, 'w') as fixture:
+        fixture.write(json.dumps(block, indent=2) + '\n')
+    print(f'Saved {path}')
@@ -1,37 +1,41 @@
-use crate::withdraw::burn::find_all_burns;
-use crate::withdraw::validator::{check_proposals_and_accept, create_new_proposal};
+use common::config::BridgeConfig;
 use common::eth::events::get_all_events;
-use common::helper::get_last_blocks;
 use common::helper::tracing::init_default_tracing;
+use common::helper::{get_last_blocks, take_dry_run_flag};
 use std::env;
-
-mod withdraw;
+use std::process::ExitCode;
+use withdraw_proposal_checker::withdraw::burn::find_all_burns;
+use withdraw_proposal_checker::withdraw::validator::{
+    check_proposals_and_accept, create_new_proposal,
+};
 
 #[tokio::main]
-async fn main() -> anyhow::Result<()> {
+async fn main() -> anyhow::Result<ExitCode> {
     dotenv::dotenv().ok();
     init_default_tracing();
+    let mut config = BridgeConfig::load()?;
 
-    let args: Vec<String> = env::args().collect();
+    let mut args: Vec<String> = env::args().collect();
+    take_dry_run_flag(&mut config, &mut args);
 
     match args.len() {
         2 => {
             if args[1] == "get_last_blocks" {
                 tracing::info!("Get last blocks");
-                get_last_blocks().await
+                get_last_blocks(&config).await.map(|_| ExitCode::SUCCESS)
             } else if args[1] == "create" {
                 tracing::info!("Create proposal");
-                create_new_proposal().await
+                create_new_proposal(&config).await?.report()
             } else if args[1] == "find_burns" {
                 tracing::info!("Find burns");
-                find_all_burns().await
+                find_all_burns(&config).await.map(|_| ExitCode::SUCCESS)
             } else if args[1] == "events" {
                 tracing::info!("Find ELock events");
-                get_all_events().await
+                get_all_events(&config).await.map(|_| ExitCode::SUCCESS)
             } else {
                 anyhow::bail!("Unknown subcommand");
             }
         }
-        _ => check_proposals_and_accept().await,
+        _ => check_proposals_and_accept(&config).await?.report(),
     }
 }
@@ -1,21 +1,27 @@
-use common::elock::{get_elock_address, get_last_gosh_block_id};
-use common::eth::create_web3_socket;
+use common::config::BridgeConfig;
+use common::elock::get_last_gosh_block_id;
+use common::eth::create_eth_client;
 use common::gosh::block::{get_latest_master_block, get_master_block_seq_no};
-use common::gosh::burn::find_burns;
+use common::gosh::burn::find_burns_cached;
 use common::gosh::helper::create_client;
+use common::retry::Retry;
+use common::store::Store;
 
 use serde_json::json;
 
-pub async fn find_all_burns() -> anyhow::Result<()> {
+pub async fn find_all_burns(config: &BridgeConfig) -> anyhow::Result<()> {
     tracing::info!("Find all burns");
     // Create client for GOSH
-    let context = create_client()?;
+    let context = Retry::new(create_client(&config.gosh)?, &config.retry);
 
     // Create client for ETH
-    let web3s = create_web3_socket().await?;
+    let web3s = create_eth_client(&config.eth, &config.retry).await?;
 
     // Load ELock address
-    let elock_address = get_elock_address()?;
+    let elock_address = config.eth.elock_address;
+
+    // Open local store with burns found before
+    let store = Store::from_config(&config.store)?;
 
     // Get saved block from ELock
     let first_block = get_last_gosh_block_id(elock_address, &web3s).await?;
@@ -35,7 +41,14 @@ pub async fn find_all_burns() -> anyhow::Result<()> {
     );
 
     // Find burns for the specified period of blocks
-    let burns = find_burns(&context, first_seq_no, current_master_block.seq_no).await?;
+    let burns = find_burns_cached(
+        &context,
+        &store,
+        &config.gosh.checker_address,
+        first_seq_no,
+        current_master_block.seq_no,
+    )
+    .await?;
     tracing::info!("burns: {burns:?}");
 
     // Count total burns number and common value
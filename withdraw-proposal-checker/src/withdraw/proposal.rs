@@ -1,16 +1,17 @@
+use common::chain::{EthChain, GoshChain};
+use common::config::BridgeConfig;
 use common::elock::get_last_gosh_block_id;
 use common::eth;
+use common::eth::signer::EthSigner;
 use common::gosh::block::{get_latest_master_block, get_master_block_seq_no};
-use common::gosh::burn::{find_burns, Burn};
-use common::gosh::helper::EverClient;
+use common::gosh::burn::{find_burns_cached, Burn};
+use common::helper::abi::ELOCK_ABI;
+use common::outcome::Outcome;
+use common::store::{ProposalKind, ProposalRecord, Store};
 use ethereum_types::BigEndianHash;
 use std::str::FromStr;
-use web3::contract::{Contract, Options};
 use web3::ethabi::Token;
-use web3::signing::SecretKey;
-use web3::transports::WebSocket;
 use web3::types::{Address, H256, U256};
-use web3::Web3;
 
 #[derive(Debug)]
 pub struct ProposalData {
@@ -21,23 +22,33 @@ pub struct ProposalData {
 }
 
 pub async fn vote_for_withdrawal(
+    config: &BridgeConfig,
     prop_key: U256,
-    elock_contract: &Contract<WebSocket>,
-    key: &SecretKey,
-) -> anyhow::Result<()> {
+    web3s: &dyn EthChain,
+    signer: &EthSigner,
+) -> anyhow::Result<H256> {
     let prop_str = web3::helpers::to_string(&H256::from_uint(&prop_key));
     tracing::info!("Vote for proposal: {prop_str}");
 
-    eth::call_function(elock_contract, key, "voteForWithdrawal", prop_key).await
+    eth::call_function(
+        web3s,
+        config.eth.elock_address,
+        signer,
+        "voteForWithdrawal",
+        vec![Token::Uint(prop_key)],
+        config.eth.confirmations,
+    )
+    .await
 }
 
 pub async fn create_proposal(
-    context: &EverClient,
-    web3s: &Web3<WebSocket>,
-    elock_address: Address,
-    elock_contract: &Contract<WebSocket>,
-    key: &SecretKey,
-) -> anyhow::Result<()> {
+    config: &BridgeConfig,
+    context: &dyn GoshChain,
+    web3s: &dyn EthChain,
+    store: &Store,
+    signer: &EthSigner,
+) -> anyhow::Result<Outcome> {
+    let elock_address = config.eth.elock_address;
     // Read last saved block hash from ELock
     let first_block = get_last_gosh_block_id(elock_address, web3s)
         .await
@@ -53,18 +64,27 @@ pub async fn create_proposal(
         .map_err(|e| anyhow::format_err!("Failed to get latest GOSH block: {e}"))?;
 
     // Find all burns for the specified period between blocks
-    let burns = find_burns(context, first_seq_no, current_master_block.seq_no).await?;
+    let burns = find_burns_cached(
+        context,
+        store,
+        &config.gosh.checker_address,
+        first_seq_no,
+        current_master_block.seq_no,
+    )
+    .await?;
     tracing::info!("burns: {burns:?}");
 
     if burns.is_empty() {
         tracing::info!("There were no burns, do not create proposal");
-        return Ok(());
+        return Ok(Outcome::NothingToDo);
     }
 
     // Convert arguments for ETH contract call
+    let burns_cnt = burns.len();
     let burns =
         convert_burns(burns).map_err(|e| anyhow::format_err!("Failed to convert burns: {e}"))?;
 
+    let first_block_id = first_block.clone();
     let first_block = Token::Uint(
         U256::from_str(&first_block)
             .map_err(|e| anyhow::format_err!("Failed to convert first block to U256: {e}"))?,
@@ -77,40 +97,73 @@ pub async fn create_proposal(
     // Create proposal in ELock
     tracing::info!("Start call of proposeWithdrawal");
     tracing::info!("{first_block} {last_block} {burns:?}");
-    eth::call_function(
-        elock_contract,
-        key,
+    let tx_hash = eth::call_function(
+        web3s,
+        elock_address,
+        signer,
         "proposeWithdrawal",
-        (first_block, last_block, burns),
+        vec![first_block, last_block, Token::Array(burns)],
+        config.eth.confirmations,
     )
-    .await
+    .await?;
+
+    let proposal = ProposalRecord {
+        kind: ProposalKind::Withdrawal,
+        from_block: first_block_id,
+        till_block: current_master_block.block_id,
+        transfers: burns_cnt,
+        tx_id: format!("{tx_hash:?}"),
+    };
+    store.save_proposal(&proposal)?;
+    Ok(Outcome::Proposed {
+        from: proposal.from_block,
+        till: proposal.till_block,
+        transfers: proposal.transfers,
+    })
 }
 
 pub async fn get_proposals(
-    elock_contract: &Contract<WebSocket>,
+    web3s: &dyn EthChain,
+    elock_address: Address,
 ) -> anyhow::Result<Vec<ProposalData>> {
     // Call ELock getter
-    let proposals: Vec<U256> = elock_contract
-        .query("getProposalList", (), None, Options::default(), None)
+    let proposals: Vec<U256> = web3s
+        .query(elock_address, ELOCK_ABI, "getProposalList", vec![])
         .await
-        .map_err(|e| anyhow::format_err!("Failed to call ELock getter getProposalList: {e}"))?;
+        .map_err(|e| anyhow::format_err!("Failed to call ELock getter getProposalList: {e}"))?
+        .into_iter()
+        .next()
+        .and_then(|token| token.into_array())
+        .ok_or(anyhow::format_err!("Wrong ELock getProposalList output"))?
+        .into_iter()
+        .map(|token| {
+            token
+                .into_uint()
+                .ok_or(anyhow::format_err!("Wrong ELock proposal key"))
+        })
+        .collect::<anyhow::Result<_>>()?;
 
     tracing::info!("getProposalList: {proposals:?}");
 
     // Load proposals data
     let mut res = vec![];
     for proposal in proposals {
-        let proposals_data: (U256, U256, Vec<Token>) = elock_contract
+        let proposals_data = web3s
             .query(
+                elock_address,
+                ELOCK_ABI,
                 "getProposal",
-                Token::Uint(proposal),
-                None,
-                Options::default(),
-                None,
+                vec![Token::Uint(proposal)],
             )
             .await
             .map_err(|e| anyhow::format_err!("Failed to call ELock getter getProposal: {e}"))?;
         tracing::info!("{proposals_data:?}");
+        let proposals_data = match proposals_data.as_slice() {
+            [Token::Uint(from), Token::Uint(till), Token::Array(transfers)] => {
+                (*from, *till, transfers.clone())
+            }
+            _ => anyhow::bail!("Wrong ELock getProposal output: {proposals_data:?}"),
+        };
 
         // Decode getter result
         let transfers = proposals_data
@@ -153,7 +206,12 @@ pub async fn get_proposals(
     Ok(res)
 }
 
-pub async fn check_proposal(context: &EverClient, proposal: &ProposalData) -> anyhow::Result<()> {
+pub async fn check_proposal(
+    context: &dyn GoshChain,
+    store: &Store,
+    checker_address: &str,
+    proposal: &ProposalData,
+) -> anyhow::Result<()> {
     tracing::info!("check proposal: {}", proposal.proposal_key);
     let start_seq_no = get_master_block_seq_no(context, &proposal.from)
         .await
@@ -167,7 +225,8 @@ pub async fn check_proposal(context: &EverClient, proposal: &ProposalData) -> an
         anyhow::bail!("Proposal start block seq_no is greater than end's");
     }
 
-    let burns = find_burns(context, start_seq_no, end_seq_no).await?;
+    let burns =
+        find_burns_cached(context, store, checker_address, start_seq_no, end_seq_no).await?;
     tracing::info!("Found burns: {burns:?}");
     if proposal.transfers != burns {
         anyhow::bail!("list of burns in proposal is not equal to the actual one");
@@ -191,3 +250,72 @@ fn convert_burns(burns: Vec<Burn>) -> anyhow::Result<Vec<Token>> {
     }
     Ok(res)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{check_proposal, ProposalData};
+    use common::chain::fake::FakeGosh;
+    use common::gosh::burn::Burn;
+    use common::store::Store;
+    use serde_json::json;
+    use web3::types::U256;
+
+    const CHECKER_ADDRESS: &str = "0:01";
+    const RECEIVER_ADDRESS: &str = "0:02";
+
+    fn setup() -> FakeGosh {
+        let context = FakeGosh::new();
+        context.set_getter(
+            CHECKER_ADDRESS,
+            "getStatus",
+            None,
+            json!({"prevhash": "0x00", "index": "0", "receiver": RECEIVER_ADDRESS}),
+        );
+        for (block_id, seq_no) in [("aa", 10), ("bb", 20)] {
+            context.set_query(
+                "block(",
+                Some(json!({ "block_id": block_id })),
+                json!({"data": {"blockchain": {"block": {"seq_no": seq_no, "workchain_id": -1}}}}),
+            );
+        }
+        context.set_query(
+            "transactions(",
+            Some(json!({ "addr": RECEIVER_ADDRESS })),
+            json!({"data": {"blockchain": {"account": {"transactions": {
+                "edges": [],
+                "pageInfo": {"hasNextPage": false, "endCursor": ""}
+            }}}}}),
+        );
+        context
+    }
+
+    fn proposal(transfers: Vec<Burn>) -> ProposalData {
+        ProposalData {
+            proposal_key: U256::one(),
+            from: "aa".to_string(),
+            till: "bb".to_string(),
+            transfers,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_check_proposal_without_burns() -> anyhow::Result<()> {
+        let context = setup();
+        let store = Store::in_memory()?;
+        check_proposal(&context, &store, CHECKER_ADDRESS, &proposal(vec![])).await
+    }
+
+    #[tokio::test]
+    async fn test_check_proposal_with_unknown_burn() {
+        let context = setup();
+        let burn = Burn {
+            dest: "0x0000000000000000000000000000000000000001".to_string(),
+            value: 1,
+            tx_id: "01".to_string(),
+            eth_root: "0x0000000000000000000000000000000000000000".to_string(),
+        };
+        let store = Store::in_memory().unwrap();
+        let res = check_proposal(&context, &store, CHECKER_ADDRESS, &proposal(vec![burn])).await;
+        assert!(res.is_err());
+    }
+}
@@ -1,57 +1,45 @@
 use crate::withdraw::proposal::{
     check_proposal, create_proposal, get_proposals, vote_for_withdrawal,
 };
-use common::elock::get_elock_address;
-use common::eth::create_web3_socket;
+use common::chain::EthChain;
+use common::config::BridgeConfig;
+use common::dry_run::DryRun;
+use common::eth::create_eth_client;
+use common::eth::signer::load_signer;
 use common::gosh::helper::create_client;
-use common::helper::abi::ELOCK_ABI;
+use common::outcome::Outcome;
+use common::retry::Retry;
+use common::store::{ProposalKind, Store};
 
 use ethereum_types::BigEndianHash;
 use sha3::{Digest, Keccak256};
-use std::env;
 use std::str::FromStr;
-use web3::contract::Contract;
-use web3::signing::SecretKey;
-use web3::transports::WebSocket;
 use web3::types::{Address, H256, U256};
-use web3::Web3;
 
 const VOTE_FOR_PROPOSAL_STORAGE_ID: &str =
     "000000000000000000000000000000000000000000000000000000000000000D";
 
-fn get_secret() -> anyhow::Result<SecretKey> {
-    let key_path = env::var("ETH_PRIVATE_KEY_PATH")
-        .map_err(|e| anyhow::format_err!("Failed to get env ETH_PRIVATE_KEY_PATH: {e}"))?;
-    SecretKey::from_str(
-        std::fs::read_to_string(key_path)
-            .map_err(|e| anyhow::format_err!("Failed to read ETH_PRIVATE_KEY_PATH: {e}"))?
-            .trim(),
-    )
-    .map_err(|e| anyhow::format_err!("Failed to load private key: {e}"))
-}
-
-pub async fn create_new_proposal() -> anyhow::Result<()> {
+pub async fn create_new_proposal(config: &BridgeConfig) -> anyhow::Result<Outcome> {
     // Create client for GOSH
-    let context = create_client()?;
+    let context = Retry::new(create_client(&config.gosh)?, &config.retry);
 
     // Create client for ETH
-    let web3s = create_web3_socket().await?;
+    let web3s = create_eth_client(&config.eth, &config.retry).await?;
 
-    // Load ELock contract
-    let elock_address = get_elock_address()?;
-    let elock_abi = web3::ethabi::Contract::load(ELOCK_ABI.as_bytes())
-        .map_err(|e| anyhow::format_err!("Failed to load elock abi: {e}"))?;
-    let elock_contract = Contract::new(web3s.eth(), elock_address, elock_abi);
+    // Load validator ETH key or connect to the remote signer holding it
+    let signer = load_signer(&config.eth).await?;
 
-    // Load validator ETH key
-    let key = get_secret()?;
+    // Open local store with burns found before
+    let store = Store::from_config(&config.store)?;
 
-    create_proposal(&context, &web3s, elock_address, &elock_contract, &key).await?;
-    Ok(())
+    // With `--dry-run` the proposal is printed instead of being sent
+    let web3s = DryRun::new(&web3s, config.dry_run);
+
+    create_proposal(config, &context, &web3s, &store, &signer).await
 }
 
 async fn has_voted(
-    web3s: &Web3<WebSocket>,
+    web3s: &dyn EthChain,
     elock_address: Address,
     proposal_key: &H256,
     validator_address: &H256,
@@ -72,49 +60,57 @@ async fn has_voted(
     let storage_key = hasher.finalize();
     let idx = U256::from_big_endian(storage_key.as_ref());
 
-    let res = web3s.eth().storage(elock_address, idx, None).await?;
+    let res = web3s.storage(elock_address, idx, None).await?;
     tracing::info!("Check validators vote for proposal result: {res}");
 
     Ok(!res.is_zero())
 }
 
-pub async fn check_proposals_and_accept() -> anyhow::Result<()> {
+pub async fn check_proposals_and_accept(config: &BridgeConfig) -> anyhow::Result<Outcome> {
     // Create client for GOSH
-    let context = create_client()?;
+    let context = Retry::new(create_client(&config.gosh)?, &config.retry);
 
     // Create client for ETH
-    let web3s = create_web3_socket().await?;
+    let web3s = create_eth_client(&config.eth, &config.retry).await?;
 
     // Load ELock contract
-    let elock_address_str = env::var("ETH_CONTRACT_ADDRESS")
-        .map_err(|e| anyhow::format_err!("Failed to get env ETH_CONTRACT_ADDRESS: {e}"))?;
-    tracing::info!("elock address: {elock_address_str}");
-    let elock_abi = web3::ethabi::Contract::load(ELOCK_ABI.as_bytes())
-        .map_err(|e| anyhow::format_err!("Failed to load elock abi: {e}"))?;
-    let elock_address = Address::from_str(&elock_address_str)
-        .map_err(|e| anyhow::format_err!("Failed to convert ETH address: {e}"))?;
-    let elock_contract = Contract::new(web3s.eth(), elock_address, elock_abi);
-
-    // Load validator's ETH key
-    let key = get_secret()?;
+    let elock_address = config.eth.elock_address;
+    tracing::info!("elock address: {elock_address:?}");
+
+    // Load validator's ETH key or connect to the remote signer holding it
+    let signer = load_signer(&config.eth).await?;
 
     // Load Validators wallet address
-    let validator_address_str = env::var("ETH_VALIDATOR_CONTRACT_ADDRESS").map_err(|e| {
-        anyhow::format_err!("Failed to get env ETH_VALIDATOR_CONTRACT_ADDRESS: {e}")
-    })?;
+    let validator_address = config.eth.validator_address()?;
 
     // Format address
-    let validator_address = Address::from_str(&validator_address_str)
-        .map_err(|e| anyhow::format_err!("Failed to convert ETH address: {e}"))?;
     let validator_address_bytes = validator_address.to_fixed_bytes();
     let mut validator_address_padded = [0_u8; 32];
     validator_address_padded[12..].copy_from_slice(&validator_address_bytes[..]);
     let validator_address = H256::from(validator_address_padded);
     tracing::info!("validator_address: {validator_address:?}");
+    let voter = format!("{validator_address:?}");
+
+    // Open local store with votes sent before
+    let store = Store::from_config(&config.store)?;
+
+    // With `--dry-run` votes are printed instead of being sent
+    let sender = DryRun::new(&web3s, config.dry_run);
 
     // Get list of proposals from ELock
-    let current_proposals = get_proposals(&elock_contract).await?;
+    let current_proposals = get_proposals(&web3s, elock_address).await?;
+    if current_proposals.is_empty() {
+        return Ok(Outcome::NothingToDo);
+    }
+
+    let mut voted = vec![];
+    let mut failures = vec![];
     for proposal in current_proposals {
+        let proposal_key = format!("{:?}", H256::from_uint(&proposal.proposal_key));
+        if let Some(tx_id) = store.vote(ProposalKind::Withdrawal, &proposal_key, &voter)? {
+            tracing::info!("Already voted for proposal {proposal_key}: {tx_id}");
+            continue;
+        }
         match has_voted(
             &web3s,
             elock_address,
@@ -131,17 +127,27 @@ pub async fn check_proposals_and_accept() -> anyhow::Result<()> {
             }
             Err(e) => {
                 tracing::info!("Failed to check vote: {e}");
+                failures.push(format!("{proposal_key}: {e}"));
                 continue;
             }
         };
-        match check_proposal(&context, &proposal).await {
+        match check_proposal(&context, &store, &config.gosh.checker_address, &proposal).await {
             Ok(()) => {
-                vote_for_withdrawal(proposal.proposal_key, &elock_contract, &key).await?;
+                let tx_hash =
+                    vote_for_withdrawal(config, proposal.proposal_key, &sender, &signer).await?;
+                store.save_vote(
+                    ProposalKind::Withdrawal,
+                    &proposal_key,
+                    &voter,
+                    &format!("{tx_hash:?}"),
+                )?;
+                voted.push(proposal_key);
             }
             Err(e) => {
                 tracing::info!("Proposal check failed for: {proposal:?} {e}");
+                failures.push(format!("{proposal_key}: {e}"));
             }
         }
     }
-    Ok(())
+    Ok(Outcome::from_votes(voted, failures))
 }